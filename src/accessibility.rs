@@ -0,0 +1,34 @@
+use egui::{Stroke, Vec2};
+
+/// Applies or removes the accessibility style: larger hit targets and higher contrast widgets
+pub fn apply_style(ctx: &egui::Context, enabled: bool) {
+    let is_dark_mode = ctx.style().visuals.dark_mode;
+    let mut visuals = if is_dark_mode { egui::Visuals::dark() } else { egui::Visuals::light() };
+    let mut spacing = egui::Style::default().spacing;
+
+    if enabled {
+        spacing.interact_size = Vec2::new(48.0, 32.0);
+        spacing.button_padding = Vec2::new(10.0, 6.0);
+        spacing.item_spacing = Vec2::new(10.0, 8.0);
+        spacing.icon_width = 20.0;
+
+        let text_color = if is_dark_mode { egui::Color32::WHITE } else { egui::Color32::BLACK };
+        visuals.override_text_color = Some(text_color);
+        for widget in [
+            &mut visuals.widgets.noninteractive,
+            &mut visuals.widgets.inactive,
+            &mut visuals.widgets.hovered,
+            &mut visuals.widgets.active,
+            &mut visuals.widgets.open,
+        ] {
+            widget.bg_stroke = Stroke::new(widget.bg_stroke.width.max(1.5), text_color);
+            widget.fg_stroke = Stroke::new(widget.fg_stroke.width.max(1.5), text_color);
+        }
+        visuals.selection.stroke.width = 2.0;
+    }
+
+    ctx.style_mut(|style| {
+        style.spacing = spacing;
+        style.visuals = visuals;
+    });
+}
@@ -1,5 +1,5 @@
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{HashMap, HashSet, VecDeque},
     env,
     error::Error,
     path::{Path, PathBuf},
@@ -32,14 +32,52 @@ use wows_replays::{analyzer::battle_controller::GameMessage, ReplayFile};
 use wowsunpack::data::idx::FileNode;
 
 use crate::{
+    accessibility,
+    app_lock::LockState,
+    autostart,
+    clan::ClanSettings,
+    collections::{CollectionEditor, ReplayCollection},
+    command_palette::{CommandPalette, PaletteAction, PaletteTarget},
+    comparison::ComparisonWindow,
+    connectivity::Connectivity,
+    contribution::ContributionWeights,
+    crash_report,
+    diagnostics::Diagnostics,
     error::ToolkitError,
+    event_bus::{BattleEvent, EventBus, EventBusSettings},
+    expected_values::ExpectedValues,
     file_unpacker::{UnpackerProgress, UNPACKER_STOP},
+    game_launcher::GameLauncher,
     game_params::game_params_bin_path,
+    health::HealthChecks,
     icons,
+    integrity::IntegrityWindow,
+    lineups::{Lineup, LineupWindow},
+    linked_video::{LinkedVideo, LinkedVideoEditor},
+    map_stats::MapStatsWindow,
+    map_veto::MapVeto,
+    matchmaking::MatchmakingWindow,
+    notifications::{NotificationAction, NotificationCenter},
+    palette::TeamPalette,
+    parse_diagnostics::ParseDiagnosticsWindow,
     plaintext_viewer::PlaintextFileViewer,
+    player_extraction::PlayerExtractionWindow,
     player_tracker::PlayerTracker,
-    replay_parser::{Replay, SharedReplayParserTabState},
+    protocol_handler::{self, DeepLink},
+    region::Region,
+    replay_cleanup::{CleanupRules, ReplayCleanupWindow},
+    replay_parser::{Replay, ReplayListing, SharedReplayParserTabState},
+    replay_titles::{ReplayTitle, ReplayTitleEditor},
+    scheduler::ScheduledJobs,
+    scouting::ScoutingWindow,
+    setup_wizard::SetupWizard,
+    storage::{self, StorageEntry},
     task::{self, BackgroundTask, BackgroundTaskCompletion, BackgroundTaskKind},
+    tournament::Tournament,
+    tray::{self, Tray, TrayAction},
+    undo::UndoStack,
+    usage_stats::{self, UsageStatsSettings},
+    util,
     wows_data::WorldOfWarshipsData,
 };
 
@@ -47,27 +85,52 @@ use crate::{
 macro_rules! update_background_task {
     ($saved_task:expr, $background_task:expr) => {
         let task = $background_task;
+        if let Some(task) = task.as_ref() {
+            $crate::usage_stats::record_feature(task.kind.name());
+        }
         if task.is_some() {
             $saved_task = task;
         }
     };
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub enum Tab {
     Unpacker,
     ReplayParser,
     Settings,
     PlayerTracker,
+    Tournament,
+    MapVeto,
 }
 
 impl Tab {
-    fn title(&self) -> String {
+    /// Every tab, in the order they're shown
+    pub fn all() -> Vec<Tab> {
+        vec![Tab::ReplayParser, Tab::PlayerTracker, Tab::Tournament, Tab::MapVeto, Tab::Unpacker, Tab::Settings]
+    }
+
+    pub fn title(&self) -> String {
         match self {
             Tab::Unpacker => format!("{} Resource Unpacker", icons::ARCHIVE),
             Tab::Settings => format!("{} Settings", icons::GEAR_FINE),
             Tab::ReplayParser => format!("{} Replay Inspector", icons::MAGNIFYING_GLASS),
             Tab::PlayerTracker => format!("{} Player Tracker", icons::DETECTIVE),
+            Tab::Tournament => format!("{} Tournament", icons::TROPHY),
+            Tab::MapVeto => format!("{} Map Veto", icons::MAP_TRIFOLD),
+        }
+    }
+
+    /// Tab names accepted by the `tab` parameter of `wows-toolkit://` links
+    fn from_link_name(name: &str) -> Option<Tab> {
+        match name {
+            "unpacker" => Some(Tab::Unpacker),
+            "settings" => Some(Tab::Settings),
+            "replays" | "replay_inspector" => Some(Tab::ReplayParser),
+            "player_tracker" => Some(Tab::PlayerTracker),
+            "tournament" => Some(Tab::Tournament),
+            "map_veto" => Some(Tab::MapVeto),
+            _ => None,
         }
     }
 }
@@ -77,8 +140,115 @@ pub struct ToolkitTabViewer<'a> {
 }
 
 impl ToolkitTabViewer<'_> {
+    fn build_game_locale_selection(&mut self, ui: &mut egui::Ui) {
+        let available_locales = self
+            .tab_state
+            .world_of_warships_data
+            .as_ref()
+            .map(|wows_data| wows_data.read().available_locales.clone())
+            .unwrap_or_default();
+
+        let default_locale_text = match self.tab_state.settings.region {
+            Some(region) => format!("Region Default ({})", region.default_locale()),
+            None => "Match Application Language".to_string(),
+        };
+
+        ui.horizontal(|ui| {
+            ui.label("Region");
+            let mut changed = false;
+            let region = &mut self.tab_state.settings.region;
+            egui::ComboBox::from_id_salt("region_selection")
+                .selected_text(region.map(|region| region.name()).unwrap_or("Not Set"))
+                .show_ui(ui, |ui| {
+                    changed |= ui.selectable_value(region, None, "Not Set").changed();
+                    for choice in Region::ALL {
+                        changed |= ui.selectable_value(region, Some(choice), choice.name()).changed();
+                    }
+                })
+                .response
+                .on_hover_text("Used for player and clan links, online lookups, and the default game data language");
+
+            // The region may change which language game data is loaded in
+            if changed && self.tab_state.settings.game_locale.is_none() && self.tab_state.can_change_wows_dir && !self.tab_state.settings.wows_dir.is_empty() {
+                let wows_dir = PathBuf::from(&self.tab_state.settings.wows_dir);
+                self.tab_state.prevent_changing_wows_dir();
+                crate::update_background_task!(self.tab_state.background_task, Some(self.tab_state.load_game_data(wows_dir)));
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Game Data Language");
+            let mut changed = false;
+            ui.add_enabled_ui(self.tab_state.can_change_wows_dir && !available_locales.is_empty(), |ui| {
+                let game_locale = &mut self.tab_state.settings.game_locale;
+                egui::ComboBox::from_id_salt("game_locale_selection")
+                    .selected_text(game_locale.clone().unwrap_or_else(|| default_locale_text.clone()))
+                    .show_ui(ui, |ui| {
+                        changed |= ui.selectable_value(game_locale, None, default_locale_text.as_str()).changed();
+                        for locale in &available_locales {
+                            changed |= ui.selectable_value(game_locale, Some(locale.clone()), locale.as_str()).changed();
+                        }
+                    });
+            })
+            .response
+            .on_hover_text("Language used for ship names, maps, and other text from the game's files");
+
+            // Translations are loaded alongside the rest of the game data
+            if changed && !self.tab_state.settings.wows_dir.is_empty() {
+                let wows_dir = PathBuf::from(&self.tab_state.settings.wows_dir);
+                self.tab_state.prevent_changing_wows_dir();
+                crate::update_background_task!(self.tab_state.background_task, Some(self.tab_state.load_game_data(wows_dir)));
+            }
+        });
+    }
+
+    fn build_clan_settings(&mut self, ui: &mut egui::Ui) {
+        let clan = &mut self.tab_state.settings.clan;
+        ui.horizontal(|ui| {
+            ui.label("Clan Tag");
+            ui.add(egui::TextEdit::singleline(&mut clan.tag).desired_width(80.0))
+                .on_hover_text("Players with this tag are highlighted as clanmates until a roster is synced");
+        });
+        ui.horizontal(|ui| {
+            ui.label("WG Application ID");
+            ui.add(egui::TextEdit::singleline(&mut clan.application_id).password(true).hint_text("Optional"))
+                .on_hover_text("Create one at developers.wargaming.net to sync your clan's roster");
+        });
+
+        let region = self.tab_state.settings.region;
+        ui.horizontal(|ui| {
+            let can_sync = region.is_some() && !clan.tag.trim().is_empty() && !clan.application_id.trim().is_empty();
+            let is_offline = self.tab_state.connectivity.is_offline();
+            let sync_button = ui
+                .add_enabled(can_sync && !is_offline, egui::Button::new(format!("{} Sync Roster", icons::ARROWS_CLOCKWISE)))
+                .on_disabled_hover_text(if is_offline {
+                    "Unavailable while offline"
+                } else {
+                    "Requires a region, clan tag, and application ID"
+                });
+            if sync_button.clicked() {
+                if let Some(region) = region {
+                    let task = task::start_syncing_clan_roster(region, clan.application_id.trim().to_string(), clan.tag.trim().to_string());
+                    crate::update_background_task!(self.tab_state.background_task, Some(task));
+                }
+            }
+
+            if let Some(last_synced) = clan.last_synced {
+                ui.label(format!("{} members, synced {}", clan.members.len(), last_synced.format("%Y-%m-%d %H:%M")));
+                if ui.button("Forget Roster").clicked() {
+                    clan.members.clear();
+                    clan.last_synced = None;
+                }
+            }
+        });
+    }
+
     fn build_settings_tab(&mut self, ui: &mut egui::Ui) {
         ui.vertical(|ui| {
+            if self.tab_state.is_locked() {
+                self.build_unlock_prompt(ui);
+                ui.disable();
+            }
             ui.label("Application Settings");
             ui.group(|ui| {
                 ui.checkbox(&mut self.tab_state.settings.check_for_updates, "Check for Updates on Startup");
@@ -91,6 +261,59 @@ impl ToolkitTabViewer<'_> {
                 {
                     self.tab_state.should_send_replays.store(self.tab_state.settings.send_replay_data, Ordering::Relaxed);
                 }
+                ui.checkbox(&mut self.tab_state.settings.performance_mode, "Performance Mode")
+                    .on_hover_text("Disables animations and loading spinners to reduce CPU and GPU usage on older hardware");
+                ui.checkbox(&mut self.tab_state.settings.accessibility_mode, "Accessibility Mode")
+                    .on_hover_text("Larger buttons and higher contrast text. Replays in the listing can be opened by focusing them with Tab and pressing Enter.");
+                #[cfg(target_os = "windows")]
+                {
+                    ui.checkbox(&mut self.tab_state.settings.minimize_to_tray, "Minimize to System Tray").on_hover_text(
+                        "Closing or minimizing the window hides it to the tray, where new replays keep being picked up and battle events sent. Quit from the tray icon's menu.",
+                    );
+                    let settings = &mut self.tab_state.settings;
+                    if ui
+                        .checkbox(&mut settings.start_with_windows, "Start With Windows")
+                        .on_hover_text("Starts the app when you log in, so replays are picked up as soon as the game is launched")
+                        .changed()
+                    {
+                        if let Err(e) = autostart::set_enabled(settings.start_with_windows) {
+                            settings.start_with_windows = !settings.start_with_windows;
+                            *self.tab_state.timed_message.write() =
+                                Some(TimedMessage::new(format!("{} Failed to change whether the app starts with Windows: {}", icons::WARNING, e)));
+                        }
+                    }
+                    ui.add_enabled(
+                        settings.start_with_windows && settings.minimize_to_tray,
+                        egui::Checkbox::new(&mut settings.start_minimized, "Start Minimized to Tray"),
+                    )
+                    .on_hover_text("When started at login, the window is hidden to the tray instead of opening")
+                    .on_disabled_hover_text("Needs Start With Windows and Minimize to System Tray");
+                }
+                ui.horizontal(|ui| {
+                    let label = ui.label("Crash Report Endpoint");
+                    ui.add(egui::TextEdit::singleline(&mut self.tab_state.settings.crash_report_endpoint).hint_text("Optional"))
+                        .labelled_by(label.id)
+                        .on_hover_text("After a crash you'll be offered to send the crash log here. Leave empty to only offer a GitHub issue.");
+                });
+                #[cfg(target_os = "windows")]
+                {
+                    if ui
+                        .button(format!("{} Open wows-toolkit:// Links With This App", icons::LINK))
+                        .on_hover_text("Lets links from Discord or your browser open replays and tabs in WoWs Toolkit")
+                        .clicked()
+                    {
+                        let message = match protocol_handler::register() {
+                            Ok(()) => format!("{} Registered wows-toolkit:// links", icons::CHECK_CIRCLE),
+                            Err(e) => format!("{} Failed to register wows-toolkit:// links: {}", icons::WARNING, e),
+                        };
+                        *self.tab_state.timed_message.write() = Some(TimedMessage::new(message));
+                    }
+                }
+            });
+            ui.label("Tabs");
+            ui.group(|ui| {
+                let toggled = build_tab_checkboxes(ui, &mut self.tab_state.settings.hidden_tabs);
+                self.tab_state.toggled_tabs.extend(toggled);
             });
             ui.label("World of Warships Settings");
             ui.group(|ui| {
@@ -132,6 +355,18 @@ impl ToolkitTabViewer<'_> {
                             });
                         });
                     });
+                    self.build_game_locale_selection(ui);
+                    ui.checkbox(&mut self.tab_state.settings.reload_on_game_update, "Reload Game Data When the Game Updates")
+                        .on_hover_text("Keeps ship and player data current after a patch without restarting the app");
+                    ui.horizontal(|ui| {
+                        ui.label("Launch Game Via");
+                        let launcher = &mut self.tab_state.settings.game_launcher;
+                        egui::ComboBox::from_id_salt("game_launcher_selection").selected_text(launcher.name()).show_ui(ui, |ui| {
+                            for choice in GameLauncher::ALL {
+                                ui.selectable_value(launcher, choice, choice.name());
+                            }
+                        });
+                    });
                 })
             });
             ui.label("Replay Settings");
@@ -139,8 +374,58 @@ impl ToolkitTabViewer<'_> {
                 ui.checkbox(&mut self.tab_state.settings.replay_settings.show_game_chat, "Show Game Chat");
                 ui.checkbox(&mut self.tab_state.settings.replay_settings.show_entity_id, "Show Entity ID Column");
                 ui.checkbox(&mut self.tab_state.settings.replay_settings.show_observed_damage, "Show Observed Damage Column");
-            })
+                ui.collapsing("Contribution Score Weights", |ui| {
+                    self.tab_state.settings.replay_settings.contribution_weights.build_settings(ui);
+                });
+                self.build_expected_values_settings(ui);
+            });
+            ui.label("Team Colors");
+            ui.group(|ui| {
+                self.build_team_color_settings(ui);
+            });
+            ui.label("Clan");
+            ui.group(|ui| {
+                self.build_clan_settings(ui);
+            });
+            ui.label("Storage");
+            ui.group(|ui| {
+                self.build_storage_settings(ui);
+            });
+            ui.label("Backups");
+            ui.group(|ui| {
+                self.build_backup_settings(ui);
+            });
+            ui.label("Scheduled Jobs");
+            ui.group(|ui| {
+                self.build_scheduled_jobs_settings(ui);
+            });
+            ui.label("Battle Event Webhooks");
+            ui.group(|ui| {
+                let mut event_bus = self.tab_state.settings.event_bus.write();
+                ui.checkbox(&mut event_bus.enabled, "Send Battle Events")
+                    .on_hover_text("Sends battle_started, battle_ended, replay_parsed, and flagged_player_seen events as JSON.");
+                ui.add_enabled(
+                    event_bus.enabled,
+                    egui::TextEdit::multiline(&mut event_bus.webhooks)
+                        .desired_rows(2)
+                        .hint_text("Webhook URLs (one per line)"),
+                );
+                ui.add_enabled(
+                    event_bus.enabled,
+                    egui::TextEdit::singleline(&mut event_bus.pipe_path).hint_text(r"Named pipe (e.g. \\.\pipe\wows_toolkit)"),
+                );
+            });
+            ui.label("Usage Statistics");
+            ui.group(|ui| {
+                self.build_usage_stats_settings(ui);
+            });
+            ui.label("Lock");
+            ui.group(|ui| {
+                self.build_lock_settings(ui);
+            });
         });
+
+        self.build_replay_cleanup_window(ui.ctx());
     }
 }
 
@@ -160,6 +445,8 @@ impl TabViewer for ToolkitTabViewer<'_> {
             Tab::Settings => self.build_settings_tab(ui),
             Tab::ReplayParser => self.build_replay_parser_tab(ui),
             Tab::PlayerTracker => self.build_player_tracker_tab(ui),
+            Tab::Tournament => self.build_tournament_tab(ui),
+            Tab::MapVeto => self.build_map_veto_tab(ui),
         }
     }
 }
@@ -169,6 +456,8 @@ pub struct ReplaySettings {
     pub show_game_chat: bool,
     pub show_entity_id: bool,
     pub show_observed_damage: bool,
+    #[serde(default)]
+    pub contribution_weights: ContributionWeights,
 }
 
 impl Default for ReplaySettings {
@@ -177,6 +466,7 @@ impl Default for ReplaySettings {
             show_game_chat: true,
             show_entity_id: false,
             show_observed_damage: true,
+            contribution_weights: Default::default(),
         }
     }
 }
@@ -185,6 +475,22 @@ pub const fn default_bool<const V: bool>() -> bool {
     V
 }
 
+const fn default_max_parsed_replays() -> usize {
+    20
+}
+
+const fn default_max_downloaded_replays_mb() -> u64 {
+    200
+}
+
+const fn default_backup_interval_hours() -> u64 {
+    24
+}
+
+const fn default_max_backups() -> usize {
+    5
+}
+
 pub fn default_sent_replays() -> Arc<RwLock<HashSet<String>>> {
     Default::default()
 }
@@ -196,6 +502,14 @@ pub struct Settings {
     #[serde(skip)]
     pub replays_dir: Option<PathBuf>,
     pub locale: Option<String>,
+    /// Locale used for text loaded from game files. `None` uses the region's default, then the application locale.
+    #[serde(default)]
+    pub game_locale: Option<String>,
+    /// Region used for links and API calls which can't be derived from a replay
+    #[serde(default)]
+    pub region: Option<Region>,
+    #[serde(default)]
+    pub clan: ClanSettings,
     #[serde(default)]
     pub replay_settings: ReplaySettings,
     #[serde(default = "default_bool::<true>")]
@@ -210,6 +524,78 @@ pub struct Settings {
     pub has_019_game_params_update: bool,
     #[serde(default)]
     pub player_tracker: Arc<RwLock<PlayerTracker>>,
+    #[serde(default)]
+    pub event_bus: Arc<RwLock<EventBusSettings>>,
+    #[serde(default)]
+    pub tournament: Arc<RwLock<Tournament>>,
+    /// Optional URL crash reports may be submitted to instead of a GitHub issue
+    #[serde(default)]
+    pub crash_report_endpoint: String,
+    #[serde(default)]
+    pub usage_stats: UsageStatsSettings,
+    /// SHA-256 of the PIN which locks changes on shared PCs
+    #[serde(default)]
+    pub lock_pin_hash: Option<String>,
+    /// Salt hashed with the PIN, generated when it's set. PINs set before salting was added have an empty salt.
+    #[serde(default)]
+    pub lock_pin_salt: String,
+    /// Replays folder chosen by the user instead of the one found in the game directory
+    #[serde(default)]
+    pub custom_replays_dir: Option<PathBuf>,
+    #[serde(default)]
+    pub hidden_tabs: Vec<Tab>,
+    #[serde(default = "default_bool::<false>")]
+    pub has_completed_setup: bool,
+    /// Turns off animations and anything which repaints continuously
+    #[serde(default = "default_bool::<false>")]
+    pub performance_mode: bool,
+    /// How many replays keep their parsed battle report around after being opened
+    #[serde(default = "default_max_parsed_replays")]
+    pub max_parsed_replays: usize,
+    #[serde(default = "default_max_downloaded_replays_mb")]
+    pub max_downloaded_replays_mb: u64,
+    #[serde(default)]
+    pub team_palette: TeamPalette,
+    /// Larger hit targets, higher contrast and keyboard shortcuts for mouse-only actions
+    #[serde(default = "default_bool::<false>")]
+    pub accessibility_mode: bool,
+    /// Hides the window to the system tray when it's closed or minimized
+    #[serde(default = "default_bool::<false>")]
+    pub minimize_to_tray: bool,
+    /// Whether the app was added to the programs started at login
+    #[serde(default = "default_bool::<false>")]
+    pub start_with_windows: bool,
+    /// Hides the window to the tray right away when started at login
+    #[serde(default = "default_bool::<false>")]
+    pub start_minimized: bool,
+    /// Hours between automatic backups, or 0 to turn them off
+    #[serde(default = "default_backup_interval_hours")]
+    pub backup_interval_hours: u64,
+    #[serde(default = "default_max_backups")]
+    pub max_backups: usize,
+    #[serde(default)]
+    pub last_backup: Option<chrono::DateTime<chrono::Local>>,
+    #[serde(default)]
+    pub scheduled_jobs: ScheduledJobs,
+    #[serde(default)]
+    pub replay_cleanup: CleanupRules,
+    #[serde(default)]
+    pub map_veto: MapVeto,
+    #[serde(default)]
+    pub lineups: Vec<Lineup>,
+    /// Reloads game data when a game update is installed so ship data isn't stale
+    #[serde(default = "default_bool::<true>")]
+    pub reload_on_game_update: bool,
+    #[serde(default)]
+    pub game_launcher: GameLauncher,
+    #[serde(default)]
+    pub replay_collections: Vec<ReplayCollection>,
+    /// Titles the user gave replays, keyed by file name
+    #[serde(default)]
+    pub replay_titles: HashMap<String, ReplayTitle>,
+    /// Screen recordings linked to replays, keyed by replay file name
+    #[serde(default)]
+    pub linked_videos: HashMap<String, LinkedVideo>,
 }
 
 impl Default for Settings {
@@ -219,6 +605,9 @@ impl Default for Settings {
             wows_dir: Default::default(),
             replays_dir: Default::default(),
             locale: Default::default(),
+            game_locale: Default::default(),
+            region: Default::default(),
+            clan: Default::default(),
             replay_settings: Default::default(),
             check_for_updates: true,
             send_replay_data: true,
@@ -226,6 +615,35 @@ impl Default for Settings {
             sent_replays: Default::default(),
             has_019_game_params_update: false,
             player_tracker: Default::default(),
+            event_bus: Default::default(),
+            tournament: Default::default(),
+            crash_report_endpoint: Default::default(),
+            usage_stats: Default::default(),
+            lock_pin_hash: None,
+            lock_pin_salt: Default::default(),
+            custom_replays_dir: Default::default(),
+            hidden_tabs: Default::default(),
+            has_completed_setup: false,
+            performance_mode: false,
+            max_parsed_replays: default_max_parsed_replays(),
+            max_downloaded_replays_mb: default_max_downloaded_replays_mb(),
+            team_palette: Default::default(),
+            accessibility_mode: false,
+            minimize_to_tray: false,
+            start_with_windows: false,
+            start_minimized: false,
+            backup_interval_hours: default_backup_interval_hours(),
+            max_backups: default_max_backups(),
+            last_backup: None,
+            scheduled_jobs: Default::default(),
+            replay_cleanup: Default::default(),
+            map_veto: Default::default(),
+            lineups: Default::default(),
+            reload_on_game_update: true,
+            game_launcher: Default::default(),
+            replay_collections: Default::default(),
+            replay_titles: Default::default(),
+            linked_videos: Default::default(),
         }
     }
 }
@@ -240,11 +658,19 @@ pub enum NotifyFileEvent {
     Added(PathBuf),
     Removed(PathBuf),
     PreferencesChanged,
+    /// A new build directory was added to the game's `bin` directory
+    BuildInstalled,
+    /// The game wrote the arena info for a battle which is loading
+    BattleStarted,
 }
 
 pub struct TimedMessage {
     pub message: String,
     pub expiration: Instant,
+    /// Follow-up offered for this message in the notification center
+    pub action: Option<NotificationAction>,
+    /// Whether this message was added to the notification center yet
+    pub logged: bool,
 }
 
 impl TimedMessage {
@@ -252,6 +678,21 @@ impl TimedMessage {
         TimedMessage {
             message,
             expiration: Instant::now() + Duration::from_secs(10),
+            action: None,
+            logged: false,
+        }
+    }
+
+    pub fn with_action(mut self, action: NotificationAction) -> Self {
+        self.action = Some(action);
+        self
+    }
+
+    /// Message for a file the user exported, offering to show it once written
+    pub fn file_exported(path: PathBuf, result: std::io::Result<()>) -> Self {
+        match result {
+            Ok(()) => TimedMessage::new(format!("{} Saved {}", icons::CHECK_CIRCLE, path.display())).with_action(NotificationAction::ShowFile(path)),
+            Err(e) => TimedMessage::new(format!("{} Failed to save {}: {}", icons::WARNING, path.display(), e)),
         }
     }
 
@@ -304,6 +745,10 @@ pub struct TabState {
     #[serde(skip)]
     pub replay_files: Option<HashMap<PathBuf, Arc<RwLock<Replay>>>>,
 
+    /// Order of `replay_files` in the listing. Cleared whenever `replay_files` changes.
+    #[serde(skip)]
+    pub replay_listing: Option<ReplayListing>,
+
     #[serde(skip)]
     pub background_task: Option<BackgroundTask>,
 
@@ -319,6 +764,101 @@ pub struct TabState {
     #[serde(skip)]
     pub should_send_replays: Arc<AtomicBool>,
 
+    #[serde(skip)]
+    pub connectivity: Connectivity,
+
+    #[serde(skip)]
+    pub event_bus: Option<EventBus>,
+
+    #[serde(skip)]
+    pub replay_url_window: Option<String>,
+
+    #[serde(skip)]
+    pub storage_entries: Option<Vec<StorageEntry>>,
+
+    #[serde(skip)]
+    pub scouting_window: Option<ScoutingWindow>,
+
+    #[serde(skip)]
+    pub comparison_window: Option<ComparisonWindow>,
+
+    #[serde(skip)]
+    pub integrity_window: Option<IntegrityWindow>,
+
+    #[serde(skip)]
+    pub parse_diagnostics_window: Option<ParseDiagnosticsWindow>,
+
+    #[serde(skip)]
+    pub player_extraction_window: Option<PlayerExtractionWindow>,
+
+    #[serde(skip)]
+    pub matchmaking_window: Option<MatchmakingWindow>,
+
+    #[serde(skip)]
+    pub map_stats_window: Option<MapStatsWindow>,
+
+    #[serde(skip)]
+    pub replay_cleanup_window: Option<ReplayCleanupWindow>,
+
+    #[serde(skip)]
+    pub lineup_window: Option<LineupWindow>,
+
+    #[serde(skip)]
+    pub expected_values: Option<Arc<ExpectedValues>>,
+
+    /// Set while waiting for a launched game client to start
+    #[serde(skip)]
+    pub game_detection: Option<mpsc::Receiver<bool>>,
+
+    /// Loads battles as soon as they start, once the game was launched from the app
+    #[serde(skip)]
+    pub monitor_live_battles: bool,
+
+    /// Collection the replay listing is filtered to
+    #[serde(skip)]
+    pub selected_collection: Option<usize>,
+
+    #[serde(skip)]
+    pub collection_editor: Option<CollectionEditor>,
+
+    #[serde(skip)]
+    pub replay_title_editor: Option<ReplayTitleEditor>,
+
+    #[serde(skip)]
+    pub linked_video_editor: Option<LinkedVideoEditor>,
+
+    #[serde(skip)]
+    pub lock: LockState,
+
+    #[serde(skip)]
+    pub undo_stack: UndoStack,
+
+    pub notifications: NotificationCenter,
+
+    /// Battles flagged players have already been announced for, so reopening a replay doesn't repeat it
+    #[serde(skip)]
+    pub flagged_player_arenas: HashSet<i64>,
+
+    /// Set when the saved settings existed but couldn't be deserialized
+    #[serde(skip)]
+    pub offer_backup_recovery: bool,
+
+    /// Set when settings were replaced by a backup, so state derived from them can be rebuilt
+    #[serde(skip)]
+    pub settings_restored: bool,
+
+    /// Tabs shown or hidden from the Settings tab, to be added to or removed from the dock
+    #[serde(skip)]
+    pub toggled_tabs: Vec<Tab>,
+
+    /// Replays with a parsed battle report, least recently opened first
+    #[serde(skip)]
+    pub parsed_replays: VecDeque<Arc<RwLock<Replay>>>,
+
+    /// Whether replays opened from a URL are saved to the replays directory rather than a temp directory
+    #[serde(default = "default_bool::<false>")]
+    pub save_downloaded_replays: bool,
+
     #[serde(default = "default_bool::<true>")]
     pub auto_load_latest_replay: bool,
 }
@@ -338,6 +878,7 @@ impl Default for TabState {
             file_viewer: Default::default(),
             file_watcher: None,
             replay_files: None,
+            replay_listing: None,
             file_receiver: None,
             background_task: None,
             can_change_wows_dir: true,
@@ -346,6 +887,35 @@ impl Default for TabState {
             used_filter: None,
             filtered_file_list: None,
             should_send_replays: Arc::new(AtomicBool::new(false)),
+            connectivity: Default::default(),
+            event_bus: None,
+            replay_url_window: None,
+            storage_entries: None,
+            scouting_window: None,
+            comparison_window: None,
+            integrity_window: None,
+            parse_diagnostics_window: None,
+            player_extraction_window: None,
+            matchmaking_window: None,
+            map_stats_window: None,
+            replay_cleanup_window: None,
+            lineup_window: None,
+            expected_values: None,
+            game_detection: None,
+            monitor_live_battles: false,
+            selected_collection: None,
+            collection_editor: None,
+            replay_title_editor: None,
+            linked_video_editor: None,
+            lock: Default::default(),
+            undo_stack: Default::default(),
+            notifications: Default::default(),
+            flagged_player_arenas: Default::default(),
+            offer_backup_recovery: false,
+            settings_restored: false,
+            toggled_tabs: Vec::new(),
+            parsed_replays: VecDeque::new(),
+            save_downloaded_replays: false,
             auto_load_latest_replay: true,
         }
     }
@@ -370,6 +940,7 @@ impl TabState {
 
                                         if let Some(replay_files) = &mut self.replay_files {
                                             replay_files.insert(new_file.clone(), Arc::clone(&replay));
+                                            self.replay_listing = None;
                                         }
 
                                         if self.auto_load_latest_replay {
@@ -390,18 +961,122 @@ impl TabState {
                     NotifyFileEvent::Removed(old_file) => {
                         if let Some(replay_files) = &mut self.replay_files {
                             replay_files.remove(&old_file);
+                            self.replay_listing = None;
                         }
                     }
-                    NotifyFileEvent::PreferencesChanged => {
-                        // debug!("Preferences file changed -- reloading game data");
-                        // self.background_task = Some(self.load_game_data(self.settings.wows_dir.clone().into()));
+                    NotifyFileEvent::PreferencesChanged | NotifyFileEvent::BuildInstalled => {
+                        self.reload_if_game_updated();
+                    }
+                    NotifyFileEvent::BattleStarted => {
+                        if self.monitor_live_battles {
+                            if let Some(wows_data) = self.world_of_warships_data.as_ref() {
+                                update_background_task!(self.background_task, wows_data.read().parse_live_replay());
+                            }
+                        }
                     }
                 }
             }
         }
     }
 
-    fn prevent_changing_wows_dir(&mut self) {
+    /// Reloads game data if the installed build no longer matches the loaded one. The game params cache
+    /// is keyed by build, so reloading also rebuilds it.
+    fn reload_if_game_updated(&mut self) {
+        // The game rewrites preferences.xml often, so this is usually a no-op
+        if !self.settings.reload_on_game_update || !self.can_change_wows_dir {
+            return;
+        }
+
+        let Some(loaded_build) = self.world_of_warships_data.as_ref().map(|wows_data| wows_data.read().game_version) else {
+            return;
+        };
+        let wows_dir = PathBuf::from(&self.settings.wows_dir);
+        // A new build directory shows up while the update is still being installed, so only trust the build once the
+        // game has written it to preferences.xml and its index files are in place
+        let Some(installed_build) = task::preferences_build(&wows_dir) else {
+            return;
+        };
+        if !wows_dir.join("bin").join(installed_build.to_string()).join("idx").is_dir() {
+            return;
+        }
+
+        if installed_build != loaded_build {
+            debug!("game updated from build {} to {} -- reloading game data", loaded_build, installed_build);
+            *self.timed_message.write() = Some(TimedMessage::new(format!("{} Game update detected, reloading game data", icons::ARROWS_CLOCKWISE)));
+            self.prevent_changing_wows_dir();
+            update_background_task!(self.background_task, Some(self.load_game_data(wows_dir)));
+        }
+    }
+
+    /// Directory replays downloaded from a URL are saved to
+    pub fn downloaded_replays_dir(&self) -> PathBuf {
+        self.world_of_warships_data
+            .as_ref()
+            .map(|wows_data| wows_data.read().replays_dir.clone())
+            .filter(|_| self.save_downloaded_replays)
+            .unwrap_or_else(storage::downloaded_replays_temp_dir)
+    }
+
+    /// Marks `replay` as the most recently opened, dropping the battle reports of the least recently
+    /// opened replays once there are more than the configured maximum.
+    fn track_parsed_replay(&mut self, replay: &Arc<RwLock<Replay>>) {
+        self.parsed_replays.retain(|parsed| !Arc::ptr_eq(parsed, replay));
+        self.parsed_replays.push_back(Arc::clone(replay));
+
+        while self.parsed_replays.len() > self.settings.max_parsed_replays.max(1) {
+            if let Some(evicted) = self.parsed_replays.pop_front() {
+                evicted.write().battle_report = None;
+            }
+        }
+    }
+
+    /// Adds replay files to the replay list and loads the first one that could be opened
+    fn open_replay_files(&mut self, paths: Vec<PathBuf>) {
+        let Some(wows_data) = self.world_of_warships_data.clone() else {
+            *self.timed_message.write() = Some(TimedMessage::new(format!("{} Game data must be loaded before opening replays", icons::WARNING)));
+            return;
+        };
+        let Some(game_metadata) = wows_data.read().game_metadata.clone() else {
+            *self.timed_message.write() = Some(TimedMessage::new(format!("{} Game data must be loaded before opening replays", icons::WARNING)));
+            return;
+        };
+
+        let mut opened = Vec::new();
+        let mut failed = Vec::new();
+        for path in paths {
+            let file_name = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+            if path.extension().map(|ext| ext != "wowsreplay").unwrap_or(true) {
+                failed.push(file_name);
+                continue;
+            }
+
+            match ReplayFile::from_file(&path) {
+                Ok(replay_file) => {
+                    let replay = Arc::new(RwLock::new(Replay::new(replay_file, Arc::clone(&game_metadata))));
+                    self.replay_files.get_or_insert_with(HashMap::new).insert(path, Arc::clone(&replay));
+                    self.replay_listing = None;
+                    opened.push(replay);
+                }
+                Err(e) => {
+                    debug!("failed to open dropped replay {:?}: {:?}", path, e);
+                    failed.push(file_name);
+                }
+            }
+        }
+
+        if let Some(replay) = opened.first() {
+            update_background_task!(self.background_task, wows_data.read().load_replay(Arc::clone(replay)));
+        }
+
+        let message = if !failed.is_empty() {
+            format!("{} Could not open: {}", icons::WARNING, failed.join(", "))
+        } else {
+            format!("{} Opened {} replay(s)", icons::CHECK_CIRCLE, opened.len())
+        };
+        *self.timed_message.write() = Some(TimedMessage::new(message));
+    }
+
+    pub fn prevent_changing_wows_dir(&mut self) {
         self.can_change_wows_dir = false;
     }
 
@@ -413,11 +1088,16 @@ impl TabState {
         let watcher = if let Some(watcher) = self.file_watcher.as_mut() {
             let old_replays_dir = self.settings.replays_dir.as_ref().expect("watcher was created but replay dir was not assigned?");
             let _ = watcher.unwatch(old_replays_dir);
+            let old_wows_dir = Path::new(&self.settings.wows_dir);
+            let _ = watcher.unwatch(old_wows_dir);
+            let _ = watcher.unwatch(&old_wows_dir.join("bin"));
             watcher
         } else {
             debug!("creating filesystem watcher");
             let (tx, rx) = mpsc::channel();
             let (background_tx, background_rx) = mpsc::channel();
+            let event_bus = EventBus::start(Arc::clone(&self.settings.event_bus));
+            self.event_bus = Some(event_bus.clone());
 
             if let Some(wows_data) = self.world_of_warships_data.clone() {
                 self.should_send_replays.store(self.settings.send_replay_data, Ordering::SeqCst);
@@ -426,7 +1106,9 @@ impl TabState {
                     Arc::clone(&self.settings.sent_replays),
                     wows_data,
                     self.should_send_replays.clone(),
+                    self.connectivity.clone(),
                     Arc::clone(&self.settings.player_tracker),
+                    event_bus.clone(),
                 );
             }
 
@@ -442,8 +1124,22 @@ impl TabState {
                                     && path.file_name().expect("path has no filename") != "temp.wowsreplay"
                                 {
                                     tx.send(NotifyFileEvent::Added(path.clone())).expect("failed to send file creation event");
+                                    event_bus.emit(BattleEvent::BattleEnded { replay_path: path.clone() });
                                     // Send this path to the thread watching for replays in background
                                     let _ = background_tx.send(path);
+                                } else if path.file_name().map(|name| name == "tempArenaInfo.json").unwrap_or(false) {
+                                    event_bus.emit_battle_started(path.clone());
+                                    let _ = tx.send(NotifyFileEvent::BattleStarted);
+                                } else if path.is_dir()
+                                    && path.parent().and_then(|parent| parent.file_name()).map(|name| name == "bin").unwrap_or(false)
+                                    && path
+                                        .file_name()
+                                        .and_then(|name| name.to_str())
+                                        .map(|name| name.parse::<usize>().is_ok())
+                                        .unwrap_or(false)
+                                {
+                                    debug!("Sending build installed event");
+                                    tx.send(NotifyFileEvent::BuildInstalled).expect("failed to send build installed event");
                                 }
                             }
                         }
@@ -478,6 +1174,13 @@ impl TabState {
         // Add a path to be watched. All files and directories at that path and
         // below will be monitored for changes.
         watcher.watch(replay_dir, RecursiveMode::NonRecursive).expect("failed to watch directory");
+        // preferences.xml and the bin directory change when the game updates
+        if let Err(e) = watcher.watch(wows_dir, RecursiveMode::NonRecursive) {
+            debug!("failed to watch game directory: {:?}", e);
+        }
+        if let Err(e) = watcher.watch(&wows_dir.join("bin"), RecursiveMode::NonRecursive) {
+            debug!("failed to watch game bin directory: {:?}", e);
+        }
 
         self.settings.wows_dir = wows_dir.to_str().unwrap().to_string();
         self.settings.replays_dir = Some(replay_dir.to_owned())
@@ -485,17 +1188,40 @@ impl TabState {
 
     #[must_use]
     pub fn load_game_data(&self, wows_directory: PathBuf) -> BackgroundTask {
-        let (tx, rx) = mpsc::channel();
-        let locale = self.settings.locale.clone().unwrap();
-        let _join_handle = std::thread::spawn(move || {
-            let _ = tx.send(task::load_wows_files(wows_directory, locale.as_str()));
-        });
+        let locale = self
+            .settings
+            .game_locale
+            .clone()
+            .or_else(|| self.settings.region.map(|region| region.default_locale().to_string()))
+            .or_else(|| self.settings.locale.clone())
+            .unwrap_or_else(|| "en".to_string());
+        let custom_replays_dir = self.settings.custom_replays_dir.clone();
+        BackgroundTask::spawn(BackgroundTaskKind::LoadingData, move || {
+            task::load_wows_files(wows_directory, locale.as_str(), custom_replays_dir)
+        })
+    }
+}
+
+fn build_dock_state(hidden_tabs: &[Tab]) -> DockState<Tab> {
+    DockState::new(Tab::all().into_iter().filter(|tab| *tab == Tab::Settings || !hidden_tabs.contains(tab)).collect())
+}
 
-        BackgroundTask {
-            receiver: rx,
-            kind: BackgroundTaskKind::LoadingData,
+/// Checkboxes for choosing which tabs are shown. Settings is always shown. Returns the tabs which were toggled.
+pub fn build_tab_checkboxes(ui: &mut egui::Ui, hidden_tabs: &mut Vec<Tab>) -> Vec<Tab> {
+    let mut toggled = Vec::new();
+    for tab in Tab::all().into_iter().filter(|tab| *tab != Tab::Settings) {
+        let mut is_visible = !hidden_tabs.contains(&tab);
+        if ui.checkbox(&mut is_visible, tab.title()).changed() {
+            if is_visible {
+                hidden_tabs.retain(|hidden| *hidden != tab);
+            } else {
+                hidden_tabs.push(tab.clone());
+            }
+            toggled.push(tab);
         }
     }
+
+    toggled
 }
 
 /// We derive Deserialize/Serialize so we can persist app state on shutdown.
@@ -521,6 +1247,50 @@ pub struct WowsToolkitApp {
 
     #[serde(skip)]
     runtime: Runtime,
+
+    #[serde(skip)]
+    deep_links: Option<mpsc::Receiver<DeepLink>>,
+    /// Replay URL or path from a link, waiting for the user to confirm opening it
+    #[serde(skip)]
+    pending_deep_link_replay: Option<String>,
+
+    #[serde(skip)]
+    checked_for_crash_log: bool,
+    #[serde(skip)]
+    crash_log: Option<String>,
+
+    #[serde(skip)]
+    setup_wizard: Option<SetupWizard>,
+
+    #[serde(skip)]
+    diagnostics: Diagnostics,
+
+    #[serde(skip)]
+    health_checks: Option<HealthChecks>,
+
+    #[serde(skip)]
+    command_palette: CommandPalette,
+
+    /// Accessibility mode setting the current style was built for
+    #[serde(skip)]
+    applied_accessibility_mode: Option<bool>,
+
+    #[serde(skip)]
+    tray: Option<Tray>,
+    /// Tray setting the tray icon was last added or removed for
+    #[serde(skip)]
+    applied_minimize_to_tray: Option<bool>,
+    #[serde(skip)]
+    hidden_to_tray: bool,
+    /// Set when the tray brought the window back, until the viewport no longer reports being minimized
+    #[serde(skip)]
+    restoring_from_tray: bool,
+    /// Set when the user asked to quit, so closing the window isn't turned into hiding it
+    #[serde(skip)]
+    quitting: bool,
+    /// Set when started at login with "start minimized" on, until the window has been hidden
+    #[serde(skip)]
+    start_hidden: bool,
 }
 
 impl Default for WowsToolkitApp {
@@ -531,10 +1301,25 @@ impl Default for WowsToolkitApp {
             latest_release: None,
             show_about_window: false,
             tab_state: Default::default(),
-            dock_state: DockState::new([Tab::ReplayParser, Tab::PlayerTracker, Tab::Unpacker, Tab::Settings].to_vec()),
+            dock_state: build_dock_state(&[]),
             show_error_window: false,
             error_to_show: None,
             runtime: Runtime::new().expect("failed to create tokio runtime"),
+            deep_links: None,
+            pending_deep_link_replay: None,
+            checked_for_crash_log: false,
+            crash_log: None,
+            setup_wizard: None,
+            diagnostics: Default::default(),
+            health_checks: None,
+            command_palette: Default::default(),
+            applied_accessibility_mode: None,
+            tray: None,
+            applied_minimize_to_tray: None,
+            hidden_to_tray: false,
+            restoring_from_tray: false,
+            quitting: false,
+            start_hidden: false,
         }
     }
 }
@@ -555,7 +1340,10 @@ impl WowsToolkitApp {
         // Load previous app state (if any).
         // Note that you must enable the `persistence` feature for this to work.
         if let Some(storage) = cc.storage {
-            let mut saved_state: Self = eframe::get_value(storage, eframe::APP_KEY).unwrap_or_default();
+            let saved_state: Option<Self> = eframe::get_value(storage, eframe::APP_KEY);
+            let settings_unreadable = saved_state.is_none() && storage.get_string(eframe::APP_KEY).is_some();
+            let mut saved_state = saved_state.unwrap_or_default();
+            saved_state.tab_state.offer_backup_recovery = settings_unreadable;
             if !saved_state.tab_state.settings.wows_dir.is_empty() {
                 saved_state.tab_state.background_task = Some(saved_state.tab_state.load_game_data(PathBuf::from(saved_state.tab_state.settings.wows_dir.clone())));
             }
@@ -579,6 +1367,24 @@ impl WowsToolkitApp {
                 .should_send_replays
                 .store(saved_state.tab_state.settings.send_replay_data, Ordering::Relaxed);
 
+            saved_state.deep_links = Some(protocol_handler::listen(cc.egui_ctx.clone()));
+            saved_state.start_hidden = saved_state.tab_state.settings.start_minimized && autostart::launched_at_login();
+            saved_state.tab_state.expected_values = ExpectedValues::load_cached().map(Arc::new);
+            saved_state.tab_state.settings.tournament.write().load_submissions();
+
+            saved_state.dock_state = build_dock_state(&saved_state.tab_state.settings.hidden_tabs);
+            if !saved_state.tab_state.settings.has_completed_setup {
+                // People who configured the app before the wizard existed don't need it
+                if saved_state.tab_state.settings.wows_dir.is_empty() {
+                    saved_state.setup_wizard = Some(SetupWizard::new());
+                } else {
+                    saved_state.tab_state.settings.has_completed_setup = true;
+                }
+            }
+            if saved_state.setup_wizard.is_none() {
+                saved_state.health_checks = Some(HealthChecks::run(&saved_state.tab_state.settings, &saved_state.tab_state.connectivity));
+            }
+
             return saved_state;
         }
 
@@ -594,16 +1400,210 @@ impl WowsToolkitApp {
             this.tab_state.background_task = Some(this.tab_state.load_game_data(default_wows_path.to_path_buf()));
         }
 
+        this.deep_links = Some(protocol_handler::listen(cc.egui_ctx.clone()));
+        this.setup_wizard = Some(SetupWizard::new());
+
         this
     }
 
+    /// Adds or removes the tabs toggled in the Settings tab, leaving the rest of the layout alone
+    fn apply_toggled_tabs(&mut self) {
+        for tab in std::mem::take(&mut self.tab_state.toggled_tabs) {
+            let location = self.dock_state.find_tab(&tab);
+            match (self.tab_state.settings.hidden_tabs.contains(&tab), location) {
+                (true, Some(location)) => {
+                    self.dock_state.remove_tab(location);
+                }
+                (false, None) => self.dock_state.push_to_first_leaf(tab),
+                _ => {}
+            }
+        }
+    }
+
+    fn focus_tab(&mut self, tab: &Tab) {
+        if let Some(location) = self.dock_state.find_tab(tab) {
+            self.dock_state.set_active_tab(location);
+        }
+    }
+
+    fn open_palette_target(&mut self, target: PaletteTarget) {
+        match target {
+            PaletteTarget::Tab(tab) => self.focus_tab(&tab),
+            PaletteTarget::Replay(replay) => {
+                self.focus_tab(&Tab::ReplayParser);
+                if let Some(wows_data) = self.tab_state.world_of_warships_data.as_ref() {
+                    crate::update_background_task!(self.tab_state.background_task, wows_data.read().load_replay(replay));
+                }
+            }
+            PaletteTarget::Player(db_id) => {
+                self.focus_tab(&Tab::PlayerTracker);
+                self.tab_state.settings.player_tracker.write().select_player(db_id);
+            }
+            PaletteTarget::Action(action) => match action {
+                PaletteAction::OpenReplayFile => {
+                    if let Some(file) = rfd::FileDialog::new().add_filter("WoWs Replays", &["wowsreplay"]).pick_file() {
+                        self.focus_tab(&Tab::ReplayParser);
+                        self.tab_state.open_replay_files(vec![file]);
+                    }
+                }
+                PaletteAction::OpenSetupWizard if !self.tab_state.is_locked() => self.setup_wizard = Some(SetupWizard::new()),
+                PaletteAction::RunHealthCheck if !self.tab_state.is_locked() => {
+                    let mut health_checks = HealthChecks::run(&self.tab_state.settings, &self.tab_state.connectivity);
+                    health_checks.open = true;
+                    self.health_checks = Some(health_checks);
+                }
+                PaletteAction::ShowDiagnostics => self.diagnostics.open = true,
+                PaletteAction::CheckForUpdates => self.checked_for_updates = false,
+                PaletteAction::Undo if !self.tab_state.is_locked() => self.tab_state.undo(),
+                PaletteAction::Redo if !self.tab_state.is_locked() => self.tab_state.redo(),
+                PaletteAction::OpenSetupWizard | PaletteAction::RunHealthCheck | PaletteAction::Undo | PaletteAction::Redo => {}
+            },
+        }
+    }
+
+    fn run_notification_action(&mut self, action: NotificationAction) {
+        match action {
+            NotificationAction::ShowFile(path) => util::open_file_explorer(&path),
+            NotificationAction::RetryDownload(url) => {
+                let destination_dir = self.tab_state.downloaded_replays_dir();
+                crate::update_background_task!(self.tab_state.background_task, Some(task::start_downloading_replay(url, destination_dir)));
+            }
+            NotificationAction::ViewPlayer(db_id) => self.open_palette_target(PaletteTarget::Player(db_id)),
+        }
+    }
+
+    /// Keeps the tray icon in line with the setting, runs what was picked from its menu, and hides the
+    /// window to it instead of closing or minimizing
+    fn update_tray(&mut self, ctx: &egui::Context, frame: &eframe::Frame) {
+        let minimize_to_tray = self.tab_state.settings.minimize_to_tray;
+        if self.applied_minimize_to_tray != Some(minimize_to_tray) {
+            self.tray = if minimize_to_tray { Tray::new(ctx, frame) } else { None };
+            self.applied_minimize_to_tray = Some(minimize_to_tray);
+        }
+        let Some(tray) = self.tray.as_ref() else {
+            return;
+        };
+        if std::mem::take(&mut self.start_hidden) {
+            tray::hide_window(ctx);
+            self.hidden_to_tray = true;
+        }
+
+        let actions: Vec<TrayAction> = std::iter::from_fn(|| tray.try_recv()).collect();
+        for action in actions {
+            self.hidden_to_tray = false;
+            // The window was restored by the tray's handlers, but the viewport reports being minimized until
+            // the commands sent with it have been applied
+            self.restoring_from_tray = true;
+            match action {
+                TrayAction::ShowWindow => {}
+                TrayAction::OpenReplayFile => self.open_palette_target(PaletteTarget::Action(PaletteAction::OpenReplayFile)),
+                TrayAction::Search => self.command_palette.show_palette(&ToolkitTabViewer { tab_state: &mut self.tab_state }),
+                TrayAction::Quit => {
+                    self.quitting = true;
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                }
+            }
+        }
+
+        let (close_requested, minimized) = ctx.input(|i| (i.viewport().close_requested(), i.viewport().minimized == Some(true)));
+        if close_requested && !self.quitting {
+            ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+            tray::hide_window(ctx);
+            self.hidden_to_tray = true;
+        } else if !minimized {
+            self.restoring_from_tray = false;
+        } else if !self.hidden_to_tray && !self.restoring_from_tray {
+            tray::hide_window(ctx);
+            self.hidden_to_tray = true;
+        }
+        if self.hidden_to_tray {
+            // Keeps the listing up to date where hidden windows are still redrawn. Where they aren't, new replays are
+            // parsed in the background anyway and the listing catches up once the window is shown.
+            ctx.request_repaint_after(Duration::from_secs(1));
+        }
+    }
+
+    fn open_deep_link(&mut self, ctx: &egui::Context, link: DeepLink) {
+        debug!("opening deep link: {:?}", link);
+        // Any local process can hand us a link, so they can't be used to get around the lock
+        if self.tab_state.is_locked() {
+            *self.tab_state.timed_message.write() = Some(TimedMessage::new(format!("{} Unlock WoWs Toolkit to open links", icons::LOCK)));
+            return;
+        }
+        ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+
+        let tab = link
+            .tab
+            .as_deref()
+            .and_then(Tab::from_link_name)
+            .or_else(|| link.replay.is_some().then_some(Tab::ReplayParser));
+        if let Some(tab) = tab {
+            self.focus_tab(&tab);
+        }
+
+        if let Some(replay) = link.replay {
+            if is_url(&replay) || Path::new(&replay).extension().map(|ext| ext == "wowsreplay").unwrap_or(false) {
+                self.pending_deep_link_replay = Some(replay);
+            } else {
+                *self.tab_state.timed_message.write() = Some(TimedMessage::new(format!("{} Links can only open .wowsreplay files", icons::WARNING)));
+            }
+        }
+    }
+
+    /// Asks before downloading or opening the replay a link pointed to
+    fn build_deep_link_window(&mut self, ctx: &egui::Context) {
+        if self.tab_state.is_locked() {
+            self.pending_deep_link_replay = None;
+        }
+        let Some(replay) = self.pending_deep_link_replay.as_ref() else {
+            return;
+        };
+
+        let mut is_open = true;
+        let mut confirmed = false;
+        let mut handled = false;
+        egui::Window::new("Open Replay From Link").open(&mut is_open).collapsible(false).show(ctx, |ui| {
+            if is_url(replay) {
+                ui.label("A link asked to download and open this replay:");
+            } else {
+                ui.label("A link asked to open this replay:");
+            }
+            ui.monospace(replay.as_str());
+            ui.horizontal(|ui| {
+                if ui.button(format!("{} Open", icons::CHECK_CIRCLE)).clicked() {
+                    confirmed = true;
+                    handled = true;
+                }
+                if ui.button("Cancel").clicked() {
+                    handled = true;
+                }
+            });
+        });
+
+        if !handled && is_open {
+            return;
+        }
+        let Some(replay) = self.pending_deep_link_replay.take().filter(|_| confirmed) else {
+            return;
+        };
+        if is_url(&replay) {
+            let destination_dir = self.tab_state.downloaded_replays_dir();
+            crate::update_background_task!(self.tab_state.background_task, Some(task::start_downloading_replay(replay, destination_dir)));
+        } else {
+            self.tab_state.open_replay_files(vec![PathBuf::from(replay)]);
+        }
+    }
+
     pub fn build_bottom_panel(&mut self, ui: &mut Ui) {
         ui.horizontal(|ui| {
             // TODO: Merge these channels
             if let Some(task) = &mut self.tab_state.background_task {
-                let desc = task.build_description(ui);
+                let desc = task.build_description(ui, self.tab_state.settings.performance_mode);
                 trace!("Task description: {:?}", desc);
-                if let Some(result) = desc {
+                if let Some(outcome) = desc {
+                    self.diagnostics.record_task(task, outcome.timing);
+                    let result = outcome.result;
+                    let mut retry_action = None;
                     match &task.kind {
                         BackgroundTaskKind::LoadingData => {
                             self.tab_state.allow_changing_wows_dir();
@@ -620,12 +1620,43 @@ impl WowsToolkitApp {
                         BackgroundTaskKind::PopulatePlayerInspectorFromReplays => {
                             // do nothing
                         }
+                        BackgroundTaskKind::ScoringTournament => {
+                            // do nothing
+                        }
+                        BackgroundTaskKind::DownloadingReplay { url, .. } => {
+                            retry_action = Some(NotificationAction::RetryDownload(url.clone()));
+                        }
+                        BackgroundTaskKind::SyncingClanRoster => {
+                            // do nothing
+                        }
+                        BackgroundTaskKind::BuildingScoutingReport => {
+                            // do nothing
+                        }
+                        BackgroundTaskKind::BuildingShipComparison => {
+                            // do nothing
+                        }
+                        BackgroundTaskKind::VerifyingReplay => {
+                            // do nothing
+                        }
+                        BackgroundTaskKind::ExtractingPlayers => {
+                            // do nothing
+                        }
+                        BackgroundTaskKind::AnalyzingMatchmaking => {
+                            // do nothing
+                        }
+                        BackgroundTaskKind::CollectingParseDiagnostics => {
+                            // do nothing
+                        }
+                        BackgroundTaskKind::CollectingMapStats => {
+                            // do nothing
+                        }
                     }
 
                     match result {
                         Ok(data) => match data {
                             BackgroundTaskCompletion::DataLoaded { new_dir, wows_data, replays } => {
                                 let replays_dir = wows_data.replays_dir.clone();
+                                crash_report::set_game_version(wows_data.game_version.to_string());
                                 if let Some(old_wows_data) = &self.tab_state.world_of_warships_data {
                                     *old_wows_data.write() = wows_data;
                                 } else {
@@ -633,6 +1664,7 @@ impl WowsToolkitApp {
                                 }
                                 self.tab_state.update_wows_dir(&new_dir, &replays_dir);
                                 self.tab_state.replay_files = replays;
+                                self.tab_state.replay_listing = None;
                                 self.tab_state.filtered_file_list = None;
                                 self.tab_state.used_filter = None;
 
@@ -643,8 +1675,24 @@ impl WowsToolkitApp {
                                     self.tab_state.replay_parser_tab.lock().game_chat.clear();
                                 }
                                 {
-                                    self.tab_state.settings.player_tracker.write().update_from_replay(&*replay.read());
+                                    let mut player_tracker = self.tab_state.settings.player_tracker.write();
+                                    // Replays newer than the game data are only parsed approximately, so they're kept out of the tracker
+                                    let game_version = self.tab_state.world_of_warships_data.as_ref().map(|wows_data| wows_data.read().game_version);
+                                    if !game_version.map(|game_version| replay.read().is_newer_than(game_version)).unwrap_or(false) {
+                                        player_tracker.update_from_replay(&*replay.read());
+                                    }
+                                    // Reopening a replay, or loading it again after a live battle, shouldn't repeat the notification
+                                    let arena_id = replay.read().battle_report.as_ref().map(|report| report.arena_id());
+                                    if arena_id.map(|arena_id| self.tab_state.flagged_player_arenas.insert(arena_id)).unwrap_or(false) {
+                                        for flagged_player in player_tracker.flagged_players_in_replay(&replay.read()) {
+                                            self.tab_state.notifications.push(
+                                                format!("{} {} is in this battle: {}", icons::FLAG, flagged_player.last_name(), flagged_player.notes().trim()),
+                                                Some(NotificationAction::ViewPlayer(flagged_player.db_id())),
+                                            );
+                                        }
+                                    }
                                 }
+                                self.tab_state.track_parsed_replay(&replay);
                                 self.tab_state.current_replay = Some(replay);
                                 *self.tab_state.timed_message.write() = Some(TimedMessage::new(format!("{} Successfully loaded replay", icons::CHECK_CIRCLE)))
                             }
@@ -666,11 +1714,73 @@ impl WowsToolkitApp {
                             BackgroundTaskCompletion::PopulatePlayerInspectorFromReplays => {
                                 // do nothing
                             }
+                            BackgroundTaskCompletion::TournamentScored => {
+                                *self.tab_state.timed_message.write() = Some(TimedMessage::new(format!("{} Tournament replays scored", icons::CHECK_CIRCLE)))
+                            }
+                            BackgroundTaskCompletion::ClanRosterSynced(members) => {
+                                let clan = &mut self.tab_state.settings.clan;
+                                clan.members = members;
+                                clan.last_synced = Some(chrono::Local::now());
+                                *self.tab_state.timed_message.write() =
+                                    Some(TimedMessage::new(format!("{} Synced {} clan members", icons::CHECK_CIRCLE, clan.members.len())))
+                            }
+                            BackgroundTaskCompletion::ScoutingReportBuilt(report) => {
+                                let battles = report.battles;
+                                self.tab_state.scouting_window.get_or_insert_with(Default::default).markdown = Some(report.to_markdown());
+                                *self.tab_state.timed_message.write() =
+                                    Some(TimedMessage::new(format!("{} Scouting report built from {} battles", icons::CHECK_CIRCLE, battles)))
+                            }
+                            BackgroundTaskCompletion::ShipComparisonBuilt(comparison) => {
+                                let battles = comparison.battles.len();
+                                if let Some(window) = self.tab_state.comparison_window.as_mut() {
+                                    window.markdown = Some(comparison.to_markdown());
+                                }
+                                *self.tab_state.timed_message.write() = Some(TimedMessage::new(format!("{} Compared {} battles", icons::CHECK_CIRCLE, battles)))
+                            }
+                            BackgroundTaskCompletion::ReplayVerified(report) => {
+                                if let Some(window) = self.tab_state.integrity_window.as_mut() {
+                                    window.report = Some(report);
+                                }
+                            }
+                            BackgroundTaskCompletion::MatchmakingAnalyzed(analysis) => {
+                                if let Some(window) = self.tab_state.matchmaking_window.as_mut() {
+                                    window.analysis = Some(analysis);
+                                }
+                            }
+                            BackgroundTaskCompletion::MapStatsCollected(stats) => {
+                                if let Some(window) = self.tab_state.map_stats_window.as_mut() {
+                                    window.stats = Some(stats);
+                                }
+                            }
+                            BackgroundTaskCompletion::ParseDiagnosticsCollected(diagnostics) => {
+                                if let Some(window) = self.tab_state.parse_diagnostics_window.as_mut() {
+                                    window.diagnostics = Some(diagnostics);
+                                }
+                            }
+                            BackgroundTaskCompletion::PlayersExtracted(extraction) => {
+                                *self.tab_state.timed_message.write() = Some(TimedMessage::new(format!(
+                                    "{} Extracted {} players from {} replays",
+                                    icons::CHECK_CIRCLE,
+                                    extraction.players.len(),
+                                    extraction.replays
+                                )));
+                                if let Some(window) = self.tab_state.player_extraction_window.as_mut() {
+                                    window.extraction = Some(extraction);
+                                }
+                            }
+                            BackgroundTaskCompletion::ReplayDownloaded(path) => {
+                                let temp_dir = storage::downloaded_replays_temp_dir();
+                                if path.starts_with(&temp_dir) {
+                                    storage::evict_least_recently_used(&temp_dir, self.tab_state.settings.max_downloaded_replays_mb * 1_000_000, Some(&path));
+                                }
+                                self.tab_state.open_replay_files(vec![path]);
+                            }
                         },
                         Err(ToolkitError::BackgroundTaskCompleted) => {
                             self.tab_state.background_task = None;
                         }
                         Err(e) => {
+                            self.tab_state.notifications.push(format!("{} {}", icons::WARNING, e), retry_action);
                             self.show_error_window = true;
                             self.error_to_show = Some(Box::new(e));
                         }
@@ -719,12 +1829,69 @@ impl WowsToolkitApp {
                     *self.tab_state.timed_message.write() = None;
                 }
             }
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                self.tab_state.notifications.build_button(ui);
+
+                if self.tab_state.connectivity.is_offline() {
+                    let offline_label = ui
+                        .add(egui::Label::new(format!("{} Offline", icons::WIFI_SLASH)).sense(egui::Sense::click()))
+                        .on_hover_text(
+                            "The internet couldn't be reached. Cached game data is used and uploads are paused until the connection is back. Click to check again.",
+                        );
+                    if offline_label.clicked() {
+                        self.tab_state.connectivity.check_now();
+                    }
+                }
+            });
         });
     }
 
     fn check_for_battle_results_update(&mut self) {}
 
+    /// Offers to report a crash from the previous run. Nothing is sent unless the user asks for it.
+    fn build_crash_report_window(&mut self, ctx: &egui::Context) {
+        let Some(log) = self.crash_log.as_ref() else {
+            return;
+        };
+
+        let mut is_open = true;
+        let mut handled = false;
+        let endpoint = self.tab_state.settings.crash_report_endpoint.trim();
+        egui::Window::new("WoWs Toolkit Crashed").open(&mut is_open).collapsible(false).show(ctx, |ui| {
+            ui.label("WoWs Toolkit crashed the last time it was running. Would you like to report it?");
+            ui.label("The report contains the log below, the app and game versions, and your operating system.");
+            egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                ui.add(egui::TextEdit::multiline(&mut log.as_str()).code_editor().desired_width(f32::INFINITY));
+            });
+            ui.horizontal(|ui| {
+                if ui.button(format!("{} Open GitHub Issue", icons::BUG)).clicked() {
+                    ui.ctx().open_url(OpenUrl::new_tab(crash_report::github_issue_url(log)));
+                    handled = true;
+                }
+                if !endpoint.is_empty() && ui.button(format!("{} Send Report", icons::SHARE)).clicked() {
+                    crash_report::submit_to_endpoint(endpoint.to_string(), log.clone());
+                    handled = true;
+                }
+                if ui.button("Don't Send").clicked() {
+                    handled = true;
+                }
+            });
+        });
+
+        if handled || !is_open {
+            crash_report::discard_crash_log();
+            self.crash_log = None;
+        }
+    }
+
     fn check_for_updates(&mut self) {
+        self.checked_for_updates = true;
+        if self.tab_state.connectivity.is_offline() {
+            debug!("offline, skipping update check");
+            return;
+        }
+
         let result = self.runtime.block_on(async {
             octocrab::instance()
                 .repos("landaire", "wows-toolkit")
@@ -750,8 +1917,10 @@ impl WowsToolkitApp {
                     }
                 }
             }
+        } else {
+            // Could be a rate limit rather than the connection, so check before going offline
+            self.tab_state.connectivity.check_now();
         }
-        self.checked_for_updates = true;
     }
 }
 
@@ -761,14 +1930,108 @@ impl eframe::App for WowsToolkitApp {
         eframe::set_value(storage, eframe::APP_KEY, self);
     }
 
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        usage_stats::flush();
+    }
+
     /// Called each time the UI needs repainting, which may be many times per second.
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
         // Put your widgets into a `SidePanel`, `TopPanel`, `CentralPanel`, `Window` or `Area`.
         // For inspiration and more examples, go to https://emilk.github.io/egui
 
         egui_extras::install_image_loaders(ctx);
 
+        let animation_time = if self.tab_state.settings.performance_mode {
+            0.0
+        } else {
+            egui::Style::default().animation_time
+        };
+        if ctx.style().animation_time != animation_time {
+            ctx.style_mut(|style| style.animation_time = animation_time);
+        }
+
+        let accessibility_mode = self.tab_state.settings.accessibility_mode;
+        if self.applied_accessibility_mode != Some(accessibility_mode) {
+            accessibility::apply_style(ctx, accessibility_mode);
+            self.applied_accessibility_mode = Some(accessibility_mode);
+        }
+
+        self.update_tray(ctx, frame);
         self.tab_state.try_update_replays();
+        self.tab_state.check_game_detection();
+
+        // Links usually reference replays, so hold on to them until game data is available
+        if self.tab_state.world_of_warships_data.is_some() {
+            while let Some(link) = self.deep_links.as_ref().and_then(|rx| rx.try_recv().ok()) {
+                self.open_deep_link(ctx, link);
+            }
+        }
+
+        let dropped_files: Vec<PathBuf> = ctx.input(|i| i.raw.dropped_files.iter().filter_map(|file| file.path.clone()).collect());
+        if !dropped_files.is_empty() {
+            self.tab_state.open_replay_files(dropped_files);
+        }
+
+        usage_stats::tick(&self.tab_state.settings.usage_stats, &self.tab_state.connectivity);
+        if !self.checked_for_crash_log {
+            self.crash_log = crash_report::pending_crash_log();
+            self.checked_for_crash_log = true;
+        }
+        self.build_crash_report_window(ctx);
+        self.build_deep_link_window(ctx);
+
+        if ctx.input_mut(|i| i.consume_shortcut(&egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::K))) {
+            if self.command_palette.open {
+                self.command_palette.open = false;
+            } else {
+                self.command_palette.show_palette(&ToolkitTabViewer { tab_state: &mut self.tab_state });
+            }
+        }
+        if let Some(target) = self.command_palette.show(ctx) {
+            self.open_palette_target(target);
+        }
+
+        self.tab_state.log_timed_message();
+        if let Some(action) = self.tab_state.notifications.show(ctx) {
+            self.run_notification_action(action);
+        }
+
+        // Text fields have their own undo history
+        if !ctx.wants_keyboard_input() && !self.tab_state.is_locked() {
+            if ctx.input_mut(|i| i.consume_shortcut(&egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Z))) {
+                self.tab_state.undo();
+            } else if ctx.input_mut(|i| i.consume_shortcut(&egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Y))) {
+                self.tab_state.redo();
+            }
+        }
+
+        self.tab_state.build_backup_recovery_window(ctx);
+        self.apply_toggled_tabs();
+        if std::mem::take(&mut self.tab_state.settings_restored) {
+            self.tab_state.should_send_replays.store(self.tab_state.settings.send_replay_data, Ordering::Relaxed);
+            self.dock_state = build_dock_state(&self.tab_state.settings.hidden_tabs);
+            if self.tab_state.settings.has_completed_setup {
+                self.setup_wizard = None;
+            }
+        }
+        if self.tab_state.is_backup_due() {
+            self.tab_state.back_up_settings();
+        }
+        self.tab_state.run_due_jobs();
+        if self.tab_state.connectivity.is_offline() {
+            self.tab_state.connectivity.recheck_if_offline();
+            // Picks up the result of the next check
+            ctx.request_repaint_after(Duration::from_secs(60));
+        }
+
+        if let Some(setup_wizard) = self.setup_wizard.as_mut() {
+            if setup_wizard.show(ctx, &mut self.tab_state) {
+                self.tab_state.settings.has_completed_setup = true;
+                self.tab_state.should_send_replays.store(self.tab_state.settings.send_replay_data, Ordering::Relaxed);
+                self.dock_state = build_dock_state(&self.tab_state.settings.hidden_tabs);
+                self.setup_wizard = None;
+            }
+        }
 
         if !self.checked_for_updates && self.tab_state.settings.check_for_updates {
             self.check_for_updates();
@@ -783,6 +2046,16 @@ impl eframe::App for WowsToolkitApp {
                     .assets
                     .iter()
                     .find(|asset| asset.name.contains("windows") && asset.name.ends_with(".zip"));
+                // The release action publishes a `<archive>.sha256sum` next to each archive
+                let checksum_asset = asset.and_then(|asset| {
+                    latest_release.assets.iter().find(|checksum| {
+                        checksum
+                            .name
+                            .strip_prefix(asset.name.as_str())
+                            .map(|suffix| matches!(suffix, ".sha256" | ".sha256sum"))
+                            .unwrap_or(checksum.name == "SHA256SUMS")
+                    })
+                });
                 // Only show the update window if we have a valid artifact to download
                 if let Some(asset) = asset {
                     egui::Window::new("Update Available").open(&mut self.update_window_open).show(ctx, |ui| {
@@ -795,7 +2068,7 @@ impl eframe::App for WowsToolkitApp {
                                 #[cfg(target_os = "windows")]
                                 {
                                     if ui.button("Install Update").clicked() {
-                                        self.tab_state.background_task = Some(crate::task::start_download_update_task(&self.runtime, asset));
+                                        self.tab_state.background_task = Some(crate::task::start_download_update_task(asset, checksum_asset));
                                     }
                                 }
                                 if ui.button("View Release").clicked() {
@@ -820,6 +2093,21 @@ impl eframe::App for WowsToolkitApp {
             }
         }
 
+        self.diagnostics.record_frame(ctx, &self.tab_state);
+        if self.diagnostics.open {
+            self.diagnostics.show(ctx, &mut self.tab_state);
+        }
+
+        if let Some(health_checks) = self.health_checks.as_mut().filter(|health_checks| health_checks.open) {
+            if let Some(fix) = health_checks.show(ctx, self.tab_state.is_locked()) {
+                if self.tab_state.apply_health_fix(fix) {
+                    let mut health_checks = HealthChecks::run(&self.tab_state.settings, &self.tab_state.connectivity);
+                    health_checks.open = true;
+                    self.health_checks = Some(health_checks);
+                }
+            }
+        }
+
         if self.show_about_window {
             egui::Window::new("About").open(&mut self.show_about_window).show(ctx, |ui| {
                 build_about_window(ui);
@@ -834,21 +2122,67 @@ impl eframe::App for WowsToolkitApp {
                 let is_web = cfg!(target_arch = "wasm32");
                 if !is_web {
                     ui.menu_button("File", |ui| {
+                        if ui.add(egui::Button::new("Search...").shortcut_text("Ctrl+K")).clicked() {
+                            self.command_palette.show_palette(&ToolkitTabViewer { tab_state: &mut self.tab_state });
+                            ui.close_menu();
+                        }
+                        let locked = self.tab_state.is_locked();
+                        if ui.add_enabled(!locked, egui::Button::new("Setup Wizard...")).clicked() {
+                            self.setup_wizard = Some(SetupWizard::new());
+                            ui.close_menu();
+                        }
                         if ui.button("Check for Updates").clicked() {
                             self.checked_for_updates = false;
                             ui.close_menu();
                         }
+                        if ui.add_enabled(!locked, egui::Button::new("Health Check")).clicked() {
+                            let mut health_checks = HealthChecks::run(&self.tab_state.settings, &self.tab_state.connectivity);
+                            health_checks.open = true;
+                            self.health_checks = Some(health_checks);
+                            ui.close_menu();
+                        }
+                        if ui.button("Diagnostics").clicked() {
+                            self.diagnostics.open = true;
+                            ui.close_menu();
+                        }
                         if ui.button("About").clicked() {
                             self.show_about_window = true;
                             ui.close_menu();
                         }
                         if ui.button("Quit").clicked() {
+                            self.quitting = true;
                             ctx.send_viewport_cmd(egui::ViewportCommand::Close);
                         }
                     });
                     ui.add_space(16.0);
                 }
 
+                ui.menu_button("Edit", |ui| {
+                    if self.tab_state.is_locked() {
+                        ui.disable();
+                    }
+                    let undo_description = self.tab_state.undo_stack.undo_description();
+                    let can_undo = undo_description.is_some();
+                    let undo_text = undo_description
+                        .map(|description| format!("Undo {}", description))
+                        .unwrap_or_else(|| "Undo".to_string());
+                    if ui.add_enabled(can_undo, egui::Button::new(undo_text).shortcut_text("Ctrl+Z")).clicked() {
+                        self.tab_state.undo();
+                        ui.close_menu();
+                    }
+
+                    let redo_description = self.tab_state.undo_stack.redo_description();
+                    let can_redo = redo_description.is_some();
+                    let redo_text = redo_description
+                        .map(|description| format!("Redo {}", description))
+                        .unwrap_or_else(|| "Redo".to_string());
+                    if ui.add_enabled(can_redo, egui::Button::new(redo_text).shortcut_text("Ctrl+Y")).clicked() {
+                        self.tab_state.redo();
+                        ui.close_menu();
+                    }
+                });
+                ui.add_space(16.0);
+
                 if ui.button(format!("{} Create Issue", icons::BUG)).clicked() {
                     ui.ctx().open_url(OpenUrl::new_tab("https://github.com/landaire/wows-toolkit/issues/new/choose"));
                 }
@@ -883,9 +2217,33 @@ impl eframe::App for WowsToolkitApp {
             .enumerate()
             .filter_map(|(idx, viewer)| if !remove_viewers.contains(&idx) { Some(viewer) } else { None })
             .collect();
+
+        preview_files_being_dropped(ctx);
     }
 }
 
+/// Dims the window while files are dragged over it so users know they can be dropped anywhere
+fn is_url(replay: &str) -> bool {
+    replay.starts_with("http://") || replay.starts_with("https://")
+}
+
+fn preview_files_being_dropped(ctx: &egui::Context) {
+    if ctx.input(|i| i.raw.hovered_files.is_empty()) {
+        return;
+    }
+
+    let painter = ctx.layer_painter(egui::LayerId::new(egui::Order::Foreground, egui::Id::new("file_drop_target")));
+    let screen_rect = ctx.screen_rect();
+    painter.rect_filled(screen_rect, 0.0, Color32::from_black_alpha(192));
+    painter.text(
+        screen_rect.center(),
+        egui::Align2::CENTER_CENTER,
+        format!("{} Drop replays to open them", icons::FILE_ARROW_DOWN),
+        egui::TextStyle::Heading.resolve(&ctx.style()),
+        Color32::WHITE,
+    );
+}
+
 fn build_about_window(ui: &mut egui::Ui) {
     ui.vertical(|ui| {
         ui.label("Made by landaire.");
@@ -0,0 +1,104 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+};
+
+use sha2::{Digest, Sha256};
+
+use crate::{
+    app::{TabState, TimedMessage, ToolkitTabViewer},
+    icons,
+};
+
+const MIN_PIN_LEN: usize = 4;
+
+fn hash_pin(salt: &str, pin: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(salt.as_bytes());
+    hasher.update(pin.trim().as_bytes());
+    data_encoding::HEXLOWER.encode(hasher.finalize().as_slice())
+}
+
+/// 128 random bits. `RandomState` keys are seeded from the OS's random number generator, which saves pulling
+/// in a crate for it.
+fn new_salt() -> String {
+    let bytes: Vec<u8> = (0..2).flat_map(|_| RandomState::new().build_hasher().finish().to_le_bytes()).collect();
+    data_encoding::HEXLOWER.encode(&bytes)
+}
+
+/// Whether a PIN-protected app has been unlocked this session
+#[derive(Default)]
+pub struct LockState {
+    unlocked: bool,
+    pin_input: String,
+    wrong_pin: bool,
+}
+
+impl TabState {
+    /// Shared PCs can be locked with a PIN so replays can be viewed, but settings, tracker data and
+    /// tournament data can't be changed
+    pub fn is_locked(&self) -> bool {
+        self.settings.lock_pin_hash.is_some() && !self.lock.unlocked
+    }
+}
+
+impl ToolkitTabViewer<'_> {
+    /// Shown above anything the lock disables
+    pub fn build_unlock_prompt(&mut self, ui: &mut egui::Ui) {
+        let lock = &mut self.tab_state.lock;
+        ui.horizontal(|ui| {
+            ui.label(format!("{} Locked. Enter the PIN to make changes.", icons::LOCK));
+            let response = ui.add(egui::TextEdit::singleline(&mut lock.pin_input).password(true).desired_width(80.0));
+            let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            if ui.button("Unlock").clicked() || submitted {
+                let matches = self.tab_state.settings.lock_pin_hash.as_deref() == Some(hash_pin(&self.tab_state.settings.lock_pin_salt, &lock.pin_input).as_str());
+                lock.unlocked = matches;
+                lock.wrong_pin = !matches;
+                lock.pin_input.clear();
+            }
+            if lock.wrong_pin {
+                ui.colored_label(ui.visuals().error_fg_color, "Wrong PIN");
+            }
+        });
+    }
+
+    pub fn build_lock_settings(&mut self, ui: &mut egui::Ui) {
+        let lock = &mut self.tab_state.lock;
+        if self.tab_state.settings.lock_pin_hash.is_some() {
+            ui.horizontal(|ui| {
+                if ui
+                    .button(format!("{} Lock Now", icons::LOCK))
+                    .on_hover_text("Also locks automatically the next time the app starts")
+                    .clicked()
+                {
+                    lock.unlocked = false;
+                }
+                if ui.button("Remove PIN").clicked() {
+                    self.tab_state.settings.lock_pin_hash = None;
+                    *self.tab_state.timed_message.write() = Some(TimedMessage::new(format!("{} PIN removed", icons::LOCK_OPEN)));
+                }
+            });
+        } else {
+            ui.label("Set a PIN to stop settings, player tracker and tournament data from being changed on a shared PC. Replays can still be viewed.");
+            ui.horizontal(|ui| {
+                let label = ui.label("PIN");
+                ui.add(egui::TextEdit::singleline(&mut lock.pin_input).password(true).desired_width(80.0))
+                    .labelled_by(label.id);
+                let valid = lock.pin_input.trim().len() >= MIN_PIN_LEN;
+                if ui
+                    .add_enabled(valid, egui::Button::new("Set PIN"))
+                    .on_hover_text(format!("At least {} characters", MIN_PIN_LEN))
+                    .clicked()
+                {
+                    let salt = new_salt();
+                    self.tab_state.settings.lock_pin_hash = Some(hash_pin(&salt, &lock.pin_input));
+                    self.tab_state.settings.lock_pin_salt = salt;
+                    // Stay unlocked until the user locks it or restarts, so they can finish what they were doing
+                    lock.unlocked = true;
+                    lock.pin_input.clear();
+                    *self.tab_state.timed_message.write() = Some(TimedMessage::new(format!("{} PIN set", icons::LOCK)));
+                }
+            });
+        }
+    }
+}
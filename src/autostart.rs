@@ -0,0 +1,33 @@
+/// Passed by the login entry so the app can tell it was started at login
+pub const AUTOSTART_ARG: &str = "--autostart";
+
+/// Whether this process was started by the login entry rather than by the user
+pub fn launched_at_login() -> bool {
+    std::env::args().skip(1).any(|arg| arg == AUTOSTART_ARG)
+}
+
+/// Adds or removes this executable from the programs Windows starts when the current user logs in
+#[cfg(target_os = "windows")]
+pub fn set_enabled(enabled: bool) -> std::io::Result<()> {
+    use std::{os::windows::process::CommandExt, process::Command};
+
+    /// Keeps `reg` from flashing a console window, since the app has none
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+    const RUN_KEY: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run";
+
+    let mut reg = Command::new("reg");
+    reg.creation_flags(CREATE_NO_WINDOW);
+    if enabled {
+        let command = format!("\"{}\" {}", std::env::current_exe()?.display(), AUTOSTART_ARG);
+        reg.args(["add", RUN_KEY, "/v", crate::APP_NAME, "/d", command.as_str(), "/f"]);
+    } else {
+        reg.args(["delete", RUN_KEY, "/v", crate::APP_NAME, "/f"]);
+    }
+
+    let status = reg.status()?;
+    if !status.success() {
+        return Err(std::io::Error::new(std::io::ErrorKind::Other, format!("reg failed with {}", status)));
+    }
+
+    Ok(())
+}
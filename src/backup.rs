@@ -0,0 +1,248 @@
+use std::{
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use chrono::{DateTime, Duration, Local};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use tracing::{debug, error};
+
+use crate::{
+    app::{Settings, TabState, TimedMessage, ToolkitTabViewer},
+    error::ToolkitError,
+    icons,
+};
+
+pub(crate) const BACKUP_EXTENSION: &str = "json.gz";
+
+pub fn backups_dir() -> PathBuf {
+    let backups_dir = Path::new("backups");
+    if let Some(storage_dir) = eframe::storage_dir(crate::APP_NAME) {
+        storage_dir.join(backups_dir)
+    } else {
+        backups_dir.to_path_buf()
+    }
+}
+
+pub struct BackupInfo {
+    pub path: PathBuf,
+    pub created: DateTime<Local>,
+    pub size: u64,
+}
+
+/// Backups in the backups directory, newest first
+pub fn list_backups() -> Vec<BackupInfo> {
+    let Ok(read_dir) = std::fs::read_dir(backups_dir()) else {
+        return Vec::new();
+    };
+
+    let mut backups: Vec<BackupInfo> = read_dir
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().ends_with(BACKUP_EXTENSION))
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            Some(BackupInfo {
+                path: entry.path(),
+                created: metadata.modified().ok()?.into(),
+                size: metadata.len(),
+            })
+        })
+        .collect();
+    backups.sort_by(|a, b| b.created.cmp(&a.created));
+
+    backups
+}
+
+fn write_backup(data: &[u8], max_backups: usize) -> Result<PathBuf, ToolkitError> {
+    let dir = backups_dir();
+    std::fs::create_dir_all(&dir)?;
+
+    let path = dir.join(format!("settings-{}.{}", Local::now().format("%Y%m%d-%H%M%S"), BACKUP_EXTENSION));
+    let mut encoder = GzEncoder::new(std::fs::File::create(&path)?, Compression::default());
+    encoder.write_all(data)?;
+    encoder.finish()?;
+
+    for old_backup in list_backups().into_iter().skip(max_backups.max(1)) {
+        debug!("removing old backup {:?}", old_backup.path);
+        let _ = std::fs::remove_file(old_backup.path);
+    }
+
+    Ok(path)
+}
+
+/// Writes a backup of `settings`, including the player tracker, to `path` outside of the backups
+/// directory so it isn't rotated out
+pub fn export_backup(settings: &Settings, path: &Path) -> Result<(), ToolkitError> {
+    let data = serde_json::to_vec(settings).map_err(|e| ToolkitError::InvalidBackup(e.to_string()))?;
+    let mut encoder = GzEncoder::new(std::fs::File::create(path)?, Compression::default());
+    encoder.write_all(&data)?;
+    encoder.finish()?;
+
+    Ok(())
+}
+
+pub fn read_backup(path: &Path) -> Result<Settings, ToolkitError> {
+    let mut data = Vec::new();
+    GzDecoder::new(std::fs::File::open(path)?).read_to_end(&mut data)?;
+
+    serde_json::from_slice(&data).map_err(|e| ToolkitError::InvalidBackup(e.to_string()))
+}
+
+impl TabState {
+    /// Whether enough time has passed since the last automatic backup
+    pub fn is_backup_due(&self) -> bool {
+        // Backing up reset settings would rotate out the backups the user may want to restore
+        if self.offer_backup_recovery {
+            return false;
+        }
+
+        let interval = self.settings.backup_interval_hours;
+        interval > 0
+            && self
+                .settings
+                .last_backup
+                .map(|last_backup| Local::now() - last_backup > Duration::hours(interval as i64))
+                .unwrap_or(true)
+    }
+
+    /// Backs up the settings, including the player tracker, on a background thread
+    pub fn back_up_settings(&mut self) {
+        self.settings.last_backup = Some(Local::now());
+
+        let data = match serde_json::to_vec(&self.settings) {
+            Ok(data) => data,
+            Err(e) => {
+                error!("failed to serialize settings for backup: {:?}", e);
+                return;
+            }
+        };
+
+        let max_backups = self.settings.max_backups;
+        std::thread::spawn(move || match write_backup(&data, max_backups) {
+            Ok(path) => debug!("wrote backup to {:?}", path),
+            Err(e) => error!("failed to write backup: {:?}", e),
+        });
+    }
+
+    /// Replaces the current settings with a backup. The current settings are backed up first.
+    pub fn restore_backup(&mut self, path: &Path) -> Result<(), ToolkitError> {
+        let mut restored = read_backup(path)?;
+        self.back_up_settings();
+
+        // These are shared with background threads, so their contents are swapped in rather than the `Arc`s
+        let settings = &mut self.settings;
+        std::mem::swap(&mut *settings.player_tracker.write(), &mut *restored.player_tracker.write());
+        std::mem::swap(&mut *settings.sent_replays.write(), &mut *restored.sent_replays.write());
+        std::mem::swap(&mut *settings.event_bus.write(), &mut *restored.event_bus.write());
+        // Tournament submissions aren't part of the settings, so only the rules are restored
+        std::mem::swap(&mut settings.tournament.write().rules, &mut restored.tournament.write().rules);
+        restored.player_tracker = Arc::clone(&settings.player_tracker);
+        restored.sent_replays = Arc::clone(&settings.sent_replays);
+        restored.event_bus = Arc::clone(&settings.event_bus);
+        restored.tournament = Arc::clone(&settings.tournament);
+
+        // Keep the replays directory found from the game directory, and the backup and job schedules
+        restored.replays_dir = settings.replays_dir.take();
+        restored.last_backup = settings.last_backup;
+        restored.scheduled_jobs = std::mem::take(&mut settings.scheduled_jobs);
+
+        let wows_dir_changed = restored.wows_dir != settings.wows_dir;
+        *settings = restored;
+        self.settings_restored = true;
+
+        if wows_dir_changed && !self.settings.wows_dir.is_empty() {
+            let wows_dir = PathBuf::from(&self.settings.wows_dir);
+            self.prevent_changing_wows_dir();
+            crate::update_background_task!(self.background_task, Some(self.load_game_data(wows_dir)));
+        }
+
+        Ok(())
+    }
+
+    /// Offers to restore the latest backup if the saved settings couldn't be read
+    pub fn build_backup_recovery_window(&mut self, ctx: &egui::Context) {
+        if !self.offer_backup_recovery {
+            return;
+        }
+
+        let latest_backup = list_backups().into_iter().next();
+        egui::Window::new(format!("{} Settings Could Not Be Loaded", icons::WARNING))
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label("Your saved settings couldn't be read and have been reset.");
+                let Some(backup) = latest_backup else {
+                    ui.label("No backups were found.");
+                    if ui.button("OK").clicked() {
+                        self.offer_backup_recovery = false;
+                    }
+                    return;
+                };
+
+                ui.label(format!("A backup from {} is available.", backup.created.format("%Y-%m-%d %H:%M")));
+                ui.horizontal(|ui| {
+                    if ui.button(format!("{} Restore Backup", icons::ARROW_COUNTER_CLOCKWISE)).clicked() {
+                        if let Err(e) = self.restore_backup(&backup.path) {
+                            *self.timed_message.write() = Some(TimedMessage::new(format!("{} {}", icons::WARNING, e)));
+                        }
+                        self.offer_backup_recovery = false;
+                    }
+                    if ui.button("Start Fresh").clicked() {
+                        self.offer_backup_recovery = false;
+                    }
+                });
+            });
+    }
+}
+
+impl ToolkitTabViewer<'_> {
+    pub fn build_backup_settings(&mut self, ui: &mut egui::Ui) {
+        let settings = &mut self.tab_state.settings;
+        ui.horizontal(|ui| {
+            ui.label("Back Up Every (Hours)");
+            ui.add(egui::DragValue::new(&mut settings.backup_interval_hours).range(0..=24 * 30))
+                .on_hover_text("0 turns off automatic backups");
+        });
+        ui.horizontal(|ui| {
+            ui.label("Backups to Keep");
+            ui.add(egui::DragValue::new(&mut settings.max_backups).range(1..=100));
+        });
+        if let Some(last_backup) = settings.last_backup {
+            ui.label(format!("Last backup: {}", last_backup.format("%Y-%m-%d %H:%M")));
+        }
+
+        if ui.button(format!("{} Back Up Now", icons::FLOPPY_DISK)).clicked() {
+            self.tab_state.back_up_settings();
+            *self.tab_state.timed_message.write() = Some(TimedMessage::new(format!("{} Settings backed up", icons::CHECK_CIRCLE)));
+        }
+
+        let mut restore = None;
+        ui.collapsing("Restore", |ui| {
+            for backup in list_backups() {
+                ui.horizontal(|ui| {
+                    ui.label(format!(
+                        "{} ({})",
+                        backup.created.format("%Y-%m-%d %H:%M"),
+                        humansize::format_size(backup.size, humansize::DECIMAL)
+                    ));
+                    if ui
+                        .button(format!("{} Restore", icons::ARROW_COUNTER_CLOCKWISE))
+                        .on_hover_text("Replaces your current settings and player tracker. They're backed up first.")
+                        .clicked()
+                    {
+                        restore = Some(backup.path);
+                    }
+                });
+            }
+        });
+
+        if let Some(path) = restore {
+            let message = match self.tab_state.restore_backup(&path) {
+                Ok(()) => format!("{} Backup restored", icons::CHECK_CIRCLE),
+                Err(e) => format!("{} {}", icons::WARNING, e),
+            };
+            *self.tab_state.timed_message.write() = Some(TimedMessage::new(message));
+        }
+    }
+}
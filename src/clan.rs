@@ -0,0 +1,94 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{error::ToolkitError, region::Region};
+
+/// The user's own clan, used to highlight clanmates
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClanSettings {
+    pub tag: String,
+    /// Wargaming developer application ID used to look up the clan roster
+    pub application_id: String,
+    /// Account IDs of clan members as of the last roster sync
+    pub members: HashSet<i64>,
+    pub last_synced: Option<DateTime<Local>>,
+}
+
+impl ClanSettings {
+    /// Uses the synced roster when there is one, otherwise falls back to comparing clan tags
+    pub fn is_clanmate(&self, db_id: i64, clan_tag: &str) -> bool {
+        if !self.members.is_empty() {
+            return self.members.contains(&db_id);
+        }
+
+        let tag = self.tag.trim();
+        !tag.is_empty() && clan_tag.eq_ignore_ascii_case(tag)
+    }
+}
+
+fn api_data(response: Value) -> Result<Value, ToolkitError> {
+    if response.get("status").and_then(Value::as_str) != Some("ok") {
+        let message = response
+            .get("error")
+            .and_then(|error| error.get("message"))
+            .and_then(Value::as_str)
+            .unwrap_or("unknown error")
+            .to_string();
+        return Err(ToolkitError::ClanRosterError(message));
+    }
+
+    response
+        .get("data")
+        .cloned()
+        .ok_or_else(|| ToolkitError::ClanRosterError("response has no data".to_string()))
+}
+
+/// Looks up the clan with `tag` and returns the account IDs of its members
+pub fn fetch_roster(region: Region, application_id: &str, tag: &str) -> Result<HashSet<i64>, ToolkitError> {
+    let client = reqwest::blocking::Client::new();
+    let api = format!("https://{}/wows/clans", region.api_host());
+
+    let clans = api_data(
+        client
+            .get(format!("{}/list/", api))
+            .query(&[("application_id", application_id), ("search", tag), ("fields", "clan_id,tag")])
+            .send()?
+            .error_for_status()?
+            .json()?,
+    )?;
+    let clan_id = clans
+        .as_array()
+        .and_then(|clans| {
+            clans.iter().find(|clan| {
+                clan.get("tag")
+                    .and_then(Value::as_str)
+                    .map(|clan_tag| clan_tag.eq_ignore_ascii_case(tag))
+                    .unwrap_or(false)
+            })
+        })
+        .and_then(|clan| clan.get("clan_id").and_then(Value::as_i64))
+        .ok_or_else(|| ToolkitError::ClanRosterError(format!("no clan with the tag [{}] was found", tag)))?;
+
+    let clan_id_str = clan_id.to_string();
+    let info = api_data(
+        client
+            .get(format!("{}/info/", api))
+            .query(&[("application_id", application_id), ("clan_id", clan_id_str.as_str()), ("fields", "members_ids")])
+            .send()?
+            .error_for_status()?
+            .json()?,
+    )?;
+
+    let members = info
+        .get(&clan_id_str)
+        .and_then(|clan| clan.get("members_ids"))
+        .and_then(Value::as_array)
+        .map(|members| members.iter().filter_map(Value::as_i64).collect())
+        .unwrap_or_default();
+
+    Ok(members)
+}
@@ -0,0 +1,258 @@
+use serde::{Deserialize, Serialize};
+use wowsunpack::{
+    data::ResourceLoader,
+    game_params::{
+        provider::GameMetadataProvider,
+        types::{GameParamProvider, Species},
+    },
+};
+
+use crate::{app::ToolkitTabViewer, icons, replay_parser::Replay, ship_picker::ShipPicker, usage_stats, util};
+
+/// Ship tiers, from I to XI (superships)
+const TIERS: std::ops::RangeInclusive<u32> = 1..=11;
+
+pub(crate) const SHIP_CLASSES: [Species; 5] = [Species::Destroyer, Species::Cruiser, Species::Battleship, Species::AirCarrier, Species::Submarine];
+
+/// Localized details of a replay used to label it in the listing and match it against collections
+pub struct ReplaySummary {
+    pub ship_name: String,
+    /// Untranslated species name of the recording player's ship, e.g. `Destroyer`
+    pub ship_class: Option<&'static str>,
+    pub ship_tier: Option<u32>,
+    pub map_name: String,
+    pub scenario: String,
+    pub game_mode: String,
+    pub time: String,
+}
+
+impl ReplaySummary {
+    pub fn new(replay: &Replay, metadata_provider: &GameMetadataProvider) -> Self {
+        let meta = &replay.replay_file.meta;
+        let player_vehicle = meta.vehicles.iter().find(|vehicle| vehicle.relation == 0);
+        let ship_name = player_vehicle
+            .and_then(|vehicle| metadata_provider.param_localization_id(vehicle.shipId as u32))
+            .and_then(|id| metadata_provider.localized_name_from_id(id))
+            .unwrap_or_else(|| "Spectator".to_string());
+        let ship_param = player_vehicle.and_then(|vehicle| metadata_provider.game_param_by_id(vehicle.shipId as u32));
+        let ship_class = ship_param.as_ref().and_then(|param| param.species()).map(|species| species.into());
+        let ship_tier = ship_param.as_ref().and_then(|param| util::ship_tier(param));
+        let map_id = format!("IDS_{}", meta.mapName.to_uppercase());
+        let map_name = metadata_provider.localized_name_from_id(&map_id).unwrap_or_else(|| meta.mapName.clone());
+
+        let game_mode = metadata_provider
+            .localized_name_from_id(&format!("IDS_{}", meta.gameType.to_ascii_uppercase()))
+            .unwrap_or_else(|| meta.gameType.clone());
+        let scenario = metadata_provider
+            .localized_name_from_id(&format!("IDS_SCENARIO_{}", meta.scenario.to_ascii_uppercase()))
+            .unwrap_or_else(|| meta.scenario.clone());
+
+        Self {
+            ship_name,
+            ship_class,
+            ship_tier,
+            map_name,
+            scenario,
+            game_mode,
+            time: meta.dateTime.clone(),
+        }
+    }
+
+    pub fn label(&self) -> String {
+        [
+            self.ship_name.as_str(),
+            self.map_name.as_str(),
+            self.scenario.as_str(),
+            self.game_mode.as_str(),
+            self.time.as_str(),
+        ]
+        .join(" - ")
+    }
+}
+
+/// Criteria a replay must match to be in a collection. Empty text fields match everything.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReplayFilter {
+    pub ship_name: String,
+    pub ship_class: Option<String>,
+    pub min_tier: Option<u32>,
+    pub max_tier: Option<u32>,
+    pub map_name: String,
+    /// Matched against both the game type (e.g. Random Battle) and the scenario (e.g. Domination)
+    pub game_mode: String,
+}
+
+fn contains_ignore_case(haystack: &str, needle: &str) -> bool {
+    let needle = needle.trim();
+    needle.is_empty() || haystack.to_lowercase().contains(&needle.to_lowercase())
+}
+
+impl ReplayFilter {
+    pub fn matches(&self, summary: &ReplaySummary) -> bool {
+        let class_matches = match self.ship_class.as_deref() {
+            Some(class) => summary.ship_class == Some(class),
+            None => true,
+        };
+
+        // Replays whose ship isn't in the game data can't match a tier range
+        let tier_matches = match summary.ship_tier {
+            Some(tier) => self.min_tier.map(|min| tier >= min).unwrap_or(true) && self.max_tier.map(|max| tier <= max).unwrap_or(true),
+            None => self.min_tier.is_none() && self.max_tier.is_none(),
+        };
+
+        class_matches
+            && tier_matches
+            && contains_ignore_case(&summary.ship_name, &self.ship_name)
+            && contains_ignore_case(&summary.map_name, &self.map_name)
+            && (contains_ignore_case(&summary.game_mode, &self.game_mode) || contains_ignore_case(&summary.scenario, &self.game_mode))
+    }
+}
+
+fn tier_combo(ui: &mut egui::Ui, id_salt: &str, tier: &mut Option<u32>) {
+    egui::ComboBox::from_id_salt(id_salt)
+        .selected_text(tier.map(|tier| tier.to_string()).unwrap_or_else(|| "Any".to_string()))
+        .show_ui(ui, |ui| {
+            ui.selectable_value(tier, None, "Any");
+            for i in TIERS {
+                ui.selectable_value(tier, Some(i), i.to_string());
+            }
+        });
+}
+
+/// A saved filter shown as a virtual folder in the replay listing
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct ReplayCollection {
+    pub name: String,
+    pub filter: ReplayFilter,
+}
+
+/// State of the window used to create or edit a collection
+pub struct CollectionEditor {
+    /// Index of the collection being edited, or `None` for a new one
+    index: Option<usize>,
+    collection: ReplayCollection,
+}
+
+impl ToolkitTabViewer<'_> {
+    /// Lists the saved collections. Selecting one filters the replay listing to it.
+    pub fn build_collections_list(&mut self, ui: &mut egui::Ui, matching_counts: &[usize]) {
+        let locked = self.tab_state.is_locked();
+        let collections = &self.tab_state.settings.replay_collections;
+        let selected = &mut self.tab_state.selected_collection;
+
+        ui.horizontal(|ui| {
+            ui.strong("Collections");
+            if ui
+                .add_enabled(!locked, egui::Button::new(icons::PLUS).small())
+                .on_hover_text("New Collection")
+                .clicked()
+            {
+                self.tab_state.collection_editor = Some(CollectionEditor {
+                    index: None,
+                    collection: Default::default(),
+                });
+            }
+        });
+
+        ui.selectable_value(selected, None, format!("{} All Replays", icons::FOLDER));
+        let mut to_remove = None;
+        for (i, collection) in collections.iter().enumerate() {
+            let count = matching_counts.get(i).copied().unwrap_or_default();
+            let response = ui.selectable_value(selected, Some(i), format!("{} {} ({})", icons::FUNNEL, collection.name, count));
+            response.context_menu(|ui| {
+                if locked {
+                    ui.disable();
+                }
+                if ui.button("Edit...").clicked() {
+                    self.tab_state.collection_editor = Some(CollectionEditor {
+                        index: Some(i),
+                        collection: collection.clone(),
+                    });
+                    ui.close_menu();
+                }
+                if ui.button(format!("{} Delete", icons::TRASH)).clicked() {
+                    to_remove = Some(i);
+                    ui.close_menu();
+                }
+            });
+        }
+
+        if let Some(i) = to_remove {
+            self.tab_state.settings.replay_collections.remove(i);
+            self.tab_state.selected_collection = None;
+        }
+    }
+
+    pub fn build_collection_editor(&mut self, ctx: &egui::Context) {
+        let Some(editor) = self.tab_state.collection_editor.as_mut() else {
+            return;
+        };
+
+        let mut is_open = true;
+        let mut save = false;
+        let title = if editor.index.is_some() { "Edit Collection" } else { "New Collection" };
+        let wows_data = self.tab_state.world_of_warships_data.as_ref();
+        egui::Window::new(title).open(&mut is_open).collapsible(false).show(ctx, |ui| {
+            let collection = &mut editor.collection;
+            egui::Grid::new("collection_editor_grid").num_columns(2).show(ui, |ui| {
+                let label = ui.label("Name");
+                ui.text_edit_singleline(&mut collection.name).labelled_by(label.id);
+                ui.end_row();
+
+                let filter = &mut collection.filter;
+                let label = ui.label("Ship Name Contains");
+                ShipPicker::new("collection_ship_name", &mut filter.ship_name, wows_data)
+                    .show(ui)
+                    .labelled_by(label.id);
+                ui.end_row();
+
+                ui.label("Ship Class");
+                egui::ComboBox::from_id_salt("collection_ship_class")
+                    .selected_text(filter.ship_class.as_deref().unwrap_or("Any"))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut filter.ship_class, None, "Any");
+                        for species in SHIP_CLASSES {
+                            let name: &'static str = species.into();
+                            ui.selectable_value(&mut filter.ship_class, Some(name.to_string()), name);
+                        }
+                    });
+                ui.end_row();
+
+                ui.label("Tier");
+                ui.horizontal(|ui| {
+                    tier_combo(ui, "collection_min_tier", &mut filter.min_tier);
+                    ui.label("to");
+                    tier_combo(ui, "collection_max_tier", &mut filter.max_tier);
+                });
+                ui.end_row();
+
+                let label = ui.label("Map Contains");
+                ui.text_edit_singleline(&mut filter.map_name).labelled_by(label.id);
+                ui.end_row();
+
+                let label = ui.label("Mode Contains");
+                ui.text_edit_singleline(&mut filter.game_mode)
+                    .labelled_by(label.id)
+                    .on_hover_text("Matches the battle type, like Random Battle, or the mode, like Domination");
+                ui.end_row();
+            });
+
+            ui.label("Replays are added to the collection as they come in.");
+            save = ui.add_enabled(!collection.name.trim().is_empty(), egui::Button::new("Save")).clicked();
+        });
+
+        if save {
+            if let Some(editor) = self.tab_state.collection_editor.take() {
+                usage_stats::record_feature("Save replay collection");
+                let collections = &mut self.tab_state.settings.replay_collections;
+                match editor.index.and_then(|i| collections.get_mut(i)) {
+                    Some(existing) => *existing = editor.collection,
+                    None => collections.push(editor.collection),
+                }
+            }
+        } else if !is_open {
+            self.tab_state.collection_editor = None;
+        }
+    }
+}
@@ -0,0 +1,249 @@
+use std::sync::Arc;
+
+use parking_lot::RwLock;
+
+use crate::{
+    app::{Tab, ToolkitTabViewer},
+    collections::ReplaySummary,
+    icons,
+    replay_parser::Replay,
+};
+
+/// Most results shown at once. Narrowing the search shows the rest.
+const MAX_RESULTS: usize = 50;
+
+/// Groups on the settings tab, so searching for a setting finds the tab it's on
+const SETTINGS_SECTIONS: [&str; 13] = [
+    "Application Settings",
+    "World of Warships Settings",
+    "Replay Settings",
+    "Team Colors",
+    "Clan",
+    "Storage",
+    "Backups",
+    "Scheduled Jobs",
+    "Battle Event Webhooks",
+    "Usage Statistics",
+    "Lock",
+    "Region",
+    "Game Data Language",
+];
+
+/// Something the app does which can be run from the palette
+#[derive(Debug, Clone, Copy)]
+pub enum PaletteAction {
+    OpenReplayFile,
+    OpenSetupWizard,
+    RunHealthCheck,
+    ShowDiagnostics,
+    CheckForUpdates,
+    Undo,
+    Redo,
+}
+
+impl PaletteAction {
+    const ALL: [PaletteAction; 7] = [
+        PaletteAction::OpenReplayFile,
+        PaletteAction::OpenSetupWizard,
+        PaletteAction::RunHealthCheck,
+        PaletteAction::ShowDiagnostics,
+        PaletteAction::CheckForUpdates,
+        PaletteAction::Undo,
+        PaletteAction::Redo,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            PaletteAction::OpenReplayFile => "Open Replay File...",
+            PaletteAction::OpenSetupWizard => "Setup Wizard",
+            PaletteAction::RunHealthCheck => "Health Check",
+            PaletteAction::ShowDiagnostics => "Diagnostics",
+            PaletteAction::CheckForUpdates => "Check for Updates",
+            PaletteAction::Undo => "Undo",
+            PaletteAction::Redo => "Redo",
+        }
+    }
+}
+
+/// Where picking a search result takes the user
+#[derive(Clone)]
+pub enum PaletteTarget {
+    Tab(Tab),
+    Replay(Arc<RwLock<Replay>>),
+    /// A player in the player tracker, by account ID
+    Player(i64),
+    Action(PaletteAction),
+}
+
+struct PaletteEntry {
+    /// Icon and category shown in front of the label
+    kind: String,
+    label: String,
+    /// Lowercased text the search is matched against
+    search_text: String,
+    target: PaletteTarget,
+}
+
+impl PaletteEntry {
+    fn new(icon: &str, category: &str, label: String, target: PaletteTarget) -> Self {
+        Self {
+            kind: format!("{} {}", icon, category),
+            search_text: format!("{} {}", category, label).to_lowercase(),
+            label,
+            target,
+        }
+    }
+
+    /// Every word of the query has to appear somewhere in the entry
+    fn matches(&self, query: &str) -> bool {
+        query.split_whitespace().all(|word| self.search_text.contains(word))
+    }
+}
+
+/// Ctrl+K search across replays, tracked players, settings, tabs and actions
+#[derive(Default)]
+pub struct CommandPalette {
+    pub open: bool,
+    query: String,
+    selected: usize,
+    entries: Vec<PaletteEntry>,
+}
+
+impl CommandPalette {
+    /// Opens the palette, gathering what can be searched as of now
+    pub fn show_palette(&mut self, viewer: &ToolkitTabViewer<'_>) {
+        self.open = true;
+        self.query.clear();
+        self.selected = 0;
+        self.entries = viewer.command_palette_entries();
+    }
+
+    /// Returns the result the user picked, if any
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<PaletteTarget> {
+        if !self.open {
+            return None;
+        }
+
+        let query = self.query.to_lowercase();
+        let results: Vec<&PaletteEntry> = self.entries.iter().filter(|entry| entry.matches(&query)).take(MAX_RESULTS).collect();
+        self.selected = self.selected.min(results.len().saturating_sub(1));
+
+        let (up, down, enter, escape) = ctx.input_mut(|input| {
+            (
+                input.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+                input.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+                input.consume_key(egui::Modifiers::NONE, egui::Key::Enter),
+                input.consume_key(egui::Modifiers::NONE, egui::Key::Escape),
+            )
+        });
+        if up {
+            self.selected = self.selected.saturating_sub(1);
+        }
+        if down && self.selected + 1 < results.len() {
+            self.selected += 1;
+        }
+
+        let mut picked = enter.then(|| results.get(self.selected).map(|entry| entry.target.clone())).flatten();
+        let mut new_query = self.query.clone();
+        egui::Window::new("Search")
+            .title_bar(false)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_TOP, [0.0, 80.0])
+            .fixed_size([500.0, 0.0])
+            .show(ctx, |ui| {
+                ui.add(
+                    egui::TextEdit::singleline(&mut new_query)
+                        .hint_text("Search replays, players, settings and actions")
+                        .desired_width(f32::INFINITY),
+                )
+                .request_focus();
+                ui.separator();
+
+                if results.is_empty() {
+                    ui.weak("No results");
+                    return;
+                }
+                egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                    for (i, entry) in results.iter().enumerate() {
+                        let response = ui
+                            .horizontal(|ui| {
+                                ui.add_sized([130.0, 18.0], egui::Label::new(egui::RichText::new(entry.kind.as_str()).weak()));
+                                ui.selectable_label(i == self.selected, entry.label.as_str())
+                            })
+                            .inner;
+                        if i == self.selected && (up || down) {
+                            response.scroll_to_me(None);
+                        }
+                        if response.clicked() {
+                            picked = Some(entry.target.clone());
+                        }
+                    }
+                });
+            });
+
+        if new_query != self.query {
+            self.query = new_query;
+            self.selected = 0;
+        }
+        if picked.is_some() || escape {
+            self.open = false;
+            self.entries.clear();
+        }
+
+        picked
+    }
+}
+
+impl ToolkitTabViewer<'_> {
+    fn command_palette_entries(&self) -> Vec<PaletteEntry> {
+        let mut entries: Vec<PaletteEntry> = Vec::new();
+
+        for action in PaletteAction::ALL {
+            entries.push(PaletteEntry::new(
+                icons::LIGHTNING,
+                "Action",
+                action.label().to_string(),
+                PaletteTarget::Action(action),
+            ));
+        }
+        for tab in Tab::all() {
+            if !self.tab_state.settings.hidden_tabs.contains(&tab) {
+                entries.push(PaletteEntry::new(icons::BROWSERS, "Tab", tab.title(), PaletteTarget::Tab(tab)));
+            }
+        }
+        for section in SETTINGS_SECTIONS {
+            entries.push(PaletteEntry::new(icons::GEAR_FINE, "Setting", section.to_string(), PaletteTarget::Tab(Tab::Settings)));
+        }
+
+        {
+            let player_tracker = self.tab_state.settings.player_tracker.read();
+            for player in player_tracker.tracked_players() {
+                let label = if player.clan().is_empty() {
+                    player.last_name().to_string()
+                } else {
+                    format!("[{}] {}", player.clan(), player.last_name())
+                };
+                entries.push(PaletteEntry::new(icons::DETECTIVE, "Player", label, PaletteTarget::Player(player.db_id())));
+            }
+        }
+
+        let metadata_provider = self
+            .tab_state
+            .world_of_warships_data
+            .as_ref()
+            .and_then(|wows_data| wows_data.read().game_metadata.clone());
+        if let (Some(replay_files), Some(metadata_provider)) = (self.tab_state.replay_files.as_ref(), metadata_provider) {
+            let mut replays: Vec<_> = replay_files.iter().collect();
+            // Newest first, like the listing
+            replays.sort_by(|a, b| b.0.cmp(a.0));
+            for (path, replay) in replays {
+                let summary = ReplaySummary::new(&replay.read(), &metadata_provider);
+                let (label, _) = self.replay_listing_label(path, summary.label());
+                entries.push(PaletteEntry::new(icons::FILM_SLATE, "Replay", label, PaletteTarget::Replay(Arc::clone(replay))));
+            }
+        }
+
+        entries
+    }
+}
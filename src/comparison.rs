@@ -0,0 +1,333 @@
+use std::{collections::BTreeMap, fmt::Write, path::PathBuf, sync::Arc};
+
+use itertools::Itertools;
+use parking_lot::RwLock;
+use wowsunpack::data::ResourceLoader;
+
+use crate::{
+    app::{TimedMessage, ToolkitTabViewer},
+    collections::ReplaySummary,
+    expected_values::ExpectedValues,
+    icons,
+    replay_parser::{Replay, DAMAGE_INDEX, SPOTTING_DAMAGE_INDEX, XP_INDEX},
+    scouting::results_value,
+    task,
+};
+
+/// Battles with less than this share of the average damage are called out as poor games
+const POOR_BATTLE_RATIO: f64 = 0.5;
+/// Maps where the average damage is below this share of the overall average are called out
+const WEAK_MAP_RATIO: f64 = 0.75;
+
+/// The recording player's results from one battle
+pub struct ComparedBattle {
+    pub date: String,
+    pub map: String,
+    pub game_mode: String,
+    pub damage: i64,
+    pub spotting_damage: i64,
+    pub base_xp: i64,
+    /// PR-like rating, if expected values have been imported
+    pub rating: Option<i64>,
+}
+
+/// The recording player's results across several battles in the same ship
+#[derive(Default)]
+pub struct ShipComparison {
+    pub ship_name: String,
+    pub battles: Vec<ComparedBattle>,
+    /// Replays which didn't have results, e.g. because the recording player left early
+    pub skipped: usize,
+    expected_values: Option<Arc<ExpectedValues>>,
+}
+
+fn average(values: impl Iterator<Item = i64>) -> i64 {
+    let (sum, count) = values.fold((0, 0), |(sum, count), value| (sum + value, count + 1));
+    sum / count.max(1)
+}
+
+impl ShipComparison {
+    pub fn new(ship_name: String, expected_values: Option<Arc<ExpectedValues>>) -> Self {
+        Self {
+            ship_name,
+            expected_values,
+            ..Default::default()
+        }
+    }
+
+    pub fn add_battle(&mut self, replay: &Replay) {
+        let Some(report) = replay.battle_report.as_ref() else {
+            return;
+        };
+
+        let recording_player = report
+            .player_entities()
+            .iter()
+            .find(|entity| entity.player().map(|player| player.relation() == 0).unwrap_or(false));
+        let Some((entity, results)) = recording_player.and_then(|entity| Some((entity, entity.results_info()?))) else {
+            self.skipped += 1;
+            return;
+        };
+
+        let damage = results_value(Some(results), DAMAGE_INDEX).unwrap_or_default();
+        let rating = self.expected_values.as_ref().and_then(|expected_values| {
+            let ship_id = entity.player()?.vehicle().id().to_string();
+            expected_values.battle_rating(&ship_id, damage).map(|rating| rating.round() as i64)
+        });
+
+        self.battles.push(ComparedBattle {
+            date: replay.replay_file.meta.dateTime.clone(),
+            map: report.map_name().to_string(),
+            game_mode: report.game_mode().to_string(),
+            damage,
+            spotting_damage: results_value(Some(results), SPOTTING_DAMAGE_INDEX).unwrap_or_default(),
+            base_xp: results_value(Some(results), XP_INDEX).unwrap_or_default(),
+            rating,
+        });
+    }
+
+    /// Patterns which hold across several battles, phrased for someone reviewing their own play
+    fn observations(&self) -> Vec<String> {
+        let mut observations = Vec::new();
+        let average_damage = average(self.battles.iter().map(|battle| battle.damage));
+        if self.battles.len() < 2 || average_damage == 0 {
+            return observations;
+        }
+
+        let poor_battles = self
+            .battles
+            .iter()
+            .filter(|battle| (battle.damage as f64) < average_damage as f64 * POOR_BATTLE_RATIO)
+            .count();
+        if poor_battles > 0 {
+            observations.push(format!(
+                "{} of {} battles ended with less than half of your average damage",
+                poor_battles,
+                self.battles.len()
+            ));
+        }
+
+        let by_map: BTreeMap<&str, Vec<&ComparedBattle>> = self.battles.iter().fold(BTreeMap::new(), |mut by_map, battle| {
+            by_map.entry(battle.map.as_str()).or_default().push(battle);
+            by_map
+        });
+        for (map, battles) in by_map {
+            let map_damage = average(battles.iter().map(|battle| battle.damage));
+            if battles.len() >= 2 && (map_damage as f64) < average_damage as f64 * WEAK_MAP_RATIO {
+                observations.push(format!(
+                    "Damage on {} averages {} across {} battles, well below your overall {}",
+                    map,
+                    map_damage,
+                    battles.len(),
+                    average_damage
+                ));
+            }
+        }
+
+        let spotting_battles = self.battles.iter().filter(|battle| battle.spotting_damage > 0).count();
+        if spotting_battles * 2 < self.battles.len() {
+            observations.push(format!("You spotted for damage in only {} of {} battles", spotting_battles, self.battles.len()));
+        }
+
+        observations
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut md = String::new();
+        let _ = writeln!(md, "# {} Comparison\n", self.ship_name);
+        let _ = writeln!(md, "Based on {} battles.", self.battles.len());
+        if self.skipped > 0 {
+            let _ = writeln!(md, "{} replays were skipped because they have no results.", self.skipped);
+        }
+
+        let _ = writeln!(md, "\n## Averages\n");
+        let _ = writeln!(md, "- Damage: {}", average(self.battles.iter().map(|battle| battle.damage)));
+        let _ = writeln!(md, "- Spotting Damage: {}", average(self.battles.iter().map(|battle| battle.spotting_damage)));
+        let _ = writeln!(md, "- Base XP: {}", average(self.battles.iter().map(|battle| battle.base_xp)));
+        let has_ratings = self.battles.iter().any(|battle| battle.rating.is_some());
+        if has_ratings {
+            let _ = writeln!(md, "- Rating: {}", average(self.battles.iter().filter_map(|battle| battle.rating)));
+        }
+
+        let observations = self.observations();
+        if !observations.is_empty() {
+            let _ = writeln!(md, "\n## Patterns\n");
+            for observation in observations {
+                let _ = writeln!(md, "- {}", observation);
+            }
+        }
+
+        let _ = writeln!(md, "\n## Battles\n");
+        let _ = write!(md, "| Date | Map | Mode | Damage | Spotting Damage | Base XP |");
+        let _ = writeln!(md, "{}", if has_ratings { " Rating |" } else { "" });
+        let _ = writeln!(md, "|---|---|---|---|---|---|{}", if has_ratings { "---|" } else { "" });
+        for battle in self.battles.iter().sorted_by(|a, b| a.date.cmp(&b.date)) {
+            let _ = write!(
+                md,
+                "| {} | {} | {} | {} | {} | {} |",
+                battle.date, battle.map, battle.game_mode, battle.damage, battle.spotting_damage, battle.base_xp
+            );
+            if has_ratings {
+                let rating = battle.rating.map(|rating| rating.to_string()).unwrap_or_else(|| "-".to_string());
+                let _ = write!(md, " {} |", rating);
+            }
+            let _ = writeln!(md);
+        }
+
+        md
+    }
+}
+
+/// A replay which can be included in a comparison
+pub struct ComparisonCandidate {
+    pub path: PathBuf,
+    pub label: String,
+    pub selected: bool,
+}
+
+/// State of the ship comparison window
+#[derive(Default)]
+pub struct ComparisonWindow {
+    pub ship_name: String,
+    /// Replays in the same ship to pick from before comparing
+    pub candidates: Vec<ComparisonCandidate>,
+    /// Set once the selected replays have been sent off to be compared
+    pub comparing: bool,
+    pub markdown: Option<String>,
+}
+
+impl ToolkitTabViewer<'_> {
+    /// Opens the comparison window to pick which replays in the listing, where the recording player was in the same
+    /// ship as `replay`, should be compared
+    pub fn start_ship_comparison(&mut self, replay: &RwLock<Replay>) {
+        let (ship_id, ship_name) = {
+            let replay = replay.read();
+            let meta = &replay.replay_file.meta;
+            let Some(ship_id) = meta.vehicles.iter().find(|vehicle| vehicle.relation == 0).map(|vehicle| vehicle.shipId) else {
+                *self.tab_state.timed_message.write() = Some(TimedMessage::new(format!("{} Spectated replays can't be compared", icons::WARNING)));
+                return;
+            };
+            let ship_name = replay
+                .resource_loader
+                .param_localization_id(ship_id as u32)
+                .and_then(|id| replay.resource_loader.localized_name_from_id(id))
+                .unwrap_or_else(|| ship_id.to_string());
+            (ship_id, ship_name)
+        };
+
+        let candidates: Vec<ComparisonCandidate> = self
+            .tab_state
+            .replay_files
+            .iter()
+            .flatten()
+            .filter_map(|(path, other)| {
+                let other = other.read();
+                let same_ship = other
+                    .replay_file
+                    .meta
+                    .vehicles
+                    .iter()
+                    .any(|vehicle| vehicle.relation == 0 && vehicle.shipId == ship_id);
+                if !same_ship {
+                    return None;
+                }
+
+                let summary = ReplaySummary::new(&other, &other.resource_loader);
+                Some(ComparisonCandidate {
+                    path: path.clone(),
+                    label: format!("{} - {} - {}", summary.time, summary.map_name, summary.game_mode),
+                    selected: true,
+                })
+            })
+            .collect();
+
+        self.tab_state.comparison_window = Some(ComparisonWindow {
+            ship_name,
+            candidates,
+            ..Default::default()
+        });
+    }
+
+    fn compare_selected_replays(&mut self) {
+        let (Some(window), Some(wows_data)) = (self.tab_state.comparison_window.as_mut(), self.tab_state.world_of_warships_data.as_ref()) else {
+            return;
+        };
+
+        let replays: Vec<PathBuf> = window
+            .candidates
+            .iter()
+            .filter(|candidate| candidate.selected)
+            .map(|candidate| candidate.path.clone())
+            .collect();
+        window.comparing = true;
+        crate::update_background_task!(
+            self.tab_state.background_task,
+            Some(task::start_building_ship_comparison(
+                window.ship_name.clone(),
+                replays,
+                Arc::clone(wows_data),
+                self.tab_state.expected_values.clone()
+            ))
+        );
+    }
+
+    pub fn build_comparison_window(&mut self, ctx: &egui::Context) {
+        let Some(window) = self.tab_state.comparison_window.as_mut() else {
+            return;
+        };
+
+        let mut is_open = true;
+        let mut compare = false;
+        egui::Window::new(format!("{} {} Comparison", icons::SCALES, window.ship_name))
+            .open(&mut is_open)
+            .default_size((600.0, 500.0))
+            .show(ctx, |ui| {
+                if !window.comparing {
+                    let selected = window.candidates.iter().filter(|candidate| candidate.selected).count();
+                    ui.horizontal(|ui| {
+                        if ui.button("Select All").clicked() {
+                            window.candidates.iter_mut().for_each(|candidate| candidate.selected = true);
+                        }
+                        if ui.button("Select None").clicked() {
+                            window.candidates.iter_mut().for_each(|candidate| candidate.selected = false);
+                        }
+                        compare = ui.add_enabled(selected > 0, egui::Button::new(format!("Compare {} Battles", selected))).clicked();
+                    });
+                    egui::ScrollArea::vertical().show(ui, |ui| {
+                        for candidate in &mut window.candidates {
+                            ui.checkbox(&mut candidate.selected, candidate.label.as_str());
+                        }
+                    });
+                    return;
+                }
+
+                let Some(markdown) = window.markdown.as_mut() else {
+                    ui.label("Comparing battles...");
+                    return;
+                };
+
+                ui.horizontal(|ui| {
+                    if ui.button(format!("{} Copy Markdown", icons::COPY)).clicked() {
+                        ui.output_mut(|output| output.copied_text = markdown.clone());
+                    }
+                    if ui.button(format!("{} Save Markdown...", icons::FLOPPY_DISK)).clicked() {
+                        let file_name = format!("{} comparison.md", window.ship_name);
+                        if let Some(path) = rfd::FileDialog::new().add_filter("Markdown", &["md"]).set_file_name(file_name).save_file() {
+                            let result = std::fs::write(&path, markdown.as_bytes());
+                            *self.tab_state.timed_message.write() = Some(TimedMessage::file_exported(path, result));
+                        }
+                    }
+                });
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    ui.add(egui::TextEdit::multiline(markdown).code_editor().desired_width(f32::INFINITY));
+                });
+            });
+
+        if compare {
+            self.compare_selected_replays();
+        }
+        if !is_open {
+            self.tab_state.comparison_window = None;
+        }
+    }
+}
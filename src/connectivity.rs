@@ -0,0 +1,75 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use parking_lot::Mutex;
+use tracing::debug;
+
+/// Host used to check whether the internet can be reached. Update checks go here, so it's a good proxy for the rest.
+const CHECK_URL: &str = "https://api.github.com";
+
+/// How often the connection is checked again while offline
+const RECHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Makes a small request to see if the internet can be reached
+pub fn check_connection() -> Result<(), String> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(5))
+        .user_agent(crate::APP_NAME)
+        .build()
+        .map_err(|e| e.to_string())?;
+    client.head(CHECK_URL).send().map(|_| ()).map_err(|e| e.to_string())
+}
+
+/// Whether the app is offline. Shared with background threads so they can skip network requests
+/// instead of failing them over and over.
+#[derive(Clone, Default)]
+pub struct Connectivity {
+    offline: Arc<AtomicBool>,
+    last_checked: Arc<Mutex<Option<Instant>>>,
+}
+
+impl Connectivity {
+    pub fn is_offline(&self) -> bool {
+        self.offline.load(Ordering::Relaxed)
+    }
+
+    pub fn set_offline(&self, offline: bool) {
+        if self.offline.swap(offline, Ordering::Relaxed) != offline {
+            debug!("connectivity changed, offline: {}", offline);
+        }
+        *self.last_checked.lock() = Some(Instant::now());
+    }
+
+    /// Marks the app offline if `error` happened because the server couldn't be reached
+    pub fn record_error(&self, error: &reqwest::Error) {
+        if error.is_connect() || error.is_timeout() {
+            self.set_offline(true);
+        }
+    }
+
+    /// Checks the connection on a background thread
+    pub fn check_now(&self) {
+        *self.last_checked.lock() = Some(Instant::now());
+        let this = self.clone();
+        std::thread::spawn(move || {
+            this.set_offline(check_connection().is_err());
+        });
+    }
+
+    /// Checks the connection again if offline and it hasn't been checked recently
+    pub fn recheck_if_offline(&self) {
+        let checked_recently = self
+            .last_checked
+            .lock()
+            .map(|last_checked| last_checked.elapsed() < RECHECK_INTERVAL)
+            .unwrap_or(false);
+        if self.is_offline() && !checked_recently {
+            self.check_now();
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use wows_replays::analyzer::battle_controller::VehicleEntity;
+
+use crate::{
+    replay_parser::{AIRSTRIKE_POTENTIAL_DAMAGE, ARTILLERY_POTENTIAL_DAMAGE, DAMAGE_INDEX, SPOTTING_DAMAGE_INDEX},
+    scouting::results_value,
+};
+
+/// How much each stat counts towards a player's contribution score. Stats are compared against the
+/// average of players in the same ship class in that battle, so a player with class-average stats scores 100.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ContributionWeights {
+    pub damage: f64,
+    pub spotting_damage: f64,
+    /// Potential damage, i.e. how much damage the player drew fire for
+    pub tanking: f64,
+    pub survival: f64,
+}
+
+impl Default for ContributionWeights {
+    fn default() -> Self {
+        Self {
+            damage: 1.0,
+            spotting_damage: 0.5,
+            tanking: 0.3,
+            survival: 0.2,
+        }
+    }
+}
+
+impl ContributionWeights {
+    pub fn build_settings(&mut self, ui: &mut egui::Ui) {
+        egui::Grid::new("contribution_weights_grid").num_columns(2).show(ui, |ui| {
+            for (label, weight) in [
+                ("Damage", &mut self.damage),
+                ("Spotting Damage", &mut self.spotting_damage),
+                ("Tanking", &mut self.tanking),
+                ("Survival", &mut self.survival),
+            ] {
+                ui.label(label);
+                ui.add(egui::DragValue::new(weight).speed(0.05).range(0.0..=10.0));
+                ui.end_row();
+            }
+        });
+        if ui.button("Reset to Defaults").clicked() {
+            *self = Default::default();
+        }
+    }
+}
+
+/// Raw stats which go into the contribution score
+struct ContributionStats {
+    damage: f64,
+    spotting_damage: f64,
+    tanking: f64,
+    /// `None` if the player survived
+    time_lived: Option<f64>,
+}
+
+impl ContributionStats {
+    fn new(entity: &VehicleEntity) -> Option<Self> {
+        let info = entity.results_info();
+        let damage = results_value(info, DAMAGE_INDEX)?;
+        let tanking = info
+            .and_then(|info| info.as_array())
+            .and_then(|info| info.get(ARTILLERY_POTENTIAL_DAMAGE..=AIRSTRIKE_POTENTIAL_DAMAGE))
+            .map(|values| values.iter().filter_map(|value| value.as_f64()).sum())
+            .unwrap_or_default();
+
+        Some(Self {
+            damage: damage as f64,
+            spotting_damage: results_value(info, SPOTTING_DAMAGE_INDEX).unwrap_or_default() as f64,
+            tanking,
+            time_lived: entity.death_info().map(|death_info| death_info.time_lived().as_secs_f64()),
+        })
+    }
+}
+
+fn ratio(value: f64, average: f64) -> f64 {
+    if average > 0.0 {
+        value / average
+    } else {
+        0.0
+    }
+}
+
+/// Contribution score of each entity, in the same order as `entities`. Players without battle
+/// results (e.g. the recording player left early) have no score.
+pub fn contribution_scores(entities: &[VehicleEntity], weights: &ContributionWeights) -> Vec<Option<f64>> {
+    let stats: Vec<Option<ContributionStats>> = entities.iter().map(ContributionStats::new).collect();
+
+    // Survivors lived as long as the longest-lived player who didn't
+    let battle_length = stats.iter().flatten().filter_map(|stats| stats.time_lived).fold(0.0, f64::max);
+    let survival = |stats: &ContributionStats| stats.time_lived.unwrap_or(battle_length);
+
+    let class_of = |entity: &VehicleEntity| -> Option<&'static str> { entity.player().and_then(|player| player.vehicle().species()).map(|species| species.into()) };
+    let mut class_averages: HashMap<Option<&'static str>, [f64; 4]> = HashMap::new();
+    let mut class_counts: HashMap<Option<&'static str>, usize> = HashMap::new();
+    for (entity, stats) in entities.iter().zip(&stats) {
+        let Some(stats) = stats else {
+            continue;
+        };
+        let class = class_of(entity);
+        let totals = class_averages.entry(class).or_default();
+        totals[0] += stats.damage;
+        totals[1] += stats.spotting_damage;
+        totals[2] += stats.tanking;
+        totals[3] += survival(stats);
+        *class_counts.entry(class).or_default() += 1;
+    }
+    for (class, totals) in class_averages.iter_mut() {
+        let count = class_counts[class] as f64;
+        totals.iter_mut().for_each(|total| *total /= count);
+    }
+
+    let total_weight = weights.damage + weights.spotting_damage + weights.tanking + weights.survival;
+    entities
+        .iter()
+        .zip(&stats)
+        .map(|(entity, stats)| {
+            let stats = stats.as_ref()?;
+            if total_weight <= 0.0 {
+                return Some(0.0);
+            }
+
+            let averages = class_averages[&class_of(entity)];
+            let weighted = weights.damage * ratio(stats.damage, averages[0])
+                + weights.spotting_damage * ratio(stats.spotting_damage, averages[1])
+                + weights.tanking * ratio(stats.tanking, averages[2])
+                + weights.survival * ratio(survival(stats), averages[3]);
+
+            Some(100.0 * weighted / total_weight)
+        })
+        .collect()
+}
+
+/// Index of the player with the highest contribution score
+pub fn battle_mvp(scores: &[Option<f64>]) -> Option<usize> {
+    scores
+        .iter()
+        .enumerate()
+        .filter_map(|(i, score)| score.map(|score| (i, score)))
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(i, _)| i)
+}
@@ -0,0 +1,120 @@
+use std::{
+    backtrace::Backtrace,
+    fmt::Write,
+    path::{Path, PathBuf},
+};
+
+use parking_lot::Mutex;
+use reqwest::Url;
+use serde::Serialize;
+use tracing::error;
+
+use crate::usage_stats;
+
+/// Game version of the currently loaded game data, included in crash logs
+static GAME_VERSION: Mutex<Option<String>> = Mutex::new(None);
+
+/// GitHub rejects issue URLs much longer than this
+const MAX_ISSUE_BODY_LEN: usize = 6000;
+
+pub fn crash_log_path() -> PathBuf {
+    let crash_log_path = Path::new("crash.log");
+    if let Some(storage_dir) = eframe::storage_dir(crate::APP_NAME) {
+        storage_dir.join(crash_log_path)
+    } else {
+        crash_log_path.to_path_buf()
+    }
+}
+
+pub fn set_game_version(version: String) {
+    *GAME_VERSION.lock() = Some(version);
+}
+
+fn environment_summary() -> String {
+    format!(
+        "App version: {}\nGame version: {}\nOS: {} ({})\n",
+        env!("CARGO_PKG_VERSION"),
+        GAME_VERSION.lock().as_deref().unwrap_or("not loaded"),
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    )
+}
+
+/// Writes panics to [crash_log_path] so that they can be reported on the next launch
+pub fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let mut log = String::new();
+        let _ = writeln!(log, "Time: {}", chrono::Local::now().to_rfc3339());
+        log.push_str(&environment_summary());
+        let _ = writeln!(log, "\n{}\n\n{}", info, Backtrace::force_capture());
+
+        let path = crash_log_path();
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        let _ = std::fs::write(path, log);
+        usage_stats::record_panic();
+
+        default_hook(info);
+    }));
+}
+
+/// Returns the crash log left behind by a previous run, if any
+pub fn pending_crash_log() -> Option<String> {
+    std::fs::read_to_string(crash_log_path()).ok().filter(|log| !log.trim().is_empty())
+}
+
+pub fn discard_crash_log() {
+    let _ = std::fs::remove_file(crash_log_path());
+}
+
+pub fn github_issue_url(log: &str) -> Url {
+    let mut body = format!("**Describe what you were doing when the crash occurred:**\n\n\n**Crash log:**\n```\n{}", log);
+    if body.len() > MAX_ISSUE_BODY_LEN {
+        let mut end = MAX_ISSUE_BODY_LEN;
+        while !body.is_char_boundary(end) {
+            end -= 1;
+        }
+        body.truncate(end);
+        body.push_str("\n(truncated)");
+    }
+    body.push_str("\n```");
+
+    let mut url = Url::parse("https://github.com/landaire/wows-toolkit/issues/new").expect("issue URL is valid");
+    url.query_pairs_mut()
+        .append_pair("title", "Crash report")
+        .append_pair("labels", "bug")
+        .append_pair("body", &body);
+
+    url
+}
+
+#[derive(Serialize)]
+struct CrashReportPayload<'a> {
+    app_version: &'static str,
+    os: &'static str,
+    arch: &'static str,
+    log: &'a str,
+}
+
+/// Submits `log` to `endpoint` on a background thread
+pub fn submit_to_endpoint(endpoint: String, log: String) {
+    let _join_handle = std::thread::spawn(move || {
+        let payload = CrashReportPayload {
+            app_version: env!("CARGO_PKG_VERSION"),
+            os: std::env::consts::OS,
+            arch: std::env::consts::ARCH,
+            log: &log,
+        };
+
+        let result = reqwest::blocking::Client::new()
+            .post(&endpoint)
+            .json(&payload)
+            .send()
+            .and_then(|res| res.error_for_status());
+        if let Err(e) = result {
+            error!("failed to submit crash report to {}: {:?}", endpoint, e);
+        }
+    });
+}
@@ -0,0 +1,178 @@
+use std::{collections::VecDeque, time::Instant};
+
+use egui_extras::{Column, TableBuilder};
+
+use crate::{
+    app::TabState,
+    game_params::game_params_bin_path,
+    icons,
+    task::{BackgroundTask, TaskTiming},
+};
+
+/// How many frames the frame time statistics are computed over
+const FRAME_HISTORY_LEN: usize = 120;
+/// How many completed background tasks are remembered
+const TASK_HISTORY_LEN: usize = 20;
+
+struct CompletedTask {
+    name: &'static str,
+    /// `None` if the task's thread went away without reporting its timing
+    timing: Option<TaskTiming>,
+}
+
+/// Tracks frame times and background task durations for the diagnostics window
+#[derive(Default)]
+pub struct Diagnostics {
+    pub open: bool,
+    frame_times: VecDeque<f32>,
+    /// ID, name, and when the UI first saw the task
+    running_task: Option<(u64, &'static str, Instant)>,
+    completed_tasks: VecDeque<CompletedTask>,
+}
+
+impl Diagnostics {
+    /// Records this frame's statistics. Must be called once per frame.
+    pub fn record_frame(&mut self, ctx: &egui::Context, tab_state: &TabState) {
+        let frame_time = ctx.input(|i| i.unstable_dt);
+        if self.frame_times.len() == FRAME_HISTORY_LEN {
+            self.frame_times.pop_front();
+        }
+        self.frame_times.push_back(frame_time);
+
+        let current_task = tab_state.background_task.as_ref().map(|task| (task.id, task.kind.name()));
+        if self.running_task.map(|(id, _, _)| id) != current_task.map(|(id, _)| id) {
+            self.running_task = current_task.map(|(id, name)| (id, name, Instant::now()));
+        }
+    }
+
+    /// Records a finished task along with the timing its thread measured
+    pub fn record_task(&mut self, task: &BackgroundTask, timing: Option<TaskTiming>) {
+        if self.completed_tasks.len() == TASK_HISTORY_LEN {
+            self.completed_tasks.pop_back();
+        }
+        self.completed_tasks.push_front(CompletedTask { name: task.kind.name(), timing });
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, tab_state: &mut TabState) {
+        let mut open = self.open;
+        egui::Window::new(format!("{} Diagnostics", icons::BUG)).open(&mut open).show(ctx, |ui| {
+            self.build_frame_times(ui);
+            ui.separator();
+            self.build_tasks(ui);
+            ui.separator();
+            build_caches(ui, tab_state);
+        });
+        self.open = open;
+    }
+
+    fn build_frame_times(&self, ui: &mut egui::Ui) {
+        ui.heading("Frame Time");
+        if self.frame_times.is_empty() {
+            return;
+        }
+
+        let average = self.frame_times.iter().sum::<f32>() / self.frame_times.len() as f32;
+        let worst = self.frame_times.iter().copied().fold(0.0, f32::max);
+        ui.label(format!(
+            "Average: {:.1} ms ({:.0} FPS), worst: {:.1} ms over the last {} frames",
+            average * 1000.0,
+            1.0 / average.max(f32::EPSILON),
+            worst * 1000.0,
+            self.frame_times.len()
+        ));
+    }
+
+    fn build_tasks(&self, ui: &mut egui::Ui) {
+        ui.heading("Background Tasks");
+        if let Some((_, name, started)) = self.running_task {
+            ui.label(format!("Running: {} ({:.1}s)", name, started.elapsed().as_secs_f32()));
+        }
+        if self.completed_tasks.is_empty() {
+            ui.label("No background tasks have completed yet");
+        }
+        for task in &self.completed_tasks {
+            let timing = match task.timing {
+                Some(TaskTiming {
+                    cpu_time: Some(cpu_time),
+                    wall_time,
+                }) => format!("{:.2}s CPU, {:.2}s total", cpu_time.as_secs_f32(), wall_time.as_secs_f32()),
+                Some(TaskTiming { cpu_time: None, wall_time }) => format!("{:.2}s total, CPU time unavailable", wall_time.as_secs_f32()),
+                None => "timing unavailable".to_string(),
+            };
+            ui.label(format!("{}: {}", task.name, timing));
+        }
+    }
+}
+
+fn build_caches(ui: &mut egui::Ui, tab_state: &mut TabState) {
+    ui.heading("Caches");
+
+    let (replay_count, parsed_count, replay_bytes) = tab_state
+        .replay_files
+        .as_ref()
+        .map(|replays| {
+            replays.values().fold((0, 0, 0), |(count, parsed, bytes), replay| {
+                let replay = replay.read();
+                (
+                    count + 1,
+                    parsed + replay.battle_report.is_some() as usize,
+                    bytes + replay.replay_file.packet_data.len(),
+                )
+            })
+        })
+        .unwrap_or_default();
+
+    let icon_bytes: usize = tab_state
+        .world_of_warships_data
+        .as_ref()
+        .map(|wows_data| wows_data.read().ship_icons.values().map(|icon| icon.data.len()).sum())
+        .unwrap_or_default();
+
+    let game_params_size = std::fs::metadata(game_params_bin_path()).map(|metadata| metadata.len()).unwrap_or_default();
+
+    let rows = [
+        (
+            "Replays in listing",
+            format!(
+                "{} ({} of packet data)",
+                replay_count,
+                humansize::format_size(replay_bytes as u64, humansize::DECIMAL)
+            ),
+        ),
+        ("Parsed battle reports", parsed_count.to_string()),
+        ("Ship icons", humansize::format_size(icon_bytes as u64, humansize::DECIMAL)),
+        ("Game params cache (disk)", humansize::format_size(game_params_size, humansize::DECIMAL)),
+    ];
+
+    TableBuilder::new(ui).column(Column::auto()).column(Column::remainder()).body(|mut body| {
+        for (name, value) in rows {
+            body.row(18.0, |mut row| {
+                row.col(|ui| {
+                    ui.label(name);
+                });
+                row.col(|ui| {
+                    ui.label(value);
+                });
+            });
+        }
+    });
+
+    if ui
+        .button(format!("{} Clear Caches", icons::TRASH))
+        .on_hover_text("Drops parsed battle reports (except the open replay) and cached images. They're rebuilt when needed.")
+        .clicked()
+    {
+        let current_replay = tab_state.current_replay.clone();
+        if let Some(replays) = tab_state.replay_files.as_ref() {
+            for replay in replays.values() {
+                if current_replay.as_ref().map(|current| std::sync::Arc::ptr_eq(current, replay)).unwrap_or(false) {
+                    continue;
+                }
+                replay.write().battle_report = None;
+            }
+        }
+        tab_state.filtered_file_list = None;
+        tab_state.used_filter = None;
+        ui.ctx().forget_all_images();
+    }
+}
@@ -0,0 +1,251 @@
+use std::{
+    fs::OpenOptions,
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+    sync::mpsc,
+    thread,
+    time::Duration,
+};
+
+use reqwest::{
+    header::{ACCEPT, ETAG, IF_RANGE, LAST_MODIFIED, RANGE},
+    StatusCode, Url,
+};
+use tracing::{debug, error};
+
+use crate::error::ToolkitError;
+
+/// Attempts made against each URL before moving on to the next mirror
+const ATTEMPTS_PER_URL: u32 = 4;
+/// Delay before the first retry. Doubled after every failed attempt.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Where downloads are kept until they're finished, so they can be resumed after the app restarts
+pub fn downloads_dir() -> PathBuf {
+    let downloads_dir = Path::new("downloads");
+    if let Some(storage_dir) = eframe::storage_dir(crate::APP_NAME) {
+        storage_dir.join(downloads_dir)
+    } else {
+        downloads_dir.to_path_buf()
+    }
+}
+
+pub struct DownloadProgress {
+    pub downloaded: u64,
+    /// `None` when the server doesn't say how large the file is
+    pub total: Option<u64>,
+    /// Why the download is waiting to be retried
+    pub retry: Option<String>,
+}
+
+impl DownloadProgress {
+    /// Progress bar for the bottom panel's status row
+    pub fn progress_bar(&self, label: &str) -> egui::ProgressBar {
+        let downloaded = humansize::format_size(self.downloaded, humansize::DECIMAL);
+        let (fraction, text) = match self.total {
+            Some(total) if total > 0 => (
+                self.downloaded as f32 / total as f32,
+                format!("{} ({} / {})", label, downloaded, humansize::format_size(total, humansize::DECIMAL)),
+            ),
+            _ => (0.0, format!("{} ({})", label, downloaded)),
+        };
+        let text = match &self.retry {
+            Some(retry) => format!("{} - {}", text, retry),
+            None => text,
+        };
+
+        egui::ProgressBar::new(fraction).text(text)
+    }
+}
+
+/// Whether a failed request is worth trying again against the same URL
+fn is_transient(error: &ToolkitError) -> bool {
+    match error {
+        ToolkitError::HttpError(e) => {
+            e.is_connect()
+                || e.is_timeout()
+                || e.is_body()
+                || e.status()
+                    .map(|status| status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS || status == StatusCode::RANGE_NOT_SATISFIABLE)
+                    .unwrap_or(false)
+        }
+        // Network errors while reading the response body surface as I/O errors. Local errors, like a full disk or
+        // a permissions problem, won't go away by retrying.
+        ToolkitError::Io(e) => {
+            matches!(
+                e.kind(),
+                io::ErrorKind::TimedOut | io::ErrorKind::ConnectionReset | io::ErrorKind::ConnectionAborted | io::ErrorKind::UnexpectedEof | io::ErrorKind::Interrupted
+            ) || e.get_ref().map(|inner| inner.is::<reqwest::Error>()).unwrap_or(false)
+        }
+        _ => false,
+    }
+}
+
+fn client() -> Result<reqwest::blocking::Client, ToolkitError> {
+    // GitHub's API rejects requests without a user agent
+    Ok(reqwest::blocking::Client::builder().user_agent(crate::APP_NAME).build()?)
+}
+
+/// Every HTTP download in the app goes through here so they all get retries with exponential backoff,
+/// mirror fallback and resuming of partially downloaded files.
+pub struct Download {
+    urls: Vec<Url>,
+    progress: Option<mpsc::Sender<DownloadProgress>>,
+}
+
+impl Download {
+    pub fn new(url: Url) -> Self {
+        Self { urls: vec![url], progress: None }
+    }
+
+    /// Adds a URL serving the same file, tried once the previous ones have failed
+    pub fn mirror(mut self, url: Url) -> Self {
+        self.urls.push(url);
+        self
+    }
+
+    pub fn progress(mut self, tx: mpsc::Sender<DownloadProgress>) -> Self {
+        self.progress = Some(tx);
+        self
+    }
+
+    fn send_progress(&self, downloaded: u64, total: Option<u64>, retry: Option<String>) {
+        if let Some(tx) = &self.progress {
+            let _ = tx.send(DownloadProgress { downloaded, total, retry });
+        }
+    }
+
+    /// Runs `attempt` against each URL until it succeeds, backing off between transient failures
+    fn with_retries<T>(&self, mut attempt: impl FnMut(&Url) -> Result<T, ToolkitError>) -> Result<T, ToolkitError> {
+        let mut last_error = None;
+        for url in &self.urls {
+            let mut backoff = INITIAL_BACKOFF;
+            for attempt_num in 1..=ATTEMPTS_PER_URL {
+                let e = match attempt(url) {
+                    Ok(result) => return Ok(result),
+                    Err(e) => e,
+                };
+
+                error!("download of {} failed (attempt {}/{}): {:?}", url, attempt_num, ATTEMPTS_PER_URL, e);
+                let transient = is_transient(&e);
+                if !transient && matches!(e, ToolkitError::Io(_)) {
+                    // Local I/O errors would fail the same way against every mirror
+                    return Err(e);
+                }
+                if transient && attempt_num < ATTEMPTS_PER_URL {
+                    self.send_progress(0, None, Some(format!("retrying in {}s: {}", backoff.as_secs(), e)));
+                    thread::sleep(backoff);
+                    backoff = (backoff * 2).min(MAX_BACKOFF);
+                }
+                last_error = Some(e);
+                if !transient {
+                    break;
+                }
+            }
+            debug!("giving up on {}", url);
+        }
+
+        Err(last_error.expect("download has no URLs"))
+    }
+
+    /// Downloads into memory. Meant for small files, so nothing is resumed.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ToolkitError> {
+        let client = client()?;
+        self.with_retries(|url| {
+            let mut response = client.get(url.clone()).header(ACCEPT, "application/octet-stream").send()?.error_for_status()?;
+            let total = response.content_length();
+            let mut data = Vec::new();
+            let mut buf = [0u8; 64 * 1024];
+            loop {
+                let read = response.read(&mut buf)?;
+                if read == 0 {
+                    break;
+                }
+                data.extend_from_slice(&buf[..read]);
+                self.send_progress(data.len() as u64, total, None);
+            }
+
+            Ok(data)
+        })
+    }
+
+    /// Downloads to `path`. The file is written to a `.part` file next to it first, which later
+    /// attempts (or a later run of the app) resume from if the server supports it. The server's ETag or
+    /// Last-Modified date is kept in a `.part.validator` file and sent as `If-Range`, so a partial file
+    /// from a different release or URL is downloaded again instead of being resumed.
+    pub fn to_file(&self, path: &Path) -> Result<PathBuf, ToolkitError> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let mut part_path = path.as_os_str().to_owned();
+        part_path.push(".part");
+        let part_path = PathBuf::from(part_path);
+        let mut validator_path = part_path.as_os_str().to_owned();
+        validator_path.push(".validator");
+        let validator_path = PathBuf::from(validator_path);
+
+        let client = client()?;
+        self.with_retries(|url| {
+            let existing = std::fs::metadata(&part_path).map(|metadata| metadata.len()).unwrap_or(0);
+            let validator = std::fs::read_to_string(&validator_path).ok().filter(|validator| !validator.is_empty());
+            let mut request = client.get(url.clone()).header(ACCEPT, "application/octet-stream");
+            let ranged = existing > 0 && validator.is_some();
+            if let Some(validator) = validator.filter(|_| ranged) {
+                request = request.header(RANGE, format!("bytes={}-", existing)).header(IF_RANGE, validator);
+            }
+            let response = request.send()?;
+            if response.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+                // The partial file doesn't match what's on the server anymore, so start over
+                let _ = std::fs::remove_file(&part_path);
+            }
+            let mut response = response.error_for_status()?;
+
+            // Servers which don't support ranges, or whose file changed since the partial download, send
+            // the whole file again
+            let resumed = ranged && response.status() == StatusCode::PARTIAL_CONTENT;
+            let mut downloaded = if resumed { existing } else { 0 };
+            if resumed {
+                debug!("resuming download of {} at {} bytes", url, existing);
+            } else if existing > 0 {
+                debug!("discarding {} bytes of a previous download of {}", existing, url);
+                let _ = std::fs::remove_file(&part_path);
+            }
+            let total = response.content_length().map(|len| len + downloaded);
+
+            if !resumed {
+                let validator = [ETAG, LAST_MODIFIED]
+                    .iter()
+                    .find_map(|header| response.headers().get(header).and_then(|value| value.to_str().ok()))
+                    .map(|validator| validator.to_string());
+                match validator {
+                    Some(validator) => std::fs::write(&validator_path, validator)?,
+                    None => {
+                        let _ = std::fs::remove_file(&validator_path);
+                    }
+                }
+            }
+
+            let mut file = OpenOptions::new().create(true).write(true).append(resumed).truncate(!resumed).open(&part_path)?;
+            let mut buf = [0u8; 64 * 1024];
+            loop {
+                let read = response.read(&mut buf)?;
+                if read == 0 {
+                    break;
+                }
+                file.write_all(&buf[..read])?;
+                downloaded += read as u64;
+                self.send_progress(downloaded, total, None);
+            }
+            file.flush()?;
+
+            Ok(())
+        })?;
+
+        std::fs::rename(&part_path, path)?;
+        let _ = std::fs::remove_file(&validator_path);
+
+        Ok(path.to_path_buf())
+    }
+}
@@ -28,8 +28,32 @@ pub enum ToolkitError {
     #[error("Background task completed")]
     BackgroundTaskCompleted,
 
-    #[error("A network error occurred while downloading an update: {0}")]
-    UpdateHttpError(#[from] reqwest::Error),
+    #[error("A network error occurred: {0}")]
+    HttpError(#[from] reqwest::Error),
+
+    #[error("{0:?} is not a valid replay URL")]
+    InvalidReplayUrl(String),
+
+    #[error("Downloaded update has SHA-256 {actual}, but the release lists {expected}")]
+    UpdateChecksumMismatch { expected: String, actual: String },
+
+    #[error("The release's checksums don't list {0}")]
+    UpdateChecksumMissing(String),
+
+    #[error("Could not sync clan roster: {0}")]
+    ClanRosterError(String),
+
+    #[error("Could not read backup: {0}")]
+    InvalidBackup(String),
+
+    #[error("No export folder has been chosen")]
+    NoExportDirectory,
+
+    #[error("Could not import players: {0}")]
+    InvalidImport(String),
+
+    #[error("Could not read expected values: {0}")]
+    InvalidExpectedValues(String),
 
     #[error("Could not not read update ZipArchive")]
     ZipReadError(#[from] zip::result::ZipError),
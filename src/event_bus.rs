@@ -0,0 +1,174 @@
+use std::{
+    fs::OpenOptions,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::{mpsc, Arc},
+    thread,
+    time::Duration,
+};
+
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, error};
+
+/// Where battle lifecycle events should be delivered
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EventBusSettings {
+    pub enabled: bool,
+    /// Webhook URLs, one per line
+    pub webhooks: String,
+    /// Named pipe (or plain file) which receives newline-delimited JSON events
+    pub pipe_path: String,
+}
+
+impl EventBusSettings {
+    fn webhook_urls(&self) -> impl Iterator<Item = &str> {
+        self.webhooks.lines().map(str::trim).filter(|line| !line.is_empty())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum BattleEvent {
+    BattleStarted {
+        player_name: String,
+        map_name: String,
+        game_type: String,
+    },
+    BattleEnded {
+        replay_path: PathBuf,
+    },
+    ReplayParsed {
+        replay_path: PathBuf,
+        arena_id: i64,
+        version: String,
+        game_type: String,
+        game_mode: String,
+        map_name: String,
+    },
+    FlaggedPlayerSeen {
+        replay_path: PathBuf,
+        player_name: String,
+        clan: String,
+        db_id: i64,
+        notes: String,
+    },
+}
+
+impl BattleEvent {
+    /// Builds a [BattleEvent::BattleStarted] from the `tempArenaInfo.json` the game writes when a battle begins.
+    fn battle_started_from_arena_info(path: &Path) -> Option<Self> {
+        // The game may still be writing this file when we're notified of it
+        for _ in 0..3 {
+            let arena_info = std::fs::read(path).ok().and_then(|data| serde_json::from_slice::<serde_json::Value>(&data).ok());
+            if let Some(arena_info) = arena_info {
+                let field = |name: &str| arena_info.get(name).and_then(|value| value.as_str()).unwrap_or_default().to_string();
+
+                return Some(BattleEvent::BattleStarted {
+                    player_name: field("playerName"),
+                    map_name: field("mapName"),
+                    game_type: field("gameType"),
+                });
+            }
+
+            thread::sleep(Duration::from_millis(500));
+        }
+
+        None
+    }
+}
+
+/// Work sent to the dispatch thread
+enum EventBusMessage {
+    Event(BattleEvent),
+    /// `tempArenaInfo.json` was written. It's read on the dispatch thread since the game may still be writing it.
+    ArenaInfoWritten(PathBuf),
+}
+
+#[derive(Serialize)]
+struct EventEnvelope<'a> {
+    timestamp: String,
+    app_version: &'static str,
+    #[serde(flatten)]
+    event: &'a BattleEvent,
+}
+
+/// Dispatches [BattleEvent]s to the configured webhooks and named pipe on a dedicated thread
+#[derive(Clone)]
+pub struct EventBus {
+    tx: mpsc::Sender<EventBusMessage>,
+}
+
+impl EventBus {
+    pub fn start(settings: Arc<RwLock<EventBusSettings>>) -> Self {
+        let (tx, rx) = mpsc::channel::<EventBusMessage>();
+
+        let _join_handle = thread::spawn(move || {
+            let client = reqwest::blocking::Client::new();
+            while let Ok(message) = rx.recv() {
+                let settings = { settings.read().clone() };
+                if !settings.enabled {
+                    continue;
+                }
+
+                let event = match message {
+                    EventBusMessage::Event(event) => event,
+                    EventBusMessage::ArenaInfoWritten(path) => match BattleEvent::battle_started_from_arena_info(&path) {
+                        Some(event) => event,
+                        None => {
+                            error!("failed to read arena info from {:?}", path);
+                            continue;
+                        }
+                    },
+                };
+
+                debug!("dispatching event: {:?}", event);
+                let envelope = EventEnvelope {
+                    timestamp: chrono::Local::now().to_rfc3339(),
+                    app_version: env!("CARGO_PKG_VERSION"),
+                    event: &event,
+                };
+
+                for url in settings.webhook_urls() {
+                    if let Err(e) = client.post(url).json(&envelope).send() {
+                        error!("failed to send event to webhook {}: {:?}", url, e);
+                    }
+                }
+
+                if !settings.pipe_path.is_empty() {
+                    if let Err(e) = write_to_pipe(Path::new(&settings.pipe_path), &envelope) {
+                        error!("failed to write event to {}: {:?}", settings.pipe_path, e);
+                    }
+                }
+            }
+        });
+
+        EventBus { tx }
+    }
+
+    pub fn emit(&self, event: BattleEvent) {
+        let _ = self.tx.send(EventBusMessage::Event(event));
+    }
+
+    /// Emits a [BattleEvent::BattleStarted] once the arena info at `path` can be read
+    pub fn emit_battle_started(&self, arena_info_path: PathBuf) {
+        let _ = self.tx.send(EventBusMessage::ArenaInfoWritten(arena_info_path));
+    }
+}
+
+fn write_to_pipe(path: &Path, envelope: &EventEnvelope<'_>) -> std::io::Result<()> {
+    let mut line = serde_json::to_vec(envelope)?;
+    line.push(b'\n');
+
+    // Windows named pipes can't be opened for appending, but regular files should
+    // accumulate events rather than be truncated.
+    let is_named_pipe = path.to_string_lossy().starts_with(r"\\.\pipe\");
+    let mut file = if is_named_pipe {
+        OpenOptions::new().write(true).open(path)?
+    } else {
+        OpenOptions::new().append(true).create(true).open(path)?
+    };
+
+    file.write_all(&line)
+}
@@ -0,0 +1,159 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use serde::Deserialize;
+use tracing::error;
+
+use crate::{
+    app::{TimedMessage, ToolkitTabViewer},
+    error::ToolkitError,
+    icons, usage_stats,
+};
+
+/// Full PR of a player who exactly meets every expected value
+const AVERAGE_RATING: f64 = 1150.0;
+/// Share of the expected damage below which the damage component of PR is zero
+const DAMAGE_FLOOR: f64 = 0.4;
+
+/// Expected stats for one ship, as published by wows-numbers
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExpectedShipValues {
+    pub average_damage_dealt: f64,
+    pub average_frags: f64,
+    pub win_rate: f64,
+}
+
+#[derive(Deserialize)]
+struct ExpectedValuesFile {
+    /// Ships without enough data are listed with an empty array instead of an object
+    data: HashMap<String, serde_json::Value>,
+}
+
+/// Imported expected values baseline, keyed by ship ID
+#[derive(Debug, Default)]
+pub struct ExpectedValues {
+    ships: HashMap<String, ExpectedShipValues>,
+}
+
+/// Where the last imported baseline is kept so it survives restarts
+fn cache_path() -> PathBuf {
+    let cache_path = Path::new("expected_values.json");
+    if let Some(storage_dir) = eframe::storage_dir(crate::APP_NAME) {
+        storage_dir.join(cache_path)
+    } else {
+        cache_path.to_path_buf()
+    }
+}
+
+impl ExpectedValues {
+    fn parse(data: &[u8]) -> Result<Self, ToolkitError> {
+        let file: ExpectedValuesFile = serde_json::from_slice(data).map_err(|e| ToolkitError::InvalidExpectedValues(e.to_string()))?;
+        let ships: HashMap<String, ExpectedShipValues> = file
+            .data
+            .into_iter()
+            .filter_map(|(ship_id, values)| Some((ship_id, serde_json::from_value(values).ok()?)))
+            .collect();
+        if ships.is_empty() {
+            return Err(ToolkitError::InvalidExpectedValues("no ships have expected values".to_string()));
+        }
+
+        Ok(Self { ships })
+    }
+
+    /// Reads a wows-numbers style expected values JSON file and keeps a copy for later runs
+    pub fn import(path: &Path) -> Result<Self, ToolkitError> {
+        let data = std::fs::read(path)?;
+        let expected_values = Self::parse(&data)?;
+        let cache_path = cache_path();
+        if let Some(parent) = cache_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(cache_path, data)?;
+
+        Ok(expected_values)
+    }
+
+    /// The previously imported baseline, if there is one
+    pub fn load_cached() -> Option<Self> {
+        let data = std::fs::read(cache_path()).ok()?;
+        match Self::parse(&data) {
+            Ok(expected_values) => Some(expected_values),
+            Err(e) => {
+                error!("failed to load cached expected values: {:?}", e);
+                None
+            }
+        }
+    }
+
+    pub fn ship_count(&self) -> usize {
+        self.ships.len()
+    }
+
+    pub fn ship(&self, ship_id: &str) -> Option<&ExpectedShipValues> {
+        self.ships.get(ship_id)
+    }
+
+    /// PR-like rating for a single battle. Battle results in replays don't include frags or the
+    /// battle outcome, so only PR's damage component is used, scaled so meeting the expected damage
+    /// rates the same as an average PR.
+    pub fn battle_rating(&self, ship_id: &str, damage: i64) -> Option<f64> {
+        let expected = self.ship(ship_id)?;
+        if expected.average_damage_dealt <= 0.0 {
+            return None;
+        }
+
+        let damage_ratio = damage as f64 / expected.average_damage_dealt;
+        let normalized_damage = ((damage_ratio - DAMAGE_FLOOR) / (1.0 - DAMAGE_FLOOR)).max(0.0);
+
+        Some(AVERAGE_RATING * normalized_damage)
+    }
+}
+
+/// Color wows-numbers uses for a PR value
+pub fn rating_color(rating: f64) -> egui::Color32 {
+    match rating as u32 {
+        0..=749 => egui::Color32::from_rgb(0xFE, 0x0E, 0x00),
+        750..=1099 => egui::Color32::from_rgb(0xFE, 0x79, 0x03),
+        1100..=1349 => egui::Color32::from_rgb(0xFF, 0xC7, 0x1F),
+        1350..=1549 => egui::Color32::from_rgb(0x44, 0xB3, 0x00),
+        1550..=1749 => egui::Color32::from_rgb(0x31, 0x80, 0x00),
+        1750..=2099 => egui::Color32::from_rgb(0x02, 0xC9, 0xB3),
+        2100..=2449 => egui::Color32::from_rgb(0xD0, 0x42, 0xF3),
+        _ => egui::Color32::from_rgb(0xA0, 0x0D, 0xC5),
+    }
+}
+
+impl ToolkitTabViewer<'_> {
+    pub fn build_expected_values_settings(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            match self.tab_state.expected_values.as_ref() {
+                Some(expected_values) => ui.label(format!("Expected values loaded for {} ships", expected_values.ship_count())),
+                None => ui.label("No expected values imported"),
+            };
+            if ui
+                .button("Import...")
+                .on_hover_text("Import an expected values JSON file, like the one published by wows-numbers, to rate players in the replay viewer")
+                .clicked()
+            {
+                if let Some(path) = rfd::FileDialog::new().add_filter("JSON", &["json"]).pick_file() {
+                    let message = match ExpectedValues::import(&path) {
+                        Ok(expected_values) => {
+                            usage_stats::record_feature("Import expected values");
+                            let message = format!("{} Imported expected values for {} ships", icons::CHECK_CIRCLE, expected_values.ship_count());
+                            self.tab_state.expected_values = Some(Arc::new(expected_values));
+                            message
+                        }
+                        Err(e) => {
+                            error!("failed to import expected values: {:?}", e);
+                            format!("{} {}", icons::WARNING, e)
+                        }
+                    };
+                    *self.tab_state.timed_message.write() = Some(TimedMessage::new(message));
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,160 @@
+use std::{
+    path::Path,
+    process::Command,
+    sync::mpsc,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::{
+    app::{TabState, TimedMessage},
+    icons,
+};
+
+/// Name of the game client's process once it's running
+const GAME_PROCESS: &str = "WorldOfWarships64.exe";
+
+const STEAM_APP_ID: u32 = 552990;
+
+/// How long to wait for the client to start before giving up, since launchers may update first
+const DETECTION_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+const DETECTION_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How the game client is started
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameLauncher {
+    /// The `WorldOfWarships.exe` in the game directory, which starts the latest installed build
+    #[default]
+    GameDirectory,
+    Steam,
+}
+
+impl GameLauncher {
+    pub const ALL: [GameLauncher; 2] = [GameLauncher::GameDirectory, GameLauncher::Steam];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameLauncher::GameDirectory => "Game Directory",
+            GameLauncher::Steam => "Steam",
+        }
+    }
+
+    fn launch(&self, wows_dir: &Path) -> std::io::Result<()> {
+        let mut command = match self {
+            GameLauncher::GameDirectory => {
+                let mut command = Command::new(wows_dir.join("WorldOfWarships.exe"));
+                command.current_dir(wows_dir);
+                command
+            }
+            GameLauncher::Steam => open_url_command(&format!("steam://rungameid/{}", STEAM_APP_ID)),
+        };
+        command.spawn().map(|_| ())
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn open_url_command(url: &str) -> Command {
+    let mut command = Command::new("cmd");
+    command.args(["/C", "start", "", url]);
+    command
+}
+
+#[cfg(not(target_os = "windows"))]
+fn open_url_command(url: &str) -> Command {
+    let mut command = Command::new("xdg-open");
+    command.arg(url);
+    command
+}
+
+fn is_game_running() -> bool {
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+
+        /// Keeps `tasklist` from flashing a console window, since the app has none
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        // `tasklist` succeeds whether or not anything matched the filter, so its output has to be checked
+        Command::new("tasklist")
+            .args(["/FI", &format!("IMAGENAME eq {}", GAME_PROCESS), "/NH"])
+            .creation_flags(CREATE_NO_WINDOW)
+            .output()
+            .map(|output| String::from_utf8_lossy(&output.stdout).to_lowercase().contains(&GAME_PROCESS.to_lowercase()))
+            .unwrap_or(false)
+    }
+    // The client runs under Wine or Proton elsewhere, which keeps the executable's name. `pgrep` only prints
+    // PIDs, but exits successfully when a process matched.
+    #[cfg(not(target_os = "windows"))]
+    {
+        Command::new("pgrep")
+            .args(["-f", GAME_PROCESS])
+            .output()
+            .map(|output| output.status.success())
+            .unwrap_or(false)
+    }
+}
+
+/// Polls until the game client is running. Sends whether it was found before timing out.
+fn watch_for_game(ctx: egui::Context) -> mpsc::Receiver<bool> {
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let started = Instant::now();
+        let found = loop {
+            if is_game_running() {
+                break true;
+            }
+            if started.elapsed() > DETECTION_TIMEOUT {
+                break false;
+            }
+            std::thread::sleep(DETECTION_INTERVAL);
+        };
+
+        debug!("game client detected: {}", found);
+        let _ = tx.send(found);
+        ctx.request_repaint();
+    });
+
+    rx
+}
+
+impl TabState {
+    /// Starts the game client with the configured launcher, then waits for it to turn on live battle monitoring
+    pub fn launch_game(&mut self, ctx: &egui::Context) {
+        let launcher = self.settings.game_launcher;
+        let message = match launcher.launch(Path::new(&self.settings.wows_dir)) {
+            Ok(()) => {
+                self.game_detection = Some(watch_for_game(ctx.clone()));
+                format!("{} Starting World of Warships via {}", icons::ROCKET_LAUNCH, launcher.name())
+            }
+            Err(e) => format!("{} Failed to start World of Warships: {}", icons::WARNING, e),
+        };
+        *self.timed_message.write() = Some(TimedMessage::new(message));
+    }
+
+    pub fn is_waiting_for_game(&self) -> bool {
+        self.game_detection.is_some()
+    }
+
+    /// Turns on loading the latest replay once the launched client is running
+    pub fn check_game_detection(&mut self) {
+        let Some(found) = self.game_detection.as_ref().and_then(|rx| rx.try_recv().ok()) else {
+            return;
+        };
+        self.game_detection = None;
+
+        let message = if found {
+            self.auto_load_latest_replay = true;
+            self.monitor_live_battles = true;
+            format!("{} World of Warships is running. Battles will load as they start and finish.", icons::CHECK_CIRCLE)
+        } else {
+            format!(
+                "{} World of Warships wasn't detected after {} minutes",
+                icons::WARNING,
+                DETECTION_TIMEOUT.as_secs() / 60
+            )
+        };
+        *self.timed_message.write() = Some(TimedMessage::new(message));
+    }
+}
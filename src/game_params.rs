@@ -25,6 +25,20 @@ struct CachedGameParams {
     params: Vec<Param>,
 }
 
+/// Leading fields of [`CachedGameParams`], which can be read without loading every param
+#[derive(Deserialize)]
+struct CachedGameParamsHeader {
+    _app_version: String,
+    game_version: usize,
+}
+
+/// Game version the game params cache was built for, if there is a cache
+pub fn cached_game_params_version() -> Option<usize> {
+    let cache_data = std::fs::File::open(game_params_bin_path()).ok()?;
+    let header: CachedGameParamsHeader = bincode::deserialize_from(cache_data).ok()?;
+    Some(header.game_version)
+}
+
 pub fn game_params_bin_path() -> PathBuf {
     let old_cache_path = Path::new("game_params.bin");
     if let Some(storage_dir) = eframe::storage_dir(crate::APP_NAME) {
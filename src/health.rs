@@ -0,0 +1,284 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::mpsc,
+    time::Duration,
+};
+
+use egui::{Color32, RichText};
+
+use crate::{
+    app::{Settings, TabState},
+    connectivity::{check_connection, Connectivity},
+    game_params::cached_game_params_version,
+    icons, task,
+};
+
+pub enum CheckStatus {
+    Pending,
+    Passed,
+    Warning(String),
+    Failed(String),
+}
+
+/// Something the user can click to resolve a failed check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fix {
+    ChooseGameDirectory,
+    ReloadGameData,
+    ChooseReplaysDirectory,
+    RunAgain,
+}
+
+impl Fix {
+    fn label(&self) -> &'static str {
+        match self {
+            Fix::ChooseGameDirectory => "Choose Game Directory...",
+            Fix::ReloadGameData => "Reload Game Data",
+            Fix::ChooseReplaysDirectory => "Choose Replays Folder...",
+            Fix::RunAgain => "Check Again",
+        }
+    }
+}
+
+pub struct HealthCheck {
+    name: &'static str,
+    status: CheckStatus,
+    fix: Option<Fix>,
+}
+
+impl HealthCheck {
+    fn passed(name: &'static str) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Passed,
+            fix: None,
+        }
+    }
+
+    fn warning(name: &'static str, message: String, fix: Fix) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Warning(message),
+            fix: Some(fix),
+        }
+    }
+
+    fn failed(name: &'static str, message: String, fix: Fix) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Failed(message),
+            fix: Some(fix),
+        }
+    }
+}
+
+fn check_game_directory(wows_dir: &Path) -> HealthCheck {
+    const NAME: &str = "Game directory";
+    if wows_dir.as_os_str().is_empty() {
+        return HealthCheck::failed(NAME, "No game directory has been chosen".to_string(), Fix::ChooseGameDirectory);
+    }
+
+    let missing: Vec<_> = ["bin", "res_packages"].into_iter().filter(|dir| !wows_dir.join(dir).exists()).collect();
+    if !wows_dir.exists() || !missing.is_empty() {
+        return HealthCheck::failed(
+            NAME,
+            format!("{} doesn't look like a World of Warships install", wows_dir.display()),
+            Fix::ChooseGameDirectory,
+        );
+    }
+
+    HealthCheck::passed(NAME)
+}
+
+fn check_game_version(wows_dir: &Path) -> HealthCheck {
+    const NAME: &str = "Cached game data";
+    let Some(installed) = task::installed_build(wows_dir) else {
+        return HealthCheck::failed(NAME, "Couldn't find the installed game version".to_string(), Fix::ChooseGameDirectory);
+    };
+
+    match cached_game_params_version() {
+        Some(cached) if cached == installed => HealthCheck::passed(NAME),
+        Some(cached) => HealthCheck::warning(
+            NAME,
+            format!(
+                "Cached for build {}, but build {} is installed. Loading game data will rebuild it.",
+                cached, installed
+            ),
+            Fix::ReloadGameData,
+        ),
+        None => HealthCheck::warning(NAME, "Not built yet. The first load of game data takes longer.".to_string(), Fix::ReloadGameData),
+    }
+}
+
+fn check_replays_dir(replays_dir: &Path) -> HealthCheck {
+    const NAME: &str = "Replays folder";
+    if !replays_dir.exists() {
+        return HealthCheck::warning(
+            NAME,
+            format!(
+                "{} doesn't exist. Enable replays in the game's settings or choose another folder.",
+                replays_dir.display()
+            ),
+            Fix::ChooseReplaysDirectory,
+        );
+    }
+
+    let test_file = replays_dir.join(".wows_toolkit_write_test");
+    match std::fs::write(&test_file, b"") {
+        Ok(()) => {
+            let _ = std::fs::remove_file(test_file);
+            HealthCheck::passed(NAME)
+        }
+        Err(e) => HealthCheck::failed(
+            NAME,
+            format!("{} isn't writable ({}). Downloaded and deleted replays can't be saved.", replays_dir.display(), e),
+            Fix::ChooseReplaysDirectory,
+        ),
+    }
+}
+
+/// Checklist of things which cause confusing errors later on if they're misconfigured
+pub struct HealthChecks {
+    pub open: bool,
+    checks: Vec<HealthCheck>,
+    network_rx: Option<mpsc::Receiver<Result<(), String>>>,
+}
+
+impl HealthChecks {
+    const NETWORK: &'static str = "Network";
+
+    /// Runs the checks. The window opens by itself if any of them fail.
+    pub fn run(settings: &Settings, connectivity: &Connectivity) -> Self {
+        let wows_dir = PathBuf::from(&settings.wows_dir);
+        let replays_dir = task::replays_dir(&wows_dir, settings.custom_replays_dir.clone());
+
+        let mut checks = vec![check_game_directory(&wows_dir)];
+        if matches!(checks[0].status, CheckStatus::Passed) {
+            checks.push(check_game_version(&wows_dir));
+            checks.push(check_replays_dir(&replays_dir));
+        }
+        checks.push(HealthCheck {
+            name: Self::NETWORK,
+            status: CheckStatus::Pending,
+            fix: None,
+        });
+
+        let (tx, rx) = mpsc::channel();
+        let connectivity = connectivity.clone();
+        std::thread::spawn(move || {
+            let result = check_connection();
+            connectivity.set_offline(result.is_err());
+            let _ = tx.send(result);
+        });
+
+        let mut health_checks = Self {
+            open: false,
+            checks,
+            network_rx: Some(rx),
+        };
+        health_checks.open = health_checks.has_failures();
+
+        health_checks
+    }
+
+    fn has_failures(&self) -> bool {
+        self.checks.iter().any(|check| matches!(check.status, CheckStatus::Failed(_)))
+    }
+
+    fn poll_network(&mut self) {
+        let Some(result) = self.network_rx.as_ref().and_then(|rx| rx.try_recv().ok()) else {
+            return;
+        };
+        self.network_rx = None;
+
+        if let Some(check) = self.checks.iter_mut().find(|check| check.name == Self::NETWORK) {
+            *check = match result {
+                Ok(()) => HealthCheck::passed(Self::NETWORK),
+                Err(e) => HealthCheck::failed(
+                    Self::NETWORK,
+                    format!("Couldn't reach the internet ({}). Update checks and online lookups won't work.", e),
+                    Fix::RunAgain,
+                ),
+            };
+        }
+
+        if self.has_failures() {
+            self.open = true;
+        }
+    }
+
+    /// Returns the fix the user clicked, if any. Only checking again is allowed while `locked`.
+    pub fn show(&mut self, ctx: &egui::Context, locked: bool) -> Option<Fix> {
+        self.poll_network();
+        if self.network_rx.is_some() {
+            ctx.request_repaint_after(Duration::from_millis(250));
+        }
+
+        let mut clicked_fix = None;
+        let mut open = self.open;
+        egui::Window::new(format!("{} Health Check", icons::STETHOSCOPE)).open(&mut open).show(ctx, |ui| {
+            egui::Grid::new("health_checks_grid").num_columns(3).spacing([10.0, 8.0]).show(ui, |ui| {
+                for check in &self.checks {
+                    let (icon, message) = match &check.status {
+                        CheckStatus::Pending => (RichText::new(icons::HOURGLASS), "Checking...".to_string()),
+                        CheckStatus::Passed => (RichText::new(icons::CHECK_CIRCLE).color(Color32::LIGHT_GREEN), "OK".to_string()),
+                        CheckStatus::Warning(message) => (RichText::new(icons::WARNING).color(Color32::YELLOW), message.clone()),
+                        CheckStatus::Failed(message) => (RichText::new(icons::X_CIRCLE).color(Color32::LIGHT_RED), message.clone()),
+                    };
+
+                    ui.label(icon);
+                    ui.vertical(|ui| {
+                        ui.strong(check.name);
+                        ui.label(message);
+                    });
+                    if let Some(fix) = check.fix {
+                        if ui.add_enabled(!locked || fix == Fix::RunAgain, egui::Button::new(fix.label())).clicked() {
+                            clicked_fix = Some(fix);
+                        }
+                    } else {
+                        ui.label("");
+                    }
+                    ui.end_row();
+                }
+            });
+        });
+        self.open = open;
+
+        clicked_fix
+    }
+}
+
+impl TabState {
+    /// Applies a fix picked in the health check window. Returns whether the checks should be run again.
+    pub fn apply_health_fix(&mut self, fix: Fix) -> bool {
+        // The checks run on startup even when locked, but their fixes change settings
+        if self.is_locked() && fix != Fix::RunAgain {
+            return false;
+        }
+
+        match fix {
+            Fix::ChooseGameDirectory => {
+                let Some(folder) = rfd::FileDialog::new().pick_folder() else {
+                    return false;
+                };
+                self.settings.wows_dir = folder.to_string_lossy().into_owned();
+            }
+            Fix::ChooseReplaysDirectory => {
+                let Some(folder) = rfd::FileDialog::new().pick_folder() else {
+                    return false;
+                };
+                self.settings.custom_replays_dir = Some(folder);
+            }
+            Fix::ReloadGameData => {}
+            Fix::RunAgain => return true,
+        }
+
+        if self.can_change_wows_dir && !self.settings.wows_dir.is_empty() {
+            let wows_dir = PathBuf::from(&self.settings.wows_dir);
+            self.prevent_changing_wows_dir();
+            crate::update_background_task!(self.background_task, Some(self.load_game_data(wows_dir)));
+        }
+
+        true
+    }
+}
@@ -0,0 +1,351 @@
+use std::{fmt::Write, path::Path, sync::Arc};
+
+use egui::{Color32, RichText};
+use sha2::{Digest, Sha256};
+use wows_replays::{
+    analyzer::{battle_controller::BattleController, AnalyzerMut},
+    packet2::{Packet, Parser},
+    ReplayFile,
+};
+use wowsunpack::game_params::provider::GameMetadataProvider;
+
+use crate::{
+    app::{TimedMessage, ToolkitTabViewer},
+    error::ToolkitError,
+    health::CheckStatus,
+    icons,
+    replay_parser::{DAMAGE_INDEX, DAMAGE_SOURCES},
+    replay_titles::replay_key,
+    scouting::results_value,
+    task,
+};
+
+/// How far past the battle time limit a recording may run before it's called out. Covers loading, the
+/// countdown and the results screen.
+const EXTRA_RECORDING_SECS: f32 = 180.0;
+
+/// Passes packets on to the battle controller while keeping track of how far into the recording they go
+pub(crate) struct ClockTracker<A> {
+    pub(crate) analyzer: A,
+    pub(crate) last_clock: f32,
+}
+
+impl<A: AnalyzerMut> AnalyzerMut for ClockTracker<A> {
+    fn process_mut(&mut self, packet: &Packet) {
+        self.last_clock = self.last_clock.max(packet.clock);
+        self.analyzer.process_mut(packet);
+    }
+
+    fn finish(&mut self) {
+        self.analyzer.finish();
+    }
+}
+
+pub(crate) fn format_secs(secs: f32) -> String {
+    let secs = secs.max(0.0) as u64;
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
+
+pub struct IntegrityCheck {
+    name: &'static str,
+    /// What was found, e.g. the file hash or client version
+    value: String,
+    status: CheckStatus,
+}
+
+impl IntegrityCheck {
+    fn passed(name: &'static str, value: String) -> Self {
+        Self {
+            name,
+            value,
+            status: CheckStatus::Passed,
+        }
+    }
+
+    fn warning(name: &'static str, value: String, message: String) -> Self {
+        Self {
+            name,
+            value,
+            status: CheckStatus::Warning(message),
+        }
+    }
+
+    fn failed(name: &'static str, value: String, message: String) -> Self {
+        Self {
+            name,
+            value,
+            status: CheckStatus::Failed(message),
+        }
+    }
+}
+
+/// Checks contest runners can attach to a submitted replay. None of them can prove a replay is
+/// genuine, but they catch edited metadata, corrupt or spliced packet data, and cut-short recordings.
+pub struct IntegrityReport {
+    pub file_name: String,
+    checks: Vec<IntegrityCheck>,
+}
+
+impl IntegrityReport {
+    pub fn verify(path: &Path, metadata_provider: Arc<GameMetadataProvider>, game_version: usize) -> Result<Self, ToolkitError> {
+        let mut report = IntegrityReport {
+            file_name: replay_key(path),
+            checks: Vec::new(),
+        };
+
+        let data = std::fs::read(path)?;
+        report.checks.push(IntegrityCheck::passed(
+            "File Hash",
+            format!("SHA-256 {}", data_encoding::HEXLOWER.encode(Sha256::digest(&data).as_slice())),
+        ));
+
+        let replay_file = match ReplayFile::from_file(path) {
+            Ok(replay_file) => replay_file,
+            Err(e) => {
+                report
+                    .checks
+                    .push(IntegrityCheck::failed("Replay File", String::new(), format!("Couldn't be read: {:?}", e)));
+                return Ok(report);
+            }
+        };
+        let raw_meta: serde_json::Value = serde_json::from_str(&replay_file.raw_meta).unwrap_or_default();
+
+        let exe_version = replay_file.meta.clientVersionFromExe.clone();
+        let build = exe_version.split(',').nth(3).unwrap_or_default().trim().to_string();
+        let xml_version = raw_meta.get("clientVersionFromXml").and_then(|version| version.as_str());
+        if build.is_empty() {
+            report
+                .checks
+                .push(IntegrityCheck::failed("Client Version", exe_version, "Version has no build number".to_string()));
+            return Ok(report);
+        }
+        if let Some(xml_version) = xml_version.filter(|xml_version| !xml_version.contains(build.as_str())) {
+            report.checks.push(IntegrityCheck::failed(
+                "Client Version",
+                exe_version,
+                format!("The client recorded two different versions ({}), so the metadata may have been edited", xml_version),
+            ));
+        } else if build != game_version.to_string() {
+            report.checks.push(IntegrityCheck::warning(
+                "Client Version",
+                exe_version,
+                format!(
+                    "Recorded on build {}, but game data for build {} is loaded, so the battle itself couldn't be checked",
+                    build, game_version
+                ),
+            ));
+            return Ok(report);
+        } else {
+            report.checks.push(IntegrityCheck::passed("Client Version", exe_version));
+        }
+
+        let mut tracker = ClockTracker {
+            analyzer: BattleController::new(&replay_file.meta, metadata_provider.as_ref()),
+            last_clock: 0.0,
+        };
+        let mut parser = Parser::new(metadata_provider.entity_specs());
+        let parse_result = parser.parse_packets_mut(&replay_file.packet_data, &mut tracker);
+        tracker.finish();
+        let last_clock = tracker.last_clock;
+        let battle_report = tracker.analyzer.build_report();
+
+        report.checks.push(match parse_result {
+            Ok(()) => IntegrityCheck::passed("Packet Data", format!("{} bytes", replay_file.packet_data.len())),
+            Err(e) => IntegrityCheck::failed(
+                "Packet Data",
+                format!("{} bytes", replay_file.packet_data.len()),
+                format!("Packets stop parsing partway through, so the file may be corrupt or modified: {:?}", e),
+            ),
+        });
+
+        let self_player = battle_report.self_entity().player();
+        let realm = self_player.map(|player| player.realm().to_string()).unwrap_or_default();
+        let self_name = self_player.map(|player| player.name().to_string()).unwrap_or_default();
+        report.checks.push(IntegrityCheck::passed("Server Realm", realm));
+        if self_name == replay_file.meta.playerName {
+            report.checks.push(IntegrityCheck::passed("Recording Player", self_name));
+        } else {
+            report.checks.push(IntegrityCheck::failed(
+                "Recording Player",
+                self_name,
+                format!("The metadata names {} as the recording player", replay_file.meta.playerName),
+            ));
+        }
+
+        let has_results = battle_report.battle_results().is_some();
+        let time_limit = raw_meta.get("duration").and_then(|duration| duration.as_f64()).unwrap_or_default() as f32;
+        let recorded = format_secs(last_clock);
+        report.checks.push(if last_clock <= 0.0 {
+            IntegrityCheck::failed("Recording Length", recorded, "Contains no battle".to_string())
+        } else if time_limit > 0.0 && last_clock > time_limit + EXTRA_RECORDING_SECS {
+            IntegrityCheck::warning(
+                "Recording Length",
+                recorded,
+                format!("Runs well past the battle time limit of {}", format_secs(time_limit)),
+            )
+        } else if !has_results && last_clock < time_limit {
+            IntegrityCheck::warning(
+                "Recording Length",
+                recorded,
+                "Stops before the time limit without battle results, so it may have been cut short".to_string(),
+            )
+        } else {
+            IntegrityCheck::passed("Recording Length", recorded)
+        });
+
+        let players = battle_report.player_entities();
+        let players_summary = format!("{} players", players.len());
+        report.checks.push(if !has_results {
+            IntegrityCheck::warning(
+                "Battle Results",
+                players_summary,
+                "No results are embedded. The recording player left before the battle ended or they were removed.".to_string(),
+            )
+        } else if players.len() != replay_file.meta.vehicles.len() {
+            IntegrityCheck::failed(
+                "Battle Results",
+                players_summary,
+                format!("The metadata lists {} players", replay_file.meta.vehicles.len()),
+            )
+        } else if battle_report.self_entity().results_info().is_none() {
+            IntegrityCheck::failed("Battle Results", players_summary, "The recording player has no results".to_string())
+        } else {
+            // Damage by source can't add up to more than the total damage
+            let inconsistent: Vec<String> = players
+                .iter()
+                .filter(|entity| {
+                    let results = entity.results_info();
+                    let total = results_value(results, DAMAGE_INDEX).unwrap_or_default();
+                    let by_source: i64 = DAMAGE_SOURCES.iter().filter_map(|(index, _)| results_value(results, *index)).sum();
+                    by_source > total
+                })
+                .filter_map(|entity| Some(entity.player()?.name().to_string()))
+                .collect();
+            if inconsistent.is_empty() {
+                IntegrityCheck::passed("Battle Results", players_summary)
+            } else {
+                IntegrityCheck::failed("Battle Results", players_summary, format!("Damage doesn't add up for {}", inconsistent.join(", ")))
+            }
+        });
+
+        Ok(report)
+    }
+
+    fn verdict(&self) -> &'static str {
+        if self.checks.iter().any(|check| matches!(check.status, CheckStatus::Failed(_))) {
+            "Failed"
+        } else if self.checks.iter().any(|check| matches!(check.status, CheckStatus::Warning(_))) {
+            "Needs Review"
+        } else {
+            "Passed"
+        }
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut md = String::new();
+        let _ = writeln!(md, "# Integrity Report: {}\n", self.file_name);
+        let _ = writeln!(md, "**Verdict:** {}\n", self.verdict());
+        let _ = writeln!(md, "| Check | Value | Result |");
+        let _ = writeln!(md, "|---|---|---|");
+        for check in &self.checks {
+            let result = match &check.status {
+                CheckStatus::Pending => "Not checked".to_string(),
+                CheckStatus::Passed => "OK".to_string(),
+                CheckStatus::Warning(message) => format!("Warning: {}", message),
+                CheckStatus::Failed(message) => format!("Failed: {}", message),
+            };
+            let _ = writeln!(md, "| {} | {} | {} |", check.name, check.value, result);
+        }
+
+        md
+    }
+}
+
+/// State of the integrity report window
+pub struct IntegrityWindow {
+    pub file_name: String,
+    pub report: Option<IntegrityReport>,
+}
+
+impl ToolkitTabViewer<'_> {
+    pub fn start_integrity_check(&mut self, path: &Path) {
+        let Some(wows_data) = self.tab_state.world_of_warships_data.as_ref() else {
+            return;
+        };
+        let (metadata_provider, game_version) = {
+            let wows_data = wows_data.read();
+            (wows_data.game_metadata.clone(), wows_data.game_version)
+        };
+        let Some(metadata_provider) = metadata_provider else {
+            return;
+        };
+
+        crate::update_background_task!(
+            self.tab_state.background_task,
+            Some(task::start_verifying_replay(path.to_path_buf(), metadata_provider, game_version))
+        );
+        self.tab_state.integrity_window = Some(IntegrityWindow {
+            file_name: replay_key(path),
+            report: None,
+        });
+    }
+
+    pub fn build_integrity_window(&mut self, ctx: &egui::Context) {
+        let Some(window) = self.tab_state.integrity_window.as_ref() else {
+            return;
+        };
+
+        let mut is_open = true;
+        egui::Window::new(format!("{} Integrity Report", icons::SEAL_CHECK))
+            .open(&mut is_open)
+            .default_size((600.0, 400.0))
+            .show(ctx, |ui| {
+                ui.label(window.file_name.as_str());
+                let Some(report) = window.report.as_ref() else {
+                    ui.label("Verifying replay...");
+                    return;
+                };
+
+                ui.heading(format!("Verdict: {}", report.verdict()));
+                egui::Grid::new("integrity_checks_grid").num_columns(2).spacing([10.0, 8.0]).show(ui, |ui| {
+                    for check in &report.checks {
+                        let (icon, message) = match &check.status {
+                            CheckStatus::Pending => (RichText::new(icons::HOURGLASS), None),
+                            CheckStatus::Passed => (RichText::new(icons::CHECK_CIRCLE).color(Color32::LIGHT_GREEN), None),
+                            CheckStatus::Warning(message) => (RichText::new(icons::WARNING).color(Color32::YELLOW), Some(message)),
+                            CheckStatus::Failed(message) => (RichText::new(icons::X_CIRCLE).color(Color32::LIGHT_RED), Some(message)),
+                        };
+
+                        ui.label(icon);
+                        ui.vertical(|ui| {
+                            ui.strong(check.name);
+                            if !check.value.is_empty() {
+                                ui.add(egui::Label::new(RichText::new(check.value.as_str()).monospace()).wrap());
+                            }
+                            if let Some(message) = message {
+                                ui.label(message.as_str());
+                            }
+                        });
+                        ui.end_row();
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    if ui.button(format!("{} Copy Markdown", icons::COPY)).clicked() {
+                        ui.output_mut(|output| output.copied_text = report.to_markdown());
+                    }
+                    if ui.button(format!("{} Save Markdown...", icons::FLOPPY_DISK)).clicked() {
+                        let file_name = format!("{} integrity.md", report.file_name);
+                        if let Some(path) = rfd::FileDialog::new().add_filter("Markdown", &["md"]).set_file_name(file_name).save_file() {
+                            let result = std::fs::write(&path, report.to_markdown());
+                            *self.tab_state.timed_message.write() = Some(TimedMessage::file_exported(path, result));
+                        }
+                    }
+                });
+            });
+
+        if !is_open {
+            self.tab_state.integrity_window = None;
+        }
+    }
+}
@@ -0,0 +1,194 @@
+use std::{collections::HashMap, path::Path};
+
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone};
+use tracing::error;
+
+use crate::{error::ToolkitError, icons, player_tracker::PlayerTracker, tournament::REPLAY_DATE_FORMAT, usage_stats};
+
+/// Column names other tools use for each field, compared after [normalize_column]
+const ID_COLUMNS: [&str; 6] = ["accountid", "account", "dbid", "playerid", "spaid", "id"];
+const NAME_COLUMNS: [&str; 5] = ["nickname", "name", "playername", "player", "username"];
+const CLAN_COLUMNS: [&str; 3] = ["clantag", "clan", "tag"];
+const CLAN_ID_COLUMNS: [&str; 1] = ["clanid"];
+const DATE_COLUMNS: [&str; 8] = ["lastseen", "lastbattle", "battletime", "datetime", "date", "time", "timestamp", "seen"];
+const ARENA_COLUMNS: [&str; 3] = ["arenaid", "arenauniqueid", "battleid"];
+const NOTES_COLUMNS: [&str; 3] = ["notes", "note", "comment"];
+
+/// One player row exported by another tool
+pub struct ImportedPlayer {
+    pub db_id: i64,
+    pub name: String,
+    pub clan: String,
+    pub clan_id: i64,
+    pub seen: Option<DateTime<Local>>,
+    pub arena_id: Option<i64>,
+    pub notes: String,
+}
+
+/// Lowercases a column name and drops separators so `Account ID`, `account_id` and `accountId` match
+fn normalize_column(column: &str) -> String {
+    column.chars().filter(|c| c.is_ascii_alphanumeric()).map(|c| c.to_ascii_lowercase()).collect()
+}
+
+fn parse_date(value: &str) -> Option<DateTime<Local>> {
+    let value = value.trim();
+    if let Ok(date) = DateTime::parse_from_rfc3339(value) {
+        return Some(date.with_timezone(&Local));
+    }
+    // Unix timestamps, in seconds or milliseconds
+    if let Ok(timestamp) = value.parse::<i64>() {
+        let timestamp = if timestamp > 100_000_000_000 { timestamp / 1000 } else { timestamp };
+        return Local.timestamp_opt(timestamp, 0).single();
+    }
+
+    let naive = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y/%m/%d %H:%M:%S", REPLAY_DATE_FORMAT]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .or_else(|| {
+            ["%Y-%m-%d", "%Y/%m/%d", "%d.%m.%Y"]
+                .iter()
+                .find_map(|format| NaiveDate::parse_from_str(value, format).ok())
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })?;
+    Local.from_local_datetime(&naive).single()
+}
+
+/// Splits one CSV line, handling quoted fields and doubled quotes
+fn parse_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' | ';' | '\t' if !in_quotes => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+
+    fields
+}
+
+/// Builds a player from a row's fields keyed by normalized column name. Rows without an account ID are skipped,
+/// since names aren't unique over time.
+fn player_from_fields(fields: &HashMap<String, String>) -> Option<ImportedPlayer> {
+    let field = |columns: &[&str]| {
+        columns
+            .iter()
+            .find_map(|column| fields.get(*column).map(|value| value.trim()).filter(|value| !value.is_empty()))
+    };
+
+    Some(ImportedPlayer {
+        db_id: field(&ID_COLUMNS)?.parse().ok().filter(|db_id| *db_id > 0)?,
+        name: field(&NAME_COLUMNS).unwrap_or_default().to_string(),
+        clan: field(&CLAN_COLUMNS).unwrap_or_default().trim_matches(['[', ']']).to_string(),
+        clan_id: field(&CLAN_ID_COLUMNS).and_then(|clan_id| clan_id.parse().ok()).unwrap_or_default(),
+        seen: field(&DATE_COLUMNS).and_then(parse_date),
+        arena_id: field(&ARENA_COLUMNS).and_then(|arena_id| arena_id.parse().ok()),
+        notes: field(&NOTES_COLUMNS).unwrap_or_default().to_string(),
+    })
+}
+
+fn parse_csv(data: &str) -> Result<Vec<ImportedPlayer>, ToolkitError> {
+    let mut lines = data.lines().filter(|line| !line.trim().is_empty());
+    let header: Vec<String> = lines
+        .next()
+        .map(|header| {
+            parse_csv_line(header.trim_start_matches('\u{feff}'))
+                .iter()
+                .map(|column| normalize_column(column))
+                .collect()
+        })
+        .ok_or_else(|| ToolkitError::InvalidImport("the file is empty".to_string()))?;
+    if !ID_COLUMNS.iter().any(|column| header.iter().any(|header| header == column)) {
+        return Err(ToolkitError::InvalidImport("no account ID column was found".to_string()));
+    }
+
+    Ok(lines
+        .filter_map(|line| {
+            let fields = header.iter().cloned().zip(parse_csv_line(line)).collect();
+            player_from_fields(&fields)
+        })
+        .collect())
+}
+
+/// Reads a JSON array of player objects, either at the top level or as the first array in an object
+fn parse_json(data: &str) -> Result<Vec<ImportedPlayer>, ToolkitError> {
+    let value: serde_json::Value = serde_json::from_str(data).map_err(|e| ToolkitError::InvalidImport(e.to_string()))?;
+    let rows = match value {
+        serde_json::Value::Array(rows) => rows,
+        serde_json::Value::Object(object) => object
+            .into_iter()
+            .find_map(|(_, value)| match value {
+                serde_json::Value::Array(rows) => Some(rows),
+                _ => None,
+            })
+            .ok_or_else(|| ToolkitError::InvalidImport("no list of players was found".to_string()))?,
+        _ => return Err(ToolkitError::InvalidImport("no list of players was found".to_string())),
+    };
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|row| {
+            let serde_json::Value::Object(row) = row else {
+                return None;
+            };
+            let fields = row
+                .into_iter()
+                .map(|(key, value)| {
+                    let value = match value {
+                        serde_json::Value::String(value) => value,
+                        value => value.to_string(),
+                    };
+                    (normalize_column(&key), value)
+                })
+                .collect();
+            player_from_fields(&fields)
+        })
+        .collect())
+}
+
+/// Reads players from a CSV or JSON export made by another community tool, like a MatchMaking Monitor history
+/// or a WoWS Stats & Numbers player list
+fn read_players(path: &Path) -> Result<Vec<ImportedPlayer>, ToolkitError> {
+    let data = std::fs::read_to_string(path)?;
+    let is_json = path.extension().map(|extension| extension.eq_ignore_ascii_case("json")).unwrap_or(false);
+    let players = if is_json { parse_json(&data)? } else { parse_csv(&data)? };
+    if players.is_empty() {
+        return Err(ToolkitError::InvalidImport("no rows had an account ID".to_string()));
+    }
+
+    Ok(players)
+}
+
+/// Asks for an export to import into the tracker. Returns a message describing the result, or `None` if no
+/// file was picked.
+pub fn import_players(player_tracker: &mut PlayerTracker) -> Option<String> {
+    let path = rfd::FileDialog::new().add_filter("Player exports", &["csv", "json", "txt"]).pick_file()?;
+    let message = match read_players(&path) {
+        Ok(players) => {
+            usage_stats::record_feature("Import players");
+            let rows = players.len();
+            let (added, updated) = player_tracker.import_players(players);
+            format!(
+                "{} Imported {} rows: {} new players, {} existing players updated",
+                icons::CHECK_CIRCLE,
+                rows,
+                added,
+                updated
+            )
+        }
+        Err(e) => {
+            error!("failed to import players from {:?}: {:?}", path, e);
+            format!("{} {}", icons::WARNING, e)
+        }
+    };
+
+    Some(message)
+}
@@ -1,16 +1,61 @@
 #![warn(clippy::all, rust_2018_idioms)]
 #![allow(clippy::blocks_in_if_conditions)]
+mod accessibility;
 mod app;
+mod app_lock;
+mod autostart;
+mod backup;
 mod build_tracker;
+mod clan;
+mod collections;
+mod command_palette;
+mod comparison;
+mod connectivity;
+mod contribution;
+mod crash_report;
+mod diagnostics;
+mod download;
 mod error;
+mod event_bus;
+mod expected_values;
 mod file_unpacker;
+mod game_launcher;
 mod game_params;
+mod health;
+mod integrity;
+mod legacy_import;
+mod lineups;
+mod linked_video;
+mod loadout;
+mod map_stats;
+mod map_veto;
+mod matchmaking;
+mod notifications;
+mod palette;
+mod parse_diagnostics;
 mod plaintext_viewer;
+mod player_extraction;
 mod player_tracker;
+mod protocol_handler;
+mod region;
+mod replay_cleanup;
 mod replay_parser;
+mod replay_titles;
+mod scheduler;
+mod scouting;
+mod setup_wizard;
+mod ship_picker;
+mod storage;
 mod task;
+mod tournament;
+mod tracker_charts;
+mod tray;
+mod undo;
+mod usage_stats;
 mod util;
 mod wows_data;
 pub use app::WowsToolkitApp;
+pub use crash_report::install_panic_hook;
+pub use protocol_handler::{forward_to_running_instance, DeepLink};
 pub const APP_NAME: &str = "WoWs Toolkit";
 pub(crate) use egui_phosphor::regular as icons;
@@ -0,0 +1,234 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app::ToolkitTabViewer,
+    icons,
+    map_veto::GameMap,
+    ship_picker::{self, ShipPicker},
+};
+
+/// Competitive lineups range from 7 ships in Clan Battles to 12 in Randoms
+const MAX_LINEUP_SIZE: usize = 12;
+
+/// A planned team composition
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Lineup {
+    pub name: String,
+    /// Map the lineup is planned for, by its ID under `spaces`. `None` is for any map.
+    pub map: Option<String>,
+    pub ships: Vec<String>,
+}
+
+/// State of the lineup planner window
+#[derive(Default)]
+pub struct LineupWindow {
+    selected: Option<usize>,
+    compare_with: Option<usize>,
+    /// Only lineups for this map are listed
+    map_filter: Option<String>,
+}
+
+/// Class and tier makeup of a lineup
+struct LineupSummary {
+    ships: usize,
+    tiers: Option<(u32, u32)>,
+    /// Ships per localized class name
+    classes: BTreeMap<String, usize>,
+    /// Ships which aren't in the game data
+    unknown: usize,
+}
+
+impl LineupSummary {
+    fn new(lineup: &Lineup, catalog: &[ship_picker::ShipEntry]) -> Self {
+        let mut summary = LineupSummary {
+            ships: 0,
+            tiers: None,
+            classes: BTreeMap::new(),
+            unknown: 0,
+        };
+        for name in lineup.ships.iter().filter(|name| !name.trim().is_empty()) {
+            summary.ships += 1;
+            let Some(ship) = catalog.iter().find(|ship| ship.name == *name) else {
+                summary.unknown += 1;
+                continue;
+            };
+            *summary.classes.entry(ship.class.clone()).or_default() += 1;
+            summary.tiers = Some(match summary.tiers {
+                Some((min, max)) => (min.min(ship.tier), max.max(ship.tier)),
+                None => (ship.tier, ship.tier),
+            });
+        }
+
+        summary
+    }
+
+    fn show(&self, ui: &mut egui::Ui) {
+        ui.label(format!("{} ships", self.ships));
+        if let Some((min, max)) = self.tiers {
+            if min == max {
+                ui.label(format!("Tier {}", min));
+            } else {
+                ui.label(format!("Tiers {}-{}", min, max));
+            }
+        }
+        for (class, count) in &self.classes {
+            ui.label(format!("{} {}", count, class));
+        }
+        if self.unknown > 0 {
+            ui.weak(format!("{} ships not found in the game data", self.unknown));
+        }
+    }
+}
+
+fn map_label(maps: &[GameMap], map: Option<&str>) -> String {
+    match map {
+        Some(id) => maps.iter().find(|map| map.id == id).map(|map| map.name.clone()).unwrap_or_else(|| id.to_string()),
+        None => "Any Map".to_string(),
+    }
+}
+
+/// Combo box for picking a map, or any map
+fn map_combo(ui: &mut egui::Ui, id_salt: &str, maps: &[GameMap], selected: &mut Option<String>) {
+    egui::ComboBox::from_id_salt(id_salt)
+        .selected_text(map_label(maps, selected.as_deref()))
+        .show_ui(ui, |ui| {
+            ui.selectable_value(selected, None, "Any Map");
+            for map in maps {
+                ui.selectable_value(selected, Some(map.id.clone()), map.name.as_str());
+            }
+        });
+}
+
+impl ToolkitTabViewer<'_> {
+    pub fn build_lineup_window(&mut self, ctx: &egui::Context) {
+        if self.tab_state.lineup_window.is_none() {
+            return;
+        }
+
+        let maps = self.game_maps();
+        let catalog = self
+            .tab_state
+            .world_of_warships_data
+            .as_ref()
+            .and_then(|wows_data| wows_data.read().game_metadata.clone())
+            .map(|metadata_provider| ship_picker::ship_catalog(ctx, &metadata_provider));
+        let Some(window) = self.tab_state.lineup_window.as_mut() else {
+            return;
+        };
+        let lineups = &mut self.tab_state.settings.lineups;
+        let wows_data = self.tab_state.world_of_warships_data.as_ref();
+
+        let mut is_open = true;
+        egui::Window::new(format!("{} Lineups", icons::USERS_THREE))
+            .open(&mut is_open)
+            .default_size((700.0, 450.0))
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Show Lineups For");
+                    map_combo(ui, "lineup_map_filter", &maps, &mut window.map_filter);
+                    if ui.button(format!("{} New Lineup", icons::PLUS)).clicked() {
+                        lineups.push(Lineup {
+                            name: format!("Lineup {}", lineups.len() + 1),
+                            map: window.map_filter.clone(),
+                            ships: Vec::new(),
+                        });
+                        window.selected = Some(lineups.len() - 1);
+                        window.compare_with = None;
+                    }
+                });
+                ui.separator();
+
+                ui.horizontal_top(|ui| {
+                    ui.vertical(|ui| {
+                        ui.set_width(160.0);
+                        for (i, lineup) in lineups.iter().enumerate() {
+                            // Lineups for any map are shown for every map
+                            if window.map_filter.is_some() && lineup.map.is_some() && lineup.map != window.map_filter {
+                                continue;
+                            }
+                            if ui.selectable_label(window.selected == Some(i), lineup.name.as_str()).clicked() {
+                                window.selected = Some(i);
+                                window.compare_with = window.compare_with.filter(|compare_with| *compare_with != i);
+                            }
+                        }
+                    });
+                    ui.separator();
+
+                    let Some(selected) = window.selected.filter(|selected| *selected < lineups.len()) else {
+                        ui.label("Select or create a lineup.");
+                        return;
+                    };
+
+                    let mut delete = false;
+                    ui.vertical(|ui| {
+                        let lineup = &mut lineups[selected];
+                        ui.horizontal(|ui| {
+                            ui.text_edit_singleline(&mut lineup.name);
+                            map_combo(ui, "lineup_map", &maps, &mut lineup.map);
+                            if ui.button(format!("{} Delete", icons::TRASH)).clicked() {
+                                delete = true;
+                            }
+                        });
+
+                        let mut removed = None;
+                        for (i, ship) in lineup.ships.iter_mut().enumerate() {
+                            ui.horizontal(|ui| {
+                                ShipPicker::new(&format!("lineup_ship_{}", i), ship, wows_data).show(ui);
+                                if ui.button(icons::X_CIRCLE).on_hover_text("Remove").clicked() {
+                                    removed = Some(i);
+                                }
+                            });
+                        }
+                        if let Some(i) = removed {
+                            lineup.ships.remove(i);
+                        }
+                        if ui
+                            .add_enabled(lineup.ships.len() < MAX_LINEUP_SIZE, egui::Button::new(format!("{} Add Ship", icons::PLUS)))
+                            .clicked()
+                        {
+                            lineup.ships.push(String::new());
+                        }
+                    });
+                    if delete {
+                        lineups.remove(selected);
+                        window.selected = None;
+                        window.compare_with = None;
+                        return;
+                    }
+
+                    ui.separator();
+                    ui.vertical(|ui| {
+                        let Some(catalog) = catalog.as_ref() else {
+                            ui.label("Load game data to see the lineup's makeup.");
+                            return;
+                        };
+
+                        ui.strong(lineups[selected].name.as_str());
+                        LineupSummary::new(&lineups[selected], catalog).show(ui);
+                        ui.add_space(10.0);
+
+                        let compare_label = window.compare_with.and_then(|i| lineups.get(i)).map(|lineup| lineup.name.clone());
+                        egui::ComboBox::from_id_salt("lineup_compare_with")
+                            .selected_text(compare_label.unwrap_or_else(|| "Compare With...".to_string()))
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut window.compare_with, None, "None");
+                                for (i, lineup) in lineups.iter().enumerate().filter(|(i, _)| *i != selected) {
+                                    ui.selectable_value(&mut window.compare_with, Some(i), lineup.name.as_str());
+                                }
+                            });
+                        if let Some(other) = window.compare_with.and_then(|i| lineups.get(i)) {
+                            ui.strong(format!("{} ({})", other.name, map_label(&maps, other.map.as_deref())));
+                            LineupSummary::new(other, catalog).show(ui);
+                        }
+                    });
+                });
+            });
+
+        if !is_open {
+            self.tab_state.lineup_window = None;
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app::{TimedMessage, ToolkitTabViewer},
+    icons,
+    replay_titles::replay_key,
+    usage_stats,
+};
+
+/// A screen recording of a battle, linked to its replay so battle times can be looked up in the footage
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LinkedVideo {
+    pub path: PathBuf,
+    /// Where in the video the battle timer starts
+    pub offset_secs: f64,
+}
+
+impl LinkedVideo {
+    /// Timestamp in the video, e.g. `1:02:03.5`, of the given time since the battle started
+    pub fn timestamp(&self, battle_time: Duration) -> String {
+        let total = (self.offset_secs + battle_time.as_secs_f64()).max(0.0);
+        let hours = (total / 3600.0) as u64;
+        let minutes = (total / 60.0) as u64 % 60;
+        let seconds = total % 60.0;
+        if hours > 0 {
+            format!("{}:{:02}:{:04.1}", hours, minutes, seconds)
+        } else {
+            format!("{}:{:04.1}", minutes, seconds)
+        }
+    }
+}
+
+/// State of the window used to link a video to a replay
+pub struct LinkedVideoEditor {
+    key: String,
+    video: LinkedVideo,
+}
+
+impl ToolkitTabViewer<'_> {
+    /// Video linked to the replay open in the replay viewer
+    pub fn current_linked_video(&self) -> Option<&LinkedVideo> {
+        let path = self.current_replay_path()?;
+        self.tab_state.settings.linked_videos.get(&replay_key(path))
+    }
+
+    pub fn edit_linked_video(&mut self, path: &Path) {
+        let key = replay_key(path);
+        let video = self.tab_state.settings.linked_videos.get(&key).cloned().unwrap_or_default();
+        self.tab_state.linked_video_editor = Some(LinkedVideoEditor { key, video });
+    }
+
+    /// Copies where `battle_time` is in the linked video to the clipboard
+    pub fn copy_video_timestamp(&self, ui: &egui::Ui, video: &LinkedVideo, battle_time: Duration) {
+        let timestamp = video.timestamp(battle_time);
+        *self.tab_state.timed_message.write() = Some(TimedMessage::new(format!("{} Copied video timestamp {}", icons::CHECK_CIRCLE, timestamp)));
+        ui.output_mut(|output| output.copied_text = timestamp);
+    }
+
+    pub fn build_linked_video_editor(&mut self, ctx: &egui::Context) {
+        let Some(editor) = self.tab_state.linked_video_editor.as_mut() else {
+            return;
+        };
+
+        let mut is_open = true;
+        let mut save = false;
+        let mut unlink = false;
+        egui::Window::new(format!("{} Link Video", icons::FILM_STRIP))
+            .open(&mut is_open)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.label(editor.key.as_str());
+                egui::Grid::new("linked_video_editor_grid").num_columns(2).show(ui, |ui| {
+                    ui.label("Video");
+                    ui.horizontal(|ui| {
+                        if editor.video.path.as_os_str().is_empty() {
+                            ui.label("None");
+                        } else {
+                            ui.label(editor.video.path.to_string_lossy());
+                        }
+                        if ui.button("Choose...").clicked() {
+                            if let Some(path) = rfd::FileDialog::new().add_filter("Videos", &["mp4", "mkv", "mov", "avi", "webm"]).pick_file() {
+                                editor.video.path = path;
+                            }
+                        }
+                    });
+                    ui.end_row();
+
+                    ui.label("Battle Starts At (Seconds)");
+                    ui.add(egui::DragValue::new(&mut editor.video.offset_secs).speed(0.1).range(0.0..=f64::MAX))
+                        .on_hover_text("How far into the video the battle timer starts counting down");
+                    ui.end_row();
+                });
+
+                ui.horizontal(|ui| {
+                    save = ui.add_enabled(!editor.video.path.as_os_str().is_empty(), egui::Button::new("Save")).clicked();
+                    unlink = ui.button("Unlink").clicked();
+                });
+            });
+
+        if save || unlink {
+            if let Some(editor) = self.tab_state.linked_video_editor.take() {
+                if save {
+                    usage_stats::record_feature("Link video");
+                    self.tab_state.settings.linked_videos.insert(editor.key, editor.video);
+                } else {
+                    self.tab_state.settings.linked_videos.remove(&editor.key);
+                }
+            }
+        } else if !is_open {
+            self.tab_state.linked_video_editor = None;
+        }
+    }
+}
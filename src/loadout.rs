@@ -0,0 +1,66 @@
+use wows_replays::analyzer::battle_controller::VehicleEntity;
+use wowsunpack::{
+    data::ResourceLoader,
+    game_params::{provider::GameMetadataProvider, types::GameParamProvider},
+};
+
+/// Localized names of what a player had mounted on their ship
+pub struct Loadout {
+    pub modules: Vec<String>,
+    pub upgrades: Vec<String>,
+    pub consumables: Vec<String>,
+    pub signals: Vec<String>,
+}
+
+/// Localized names of the given GameParams, falling back to the param's name when there's no translation
+fn param_names(ids: &[u32], metadata_provider: &GameMetadataProvider) -> Vec<String> {
+    ids.iter()
+        .filter_map(|id| {
+            let param = metadata_provider.game_param_by_id(*id)?;
+            Some(
+                metadata_provider
+                    .localized_name_from_param(&param)
+                    .map(ToString::to_string)
+                    .unwrap_or_else(|| param.name().to_string()),
+            )
+        })
+        .collect()
+}
+
+impl Loadout {
+    pub fn new(entity: &VehicleEntity, metadata_provider: &GameMetadataProvider) -> Self {
+        let config = entity.props().ship_config();
+
+        Self {
+            modules: param_names(config.units(), metadata_provider),
+            upgrades: param_names(config.modernization(), metadata_provider),
+            consumables: param_names(config.abilities(), metadata_provider),
+            signals: param_names(config.signals(), metadata_provider),
+        }
+    }
+
+    /// Short summary for the scoreboard, e.g. `6 upgrades, 3 consumables, 8 flags`
+    pub fn summary(&self) -> String {
+        format!(
+            "{} upgrades, {} consumables, {} flags",
+            self.upgrades.len(),
+            self.consumables.len(),
+            self.signals.len()
+        )
+    }
+
+    /// Every item, grouped by kind
+    pub fn details(&self) -> String {
+        [
+            ("Modules", &self.modules),
+            ("Upgrades", &self.upgrades),
+            ("Consumables", &self.consumables),
+            ("Signals", &self.signals),
+        ]
+        .iter()
+        .filter(|(_, names)| !names.is_empty())
+        .map(|(kind, names)| format!("{}:\n{}", kind, names.iter().map(|name| format!("  {}", name)).collect::<Vec<_>>().join("\n")))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+    }
+}
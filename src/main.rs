@@ -65,13 +65,25 @@ fn main() -> eframe::Result<()> {
         viewport: egui::ViewportBuilder::default()
             .with_inner_size([600.0, 400.0])
             .with_min_inner_size([400.0, 300.0])
+            .with_drag_and_drop(true)
             .with_icon(eframe::icon_data::from_png_bytes(icon_data).expect("failed to load application icon"))
             .with_title(format!("{} v{}", wows_toolkit::APP_NAME, env!("CARGO_PKG_VERSION"))),
         ..Default::default()
     };
 
-    // Check to see if we need to delete the previous application
+    wows_toolkit::install_panic_hook();
+
     let args: Vec<String> = env::args().collect();
+
+    // If we were launched from a wows-toolkit:// link and the app is already open, let
+    // that instance handle it instead of opening a second window.
+    if let Some(link) = wows_toolkit::DeepLink::from_args(args.iter().cloned()) {
+        if wows_toolkit::forward_to_running_instance(&link) {
+            return Ok(());
+        }
+    }
+
+    // Check to see if we need to delete the previous application
     if args.len() == 2 {
         let current_path = Path::new(args[0].as_str());
         let old_path = Path::new(args[1].as_str());
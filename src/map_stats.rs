@@ -0,0 +1,169 @@
+use std::{collections::BTreeMap, fmt::Write};
+
+use egui_extras::{Column, TableBuilder};
+
+use crate::{
+    app::{TimedMessage, ToolkitTabViewer},
+    icons,
+    integrity::format_secs,
+    replay_parser::Replay,
+};
+
+/// Battles played on one map
+#[derive(Default)]
+pub struct MapRecord {
+    /// Localized name from the battle report
+    name: String,
+    /// How long each battle ran, in seconds
+    lengths: Vec<f32>,
+}
+
+impl MapRecord {
+    fn battles(&self) -> usize {
+        self.lengths.len()
+    }
+
+    fn average_length(&self) -> f32 {
+        self.lengths.iter().sum::<f32>() / self.lengths.len().max(1) as f32
+    }
+
+    fn shortest(&self) -> f32 {
+        self.lengths.iter().copied().fold(f32::INFINITY, f32::min)
+    }
+
+    fn longest(&self) -> f32 {
+        self.lengths.iter().copied().fold(0.0, f32::max)
+    }
+}
+
+/// Battle counts and lengths per map across the replays in the listing
+#[derive(Default)]
+pub struct MapStats {
+    /// Records by map ID under `spaces`, e.g. `16_OC_bees_to_honey`
+    pub maps: BTreeMap<String, MapRecord>,
+}
+
+impl MapStats {
+    pub fn add_battle(&mut self, replay: &Replay) {
+        let (Some(report), Some(battle_length)) = (replay.battle_report.as_ref(), replay.battle_length) else {
+            return;
+        };
+
+        let map_id = replay.replay_file.meta.mapName.trim_start_matches("spaces/").to_string();
+        let record = self.maps.entry(map_id).or_default();
+        record.name = report.map_name().to_string();
+        record.lengths.push(battle_length);
+    }
+
+    pub fn battles(&self) -> usize {
+        self.maps.values().map(MapRecord::battles).sum()
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("Map,Battles,Average Length,Shortest,Longest\n");
+        for record in self.maps.values() {
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{}",
+                record.name,
+                record.battles(),
+                format_secs(record.average_length()),
+                format_secs(record.shortest()),
+                format_secs(record.longest())
+            );
+        }
+
+        csv
+    }
+}
+
+/// State of the map stats window
+#[derive(Default)]
+pub struct MapStatsWindow {
+    pub stats: Option<MapStats>,
+    /// Map whose details are shown, by ID
+    selected: Option<String>,
+}
+
+impl ToolkitTabViewer<'_> {
+    pub fn build_map_stats_window(&mut self, ctx: &egui::Context) {
+        let Some(window) = self.tab_state.map_stats_window.as_mut() else {
+            return;
+        };
+
+        let mut is_open = true;
+        egui::Window::new(format!("{} Map Stats", icons::MAP_TRIFOLD))
+            .open(&mut is_open)
+            .default_size((550.0, 450.0))
+            .show(ctx, |ui| {
+                let Some(stats) = window.stats.as_ref() else {
+                    ui.label("Analyzing replays...");
+                    return;
+                };
+                if stats.maps.is_empty() {
+                    ui.label("No replays from the current game version could be analyzed.");
+                    return;
+                }
+
+                ui.horizontal(|ui| {
+                    ui.label(format!("Based on {} battles from the current game version.", stats.battles()));
+                    if ui.button(format!("{} Save CSV...", icons::FLOPPY_DISK)).clicked() {
+                        if let Some(path) = rfd::FileDialog::new().add_filter("CSV", &["csv"]).set_file_name("map_stats.csv").save_file() {
+                            let result = std::fs::write(&path, stats.to_csv());
+                            *self.tab_state.timed_message.write() = Some(TimedMessage::file_exported(path, result));
+                        }
+                    }
+                });
+
+                if let Some(record) = window.selected.as_ref().and_then(|map_id| stats.maps.get(map_id)) {
+                    ui.group(|ui| {
+                        ui.strong(record.name.as_str());
+                        ui.label(format!("{} battles, {} on average", record.battles(), format_secs(record.average_length())));
+                        ui.label(format!("Shortest {}, longest {}", format_secs(record.shortest()), format_secs(record.longest())));
+                    });
+                } else {
+                    ui.label("Select a map to see its details.");
+                }
+
+                TableBuilder::new(ui)
+                    .striped(true)
+                    .column(Column::remainder())
+                    .column(Column::auto())
+                    .column(Column::auto())
+                    .header(20.0, |mut header| {
+                        header.col(|ui| {
+                            ui.strong("Map");
+                        });
+                        header.col(|ui| {
+                            ui.strong("Battles");
+                        });
+                        header.col(|ui| {
+                            ui.strong("Average Length");
+                        });
+                    })
+                    .body(|mut body| {
+                        let mut maps: Vec<_> = stats.maps.iter().collect();
+                        maps.sort_by(|(_, a), (_, b)| b.battles().cmp(&a.battles()).then_with(|| a.name.cmp(&b.name)));
+                        for (map_id, record) in maps {
+                            body.row(18.0, |mut row| {
+                                row.col(|ui| {
+                                    if ui.selectable_label(window.selected.as_ref() == Some(map_id), record.name.as_str()).clicked() {
+                                        window.selected = Some(map_id.clone());
+                                    }
+                                });
+                                row.col(|ui| {
+                                    ui.label(record.battles().to_string());
+                                });
+                                row.col(|ui| {
+                                    ui.label(format_secs(record.average_length()));
+                                });
+                            });
+                        }
+                    });
+            });
+
+        if !is_open {
+            self.tab_state.map_stats_window = None;
+        }
+    }
+}
@@ -0,0 +1,312 @@
+use std::sync::Arc;
+
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app::{TimedMessage, ToolkitTabViewer},
+    icons, task,
+};
+
+/// A map which can be played, from the game's `spaces` directory
+#[derive(Debug, Clone)]
+pub struct GameMap {
+    /// Directory name under `spaces`, e.g. `16_OC_bees_to_honey`
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VetoAction {
+    Ban,
+    Pick,
+}
+
+/// Common pick/ban orders. Teams alternate, starting with the first team, and once the fixed steps are done
+/// maps are banned until one is left as the decider.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VetoFormat {
+    #[default]
+    BestOfOne,
+    BestOfThree,
+    BestOfFive,
+}
+
+impl VetoFormat {
+    const ALL: [VetoFormat; 3] = [VetoFormat::BestOfOne, VetoFormat::BestOfThree, VetoFormat::BestOfFive];
+
+    fn name(&self) -> &'static str {
+        match self {
+            VetoFormat::BestOfOne => "Best of 1",
+            VetoFormat::BestOfThree => "Best of 3",
+            VetoFormat::BestOfFive => "Best of 5",
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        match self {
+            VetoFormat::BestOfOne => "Teams ban in turn until one map is left",
+            VetoFormat::BestOfThree => "One ban each, one pick each, then bans until the decider is left",
+            VetoFormat::BestOfFive => "One ban each, two picks each, then bans until the decider is left",
+        }
+    }
+
+    fn fixed_steps(&self) -> &'static [VetoAction] {
+        use VetoAction::*;
+        match self {
+            VetoFormat::BestOfOne => &[],
+            VetoFormat::BestOfThree => &[Ban, Ban, Pick, Pick],
+            VetoFormat::BestOfFive => &[Ban, Ban, Pick, Pick, Pick, Pick],
+        }
+    }
+
+    /// Smallest pool the format can be run with
+    fn min_maps(&self) -> usize {
+        self.fixed_steps().len() + 1
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct VetoStep {
+    /// Index into [MapVeto::teams]
+    team: usize,
+    action: VetoAction,
+    map: String,
+}
+
+/// The map pool and the pick/ban session using it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MapVeto {
+    /// Map IDs which make up the pool
+    pub map_pool: Vec<String>,
+    format: VetoFormat,
+    teams: [String; 2],
+    steps: Vec<VetoStep>,
+}
+
+impl Default for MapVeto {
+    fn default() -> Self {
+        Self {
+            map_pool: Vec::new(),
+            format: VetoFormat::default(),
+            teams: ["Team A".to_string(), "Team B".to_string()],
+            steps: Vec::new(),
+        }
+    }
+}
+
+impl MapVeto {
+    /// Pool maps which haven't been picked or banned yet
+    fn remaining_maps(&self) -> Vec<&String> {
+        self.map_pool.iter().filter(|map| !self.steps.iter().any(|step| &step.map == *map)).collect()
+    }
+
+    /// Team and action for the next step, or `None` once only the decider is left
+    fn next_step(&self) -> Option<(usize, VetoAction)> {
+        if self.remaining_maps().len() <= 1 {
+            return None;
+        }
+
+        let index = self.steps.len();
+        let action = self.format.fixed_steps().get(index).copied().unwrap_or(VetoAction::Ban);
+        Some((index % 2, action))
+    }
+
+    fn is_started(&self) -> bool {
+        !self.steps.is_empty()
+    }
+
+    /// Text summary for sharing in chat
+    fn summary(&self, map_name: impl Fn(&str) -> String) -> String {
+        let mut lines = vec![format!("{} vs {} ({})", self.teams[0], self.teams[1], self.format.name())];
+        for step in &self.steps {
+            let verb = match step.action {
+                VetoAction::Ban => "banned",
+                VetoAction::Pick => "picked",
+            };
+            lines.push(format!("{} {} {}", self.teams[step.team], verb, map_name(&step.map)));
+        }
+
+        let mut maps: Vec<String> = self
+            .steps
+            .iter()
+            .filter(|step| step.action == VetoAction::Pick)
+            .map(|step| format!("{} ({} pick)", map_name(&step.map), self.teams[step.team]))
+            .collect();
+        if self.next_step().is_none() {
+            if let Some(decider) = self.remaining_maps().first() {
+                maps.push(format!("{} (decider)", map_name(decider)));
+            }
+        }
+        if !maps.is_empty() {
+            lines.push(String::new());
+            lines.extend(maps.iter().enumerate().map(|(i, map)| format!("Map {}: {}", i + 1, map)));
+        }
+
+        lines.join("\n")
+    }
+}
+
+impl ToolkitTabViewer<'_> {
+    /// Maps with a localized name in the loaded game data, sorted by name
+    pub fn game_maps(&self) -> Vec<GameMap> {
+        let Some(wows_data) = self.tab_state.world_of_warships_data.as_ref() else {
+            return Vec::new();
+        };
+        let wows_data = wows_data.read();
+        let Some(metadata_provider) = wows_data.game_metadata.as_ref() else {
+            return Vec::new();
+        };
+        let Some(spaces) = wows_data.file_tree.children().get("spaces") else {
+            return Vec::new();
+        };
+
+        // Test and port spaces don't have a localized name
+        spaces
+            .children()
+            .iter()
+            .filter(|(_, node)| !node.is_file())
+            .filter_map(|(id, _)| {
+                let name = metadata_provider.localized_name_from_id(&format!("IDS_SPACES/{}", id.to_uppercase()))?;
+                Some(GameMap { id: id.clone(), name })
+            })
+            .sorted_by(|a, b| a.name.cmp(&b.name))
+            .collect()
+    }
+
+    pub fn build_map_veto_tab(&mut self, ui: &mut egui::Ui) {
+        self.build_lineup_window(ui.ctx());
+        self.build_map_stats_window(ui.ctx());
+        let maps = self.game_maps();
+        let map_name = |id: &str| maps.iter().find(|map| map.id == id).map(|map| map.name.clone()).unwrap_or_else(|| id.to_string());
+        let veto = &mut self.tab_state.settings.map_veto;
+
+        ui.horizontal(|ui| {
+            ui.add_enabled_ui(!veto.is_started(), |ui| {
+                egui::ComboBox::from_id_salt("map_veto_format")
+                    .selected_text(veto.format.name())
+                    .show_ui(ui, |ui| {
+                        for format in VetoFormat::ALL {
+                            ui.selectable_value(&mut veto.format, format, format.name()).on_hover_text(format.description());
+                        }
+                    })
+                    .response
+                    .on_hover_text(veto.format.description());
+                for team in veto.teams.iter_mut() {
+                    ui.add(egui::TextEdit::singleline(team).desired_width(120.0));
+                }
+                if ui
+                    .button(format!("{} Swap", icons::ARROWS_CLOCKWISE))
+                    .on_hover_text("Swap which team goes first")
+                    .clicked()
+                {
+                    veto.teams.swap(0, 1);
+                }
+            });
+            if ui
+                .add_enabled(veto.is_started(), egui::Button::new(format!("{} Restart", icons::ARROW_CLOCKWISE)))
+                .clicked()
+            {
+                veto.steps.clear();
+            }
+            if ui
+                .add_enabled(veto.is_started(), egui::Button::new(format!("{} Undo Step", icons::ARROW_COUNTER_CLOCKWISE)))
+                .clicked()
+            {
+                veto.steps.pop();
+            }
+            if ui
+                .add_enabled(veto.is_started(), egui::Button::new(format!("{} Copy Summary", icons::COPY)))
+                .clicked()
+            {
+                ui.output_mut(|output| output.copied_text = veto.summary(&map_name));
+                *self.tab_state.timed_message.write() = Some(TimedMessage::new(format!("{} Veto summary copied", icons::CHECK_CIRCLE)));
+            }
+            if ui
+                .button(format!("{} Lineups...", icons::USERS_THREE))
+                .on_hover_text("Plan team compositions for each map")
+                .clicked()
+            {
+                self.tab_state.lineup_window.get_or_insert_with(Default::default);
+            }
+            if let (Some(replay_files), Some(wows_data)) = (self.tab_state.replay_files.as_ref(), self.tab_state.world_of_warships_data.as_ref()) {
+                if ui
+                    .button(format!("{} Map Stats...", icons::CHART_BAR))
+                    .on_hover_text("Battles played and average battle length per map across the listed replays")
+                    .clicked()
+                {
+                    crate::update_background_task!(
+                        self.tab_state.background_task,
+                        Some(task::start_collecting_map_stats(replay_files.keys().cloned().collect(), Arc::clone(wows_data)))
+                    );
+                    self.tab_state.map_stats_window = Some(Default::default());
+                }
+            }
+        });
+        ui.separator();
+
+        ui.columns(2, |columns| {
+            let ui = &mut columns[0];
+            ui.heading("Map Pool");
+            if maps.is_empty() {
+                ui.label("Load game data to list maps.");
+            }
+            ui.add_enabled_ui(!veto.is_started(), |ui| {
+                egui::ScrollArea::vertical().id_salt("map_veto_pool").show(ui, |ui| {
+                    for map in &maps {
+                        let mut in_pool = veto.map_pool.contains(&map.id);
+                        if ui.checkbox(&mut in_pool, map.name.as_str()).changed() {
+                            if in_pool {
+                                veto.map_pool.push(map.id.clone());
+                            } else {
+                                veto.map_pool.retain(|id| *id != map.id);
+                            }
+                        }
+                    }
+                });
+            });
+
+            let ui = &mut columns[1];
+            ui.heading("Veto");
+            if veto.map_pool.len() < veto.format.min_maps() {
+                ui.label(format!("{} needs at least {} maps in the pool.", veto.format.name(), veto.format.min_maps()));
+                return;
+            }
+
+            for step in &veto.steps {
+                let (icon, verb) = match step.action {
+                    VetoAction::Ban => (icons::X_CIRCLE, "banned"),
+                    VetoAction::Pick => (icons::CHECK_CIRCLE, "picked"),
+                };
+                ui.label(format!("{} {} {} {}", icon, veto.teams[step.team], verb, map_name(&step.map)));
+            }
+
+            match veto.next_step() {
+                Some((team, action)) => {
+                    let verb = match action {
+                        VetoAction::Ban => "ban",
+                        VetoAction::Pick => "pick",
+                    };
+                    ui.strong(format!("{} to {}:", veto.teams[team], verb));
+                    let mut chosen = None;
+                    ui.horizontal_wrapped(|ui| {
+                        for map in veto.remaining_maps() {
+                            if ui.button(map_name(map)).clicked() {
+                                chosen = Some(map.clone());
+                            }
+                        }
+                    });
+                    if let Some(map) = chosen {
+                        veto.steps.push(VetoStep { team, action, map });
+                    }
+                }
+                None => {
+                    if let Some(decider) = veto.remaining_maps().first() {
+                        ui.label(format!("{} Decider: {}", icons::FLAG, map_name(decider)));
+                    }
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,233 @@
+use std::{collections::BTreeMap, fmt::Write};
+
+use chrono::{NaiveDate, NaiveDateTime};
+use egui_plot::{Bar, BarChart, Legend, Line, Plot};
+use wowsunpack::game_params::{
+    provider::GameMetadataProvider,
+    types::{GameParamProvider, Species},
+};
+
+use crate::{
+    app::{TimedMessage, ToolkitTabViewer},
+    icons,
+    replay_parser::Replay,
+    tournament::REPLAY_DATE_FORMAT,
+    tracker_charts::{date_to_x, week_start, x_to_date},
+    util::ship_tier,
+};
+
+/// Radar consumables all have this in their GameParams name
+const RADAR_PARAM_NAME: &str = "RLSSearch";
+
+/// Who matchmaking put into one battle
+pub struct BattleComposition {
+    pub played_at: Option<NaiveDateTime>,
+    pub map: String,
+    pub game_type: String,
+    /// Difference between the highest and lowest tier in the battle
+    pub tier_spread: u32,
+    pub carriers: usize,
+    /// Ships with a radar consumable mounted
+    pub radars: usize,
+    pub divisions: usize,
+}
+
+/// Averages per battle for the week starting on the given Monday
+#[derive(Default, Clone, Copy)]
+struct WeeklyComposition {
+    battles: usize,
+    carriers: usize,
+    radars: usize,
+    divisions: usize,
+}
+
+impl WeeklyComposition {
+    fn per_battle(&self, value: usize) -> f64 {
+        value as f64 / self.battles.max(1) as f64
+    }
+}
+
+/// Matchmaking patterns across the replays in the listing
+#[derive(Default)]
+pub struct MatchmakingAnalysis {
+    pub battles: Vec<BattleComposition>,
+}
+
+impl MatchmakingAnalysis {
+    pub fn add_battle(&mut self, replay: &Replay) {
+        let Some(report) = replay.battle_report.as_ref() else {
+            return;
+        };
+        let metadata: &GameMetadataProvider = &replay.resource_loader;
+
+        let mut tiers = Vec::new();
+        let mut carriers = 0;
+        let mut radars = 0;
+        let mut divisions = Vec::new();
+        for entity in report.player_entities() {
+            let Some(player) = entity.player() else {
+                continue;
+            };
+            let ship = player.vehicle();
+            tiers.extend(ship_tier(ship));
+            if ship.species() == Some(Species::AirCarrier) {
+                carriers += 1;
+            }
+            let has_radar = entity
+                .props()
+                .ship_config()
+                .abilities()
+                .iter()
+                .filter_map(|id| metadata.game_param_by_id(*id))
+                .any(|param| param.name().contains(RADAR_PARAM_NAME));
+            if has_radar {
+                radars += 1;
+            }
+            // Division IDs are only unique within a team
+            if player.division_id() > 0 && !divisions.contains(&(player.relation() == 2, player.division_id())) {
+                divisions.push((player.relation() == 2, player.division_id()));
+            }
+        }
+
+        self.battles.push(BattleComposition {
+            played_at: NaiveDateTime::parse_from_str(&replay.replay_file.meta.dateTime, REPLAY_DATE_FORMAT).ok(),
+            map: report.map_name().to_string(),
+            game_type: report.game_type().to_string(),
+            tier_spread: tiers.iter().max().zip(tiers.iter().min()).map(|(max, min)| max - min).unwrap_or_default(),
+            carriers,
+            radars,
+            divisions: divisions.len(),
+        });
+    }
+
+    /// How many battles had each tier spread
+    fn tier_spreads(&self) -> BTreeMap<u32, usize> {
+        self.battles.iter().fold(BTreeMap::new(), |mut spreads, battle| {
+            *spreads.entry(battle.tier_spread).or_default() += 1;
+            spreads
+        })
+    }
+
+    fn weekly(&self) -> BTreeMap<NaiveDate, WeeklyComposition> {
+        let mut weekly: BTreeMap<NaiveDate, WeeklyComposition> = BTreeMap::new();
+        for battle in &self.battles {
+            let Some(played_at) = battle.played_at else {
+                continue;
+            };
+            let week = weekly.entry(week_start(played_at.date())).or_default();
+            week.battles += 1;
+            week.carriers += battle.carriers;
+            week.radars += battle.radars;
+            week.divisions += battle.divisions;
+        }
+
+        weekly
+    }
+
+    /// Share of battles with at least one carrier
+    fn carrier_rate(&self) -> f64 {
+        let with_carriers = self.battles.iter().filter(|battle| battle.carriers > 0).count();
+        with_carriers as f64 / self.battles.len().max(1) as f64
+    }
+
+    fn average(&self, value: impl Fn(&BattleComposition) -> usize) -> f64 {
+        self.battles.iter().map(value).sum::<usize>() as f64 / self.battles.len().max(1) as f64
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("Date,Game Type,Map,Tier Spread,Carriers,Radars,Divisions\n");
+        for battle in &self.battles {
+            let date = battle.played_at.map(|date| date.format("%Y-%m-%d %H:%M:%S").to_string()).unwrap_or_default();
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{},{},{}",
+                date, battle.game_type, battle.map, battle.tier_spread, battle.carriers, battle.radars, battle.divisions
+            );
+        }
+
+        csv
+    }
+}
+
+/// State of the matchmaking analysis window
+#[derive(Default)]
+pub struct MatchmakingWindow {
+    pub analysis: Option<MatchmakingAnalysis>,
+}
+
+impl ToolkitTabViewer<'_> {
+    pub fn build_matchmaking_window(&mut self, ctx: &egui::Context) {
+        let Some(window) = self.tab_state.matchmaking_window.as_ref() else {
+            return;
+        };
+
+        let mut is_open = true;
+        egui::Window::new(format!("{} Matchmaking Analysis", icons::USERS_FOUR))
+            .open(&mut is_open)
+            .default_size((650.0, 600.0))
+            .show(ctx, |ui| {
+                let Some(analysis) = window.analysis.as_ref() else {
+                    ui.label("Analyzing replays...");
+                    return;
+                };
+                if analysis.battles.is_empty() {
+                    ui.label("No replays from the current game version could be analyzed.");
+                    return;
+                }
+
+                ui.horizontal(|ui| {
+                    ui.label(format!("Based on {} battles from the current game version.", analysis.battles.len()));
+                    if ui.button(format!("{} Save CSV...", icons::FLOPPY_DISK)).clicked() {
+                        if let Some(path) = rfd::FileDialog::new().add_filter("CSV", &["csv"]).set_file_name("matchmaking.csv").save_file() {
+                            let result = std::fs::write(&path, analysis.to_csv());
+                            *self.tab_state.timed_message.write() = Some(TimedMessage::file_exported(path, result));
+                        }
+                    }
+                });
+                ui.label(format!(
+                    "Carriers in {:.0}% of battles. Per battle: {:.1} radars, {:.1} divisions.",
+                    analysis.carrier_rate() * 100.0,
+                    analysis.average(|battle| battle.radars),
+                    analysis.average(|battle| battle.divisions)
+                ));
+
+                ui.strong("Tier Spread");
+                let spreads: Vec<Bar> = analysis
+                    .tier_spreads()
+                    .into_iter()
+                    .map(|(spread, battles)| Bar::new(spread as f64, battles as f64).width(0.8))
+                    .collect();
+                Plot::new("matchmaking_tier_spread")
+                    .height(200.0)
+                    .allow_scroll(false)
+                    .include_y(0.0)
+                    .x_axis_formatter(|mark, _range| format!("±{}", mark.value))
+                    .show(ui, |plot_ui| {
+                        plot_ui.bar_chart(BarChart::new(spreads).name("Battles"));
+                    });
+
+                ui.strong("Per Battle, by Week");
+                let weekly = analysis.weekly();
+                let line = |value: fn(&WeeklyComposition) -> usize| -> Vec<[f64; 2]> {
+                    weekly
+                        .iter()
+                        .map(|(week, composition)| [date_to_x(*week) + 3.5, composition.per_battle(value(composition))])
+                        .collect()
+                };
+                Plot::new("matchmaking_weekly_composition")
+                    .legend(Legend::default())
+                    .allow_scroll(false)
+                    .include_y(0.0)
+                    .x_axis_formatter(|mark, _range| x_to_date(mark.value).map(|date| date.format("%Y-%m-%d").to_string()).unwrap_or_default())
+                    .show(ui, |plot_ui| {
+                        plot_ui.line(Line::new(line(|composition| composition.radars)).name("Radars"));
+                        plot_ui.line(Line::new(line(|composition| composition.carriers)).name("Carriers"));
+                        plot_ui.line(Line::new(line(|composition| composition.divisions)).name("Divisions"));
+                    });
+            });
+
+        if !is_open {
+            self.tab_state.matchmaking_window = None;
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use std::{collections::VecDeque, path::PathBuf};
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::{app::TabState, icons};
+
+/// Oldest notifications are dropped past this many
+const MAX_NOTIFICATIONS: usize = 100;
+
+/// Follow-up offered next to a notification
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum NotificationAction {
+    /// Shows a file the app wrote in the file explorer
+    ShowFile(PathBuf),
+    RetryDownload(String),
+    /// Opens the player's details in the player tracker
+    ViewPlayer(i64),
+}
+
+impl NotificationAction {
+    fn label(&self) -> String {
+        match self {
+            NotificationAction::ShowFile(_) => format!("{} Show File", icons::FOLDER_OPEN),
+            NotificationAction::RetryDownload(_) => format!("{} Retry", icons::ARROW_CLOCKWISE),
+            NotificationAction::ViewPlayer(_) => format!("{} View Player", icons::DETECTIVE),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Notification {
+    pub time: DateTime<Local>,
+    pub message: String,
+    pub action: Option<NotificationAction>,
+}
+
+/// Everything shown in the status bar, kept around so it can be read and acted on later
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationCenter {
+    notifications: VecDeque<Notification>,
+    unread: usize,
+    #[serde(skip)]
+    open: bool,
+}
+
+impl NotificationCenter {
+    pub fn push(&mut self, message: String, action: Option<NotificationAction>) {
+        self.notifications.push_front(Notification {
+            time: Local::now(),
+            message,
+            action,
+        });
+        self.notifications.truncate(MAX_NOTIFICATIONS);
+        self.unread = (self.unread + 1).min(self.notifications.len());
+    }
+
+    /// Status bar button which opens the notification center
+    pub fn build_button(&mut self, ui: &mut egui::Ui) {
+        let text = if self.unread > 0 {
+            format!("{} {}", icons::BELL_RINGING, self.unread)
+        } else {
+            icons::BELL.to_string()
+        };
+        if ui.button(text).on_hover_text("Notifications").clicked() {
+            self.open = !self.open;
+            self.unread = 0;
+        }
+    }
+
+    /// Returns the action the user clicked, if any
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<NotificationAction> {
+        let mut clicked_action = None;
+        let mut open = self.open;
+        egui::Window::new(format!("{} Notifications", icons::BELL))
+            .open(&mut open)
+            .default_size((450.0, 400.0))
+            .show(ctx, |ui| {
+                if self.notifications.is_empty() {
+                    ui.label("Nothing yet. Finished tasks, exports and errors show up here.");
+                    return;
+                }
+
+                if ui.button(format!("{} Clear All", icons::TRASH)).clicked() {
+                    self.notifications.clear();
+                }
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    egui::Grid::new("notifications_grid").num_columns(3).striped(true).show(ui, |ui| {
+                        for notification in &self.notifications {
+                            ui.weak(notification.time.format("%H:%M").to_string())
+                                .on_hover_text(notification.time.format("%Y-%m-%d %H:%M:%S").to_string());
+                            ui.label(notification.message.as_str());
+                            if let Some(action) = notification.action.as_ref() {
+                                if ui.button(action.label()).clicked() {
+                                    clicked_action = Some(action.clone());
+                                }
+                            } else {
+                                ui.label("");
+                            }
+                            ui.end_row();
+                        }
+                    });
+                });
+            });
+        self.open = open;
+
+        clicked_action
+    }
+}
+
+impl TabState {
+    /// Adds the status bar message to the notification center the first time it's seen
+    pub fn log_timed_message(&mut self) {
+        let mut timed_message = self.timed_message.write();
+        if let Some(timed_message) = timed_message.as_mut().filter(|timed_message| !timed_message.logged) {
+            timed_message.logged = true;
+            self.notifications.push(timed_message.message.clone(), timed_message.action.clone());
+        }
+    }
+}
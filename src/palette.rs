@@ -0,0 +1,174 @@
+use std::collections::{BTreeMap, HashMap};
+
+use egui::Color32;
+use serde::{Deserialize, Serialize};
+
+use crate::{app::ToolkitTabViewer, icons};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PalettePreset {
+    #[default]
+    Default,
+    /// Blue and orange, which stay distinct with reduced green sensitivity
+    Deuteranopia,
+    /// Blue and yellow, avoiding reds which appear dark with reduced red sensitivity
+    Protanopia,
+    Custom,
+}
+
+impl PalettePreset {
+    const ALL: [PalettePreset; 4] = [PalettePreset::Default, PalettePreset::Deuteranopia, PalettePreset::Protanopia, PalettePreset::Custom];
+
+    fn name(&self) -> &'static str {
+        match self {
+            PalettePreset::Default => "Default",
+            PalettePreset::Deuteranopia => "Deuteranopia",
+            PalettePreset::Protanopia => "Protanopia",
+            PalettePreset::Custom => "Custom",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct TeamColors {
+    pub recording_player: Color32,
+    pub ally: Color32,
+    pub enemy: Color32,
+}
+
+impl Default for TeamColors {
+    fn default() -> Self {
+        Self {
+            recording_player: Color32::GOLD,
+            ally: Color32::LIGHT_GREEN,
+            enemy: Color32::LIGHT_RED,
+        }
+    }
+}
+
+/// Colors used to tell the recording player, allies and enemies apart
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TeamPalette {
+    pub preset: PalettePreset,
+    /// Colors used with the custom preset
+    pub custom: TeamColors,
+    /// Colors for specific players, by player name, which override their team's color. Each preset has its own.
+    pub player_colors: HashMap<PalettePreset, BTreeMap<String, Color32>>,
+    /// Name typed into the settings to add a player color for
+    #[serde(skip)]
+    new_player_name: String,
+}
+
+impl TeamPalette {
+    pub fn colors(&self, is_dark_mode: bool) -> TeamColors {
+        match (self.preset, is_dark_mode) {
+            (PalettePreset::Default, true) => TeamColors::default(),
+            (PalettePreset::Default, false) => TeamColors {
+                recording_player: Color32::GOLD,
+                ally: Color32::DARK_GREEN,
+                enemy: Color32::DARK_RED,
+            },
+            (PalettePreset::Deuteranopia, true) => TeamColors {
+                recording_player: Color32::from_rgb(0xF0, 0xE4, 0x42),
+                ally: Color32::from_rgb(0x56, 0xB4, 0xE9),
+                enemy: Color32::from_rgb(0xE6, 0x9F, 0x00),
+            },
+            (PalettePreset::Deuteranopia, false) => TeamColors {
+                recording_player: Color32::from_rgb(0xCC, 0x79, 0xA7),
+                ally: Color32::from_rgb(0x00, 0x72, 0xB2),
+                enemy: Color32::from_rgb(0xD5, 0x5E, 0x00),
+            },
+            (PalettePreset::Protanopia, true) => TeamColors {
+                recording_player: Color32::WHITE,
+                ally: Color32::from_rgb(0x56, 0xB4, 0xE9),
+                enemy: Color32::from_rgb(0xF0, 0xE4, 0x42),
+            },
+            (PalettePreset::Protanopia, false) => TeamColors {
+                recording_player: Color32::BLACK,
+                ally: Color32::from_rgb(0x00, 0x72, 0xB2),
+                enemy: Color32::from_rgb(0x9E, 0x8A, 0x00),
+            },
+            (PalettePreset::Custom, _) => self.custom,
+        }
+    }
+
+    pub fn color_for_relation(&self, relation: u32, is_dark_mode: bool) -> Color32 {
+        let colors = self.colors(is_dark_mode);
+        match relation {
+            0 => colors.recording_player,
+            1 => colors.ally,
+            _ => colors.enemy,
+        }
+    }
+
+    /// The player's custom color for the current preset, if they have one
+    pub fn player_color(&self, player_name: &str) -> Option<Color32> {
+        self.player_colors.get(&self.preset).and_then(|colors| colors.get(player_name)).copied()
+    }
+
+    fn build_player_color_settings(&mut self, ui: &mut egui::Ui) {
+        let player_colors = self.player_colors.entry(self.preset).or_default();
+        let mut to_remove = None;
+        for (name, color) in player_colors.iter_mut() {
+            ui.horizontal(|ui| {
+                ui.color_edit_button_srgba(color);
+                ui.label(name.as_str());
+                if ui.small_button(icons::TRASH).on_hover_text("Remove").clicked() {
+                    to_remove = Some(name.clone());
+                }
+            });
+        }
+        if let Some(name) = to_remove {
+            player_colors.remove(&name);
+        }
+
+        ui.horizontal(|ui| {
+            let label = ui.label("Player Name");
+            ui.text_edit_singleline(&mut self.new_player_name).labelled_by(label.id);
+            let name = self.new_player_name.trim();
+            if ui.add_enabled(!name.is_empty(), egui::Button::new(format!("{} Add", icons::PLUS))).clicked() {
+                player_colors.insert(name.to_string(), Color32::LIGHT_BLUE);
+                self.new_player_name.clear();
+            }
+        });
+    }
+}
+
+impl ToolkitTabViewer<'_> {
+    pub fn build_team_color_settings(&mut self, ui: &mut egui::Ui) {
+        let palette = &mut self.tab_state.settings.team_palette;
+        ui.horizontal(|ui| {
+            ui.label("Preset");
+            egui::ComboBox::from_id_salt("team_palette_preset")
+                .selected_text(palette.preset.name())
+                .show_ui(ui, |ui| {
+                    for preset in PalettePreset::ALL {
+                        ui.selectable_value(&mut palette.preset, preset, preset.name());
+                    }
+                });
+        });
+
+        if palette.preset == PalettePreset::Custom {
+            let custom = &mut palette.custom;
+            for (name, color) in [("You", &mut custom.recording_player), ("Allies", &mut custom.ally), ("Enemies", &mut custom.enemy)] {
+                ui.horizontal(|ui| {
+                    ui.color_edit_button_srgba(color);
+                    ui.label(name);
+                });
+            }
+        } else {
+            let colors = palette.colors(ui.visuals().dark_mode);
+            ui.horizontal(|ui| {
+                ui.colored_label(colors.recording_player, "You");
+                ui.colored_label(colors.ally, "Allies");
+                ui.colored_label(colors.enemy, "Enemies");
+            });
+        }
+
+        ui.collapsing("Player Colors", |ui| {
+            ui.label("Players listed here are shown in their own color instead of their team's.");
+            palette.build_player_color_settings(ui);
+        });
+    }
+}
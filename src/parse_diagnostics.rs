@@ -0,0 +1,303 @@
+use std::{collections::BTreeMap, fmt::Write, path::Path, sync::Arc};
+
+use egui_extras::{Column, TableBuilder};
+use wows_replays::{
+    analyzer::AnalyzerMut,
+    packet2::{Packet, PacketType, Parser},
+    ReplayFile,
+};
+use wowsunpack::game_params::provider::GameMetadataProvider;
+
+use crate::{
+    app::{TimedMessage, ToolkitTabViewer},
+    icons,
+    replay_titles::replay_key,
+    task,
+};
+
+/// How many unreadable packets are kept for hex dumps
+const MAX_SAMPLES: usize = 50;
+/// Longer packets are cut off in hex dumps
+const MAX_SAMPLE_BYTES: usize = 512;
+
+/// A packet the parser couldn't make sense of
+pub struct PacketSample {
+    clock: f32,
+    packet_type: u32,
+    /// Why the packet couldn't be read
+    kind: &'static str,
+    /// The start of the packet's raw bytes, up to [MAX_SAMPLE_BYTES]
+    bytes: Vec<u8>,
+    /// Length of the whole packet
+    len: usize,
+}
+
+/// What the parser encountered in a replay, for reporting parsing gaps after a game update
+pub struct ParseDiagnostics {
+    pub file_name: String,
+    client_version: String,
+    packets: usize,
+    /// Packet counts by packet type ID
+    packet_types: BTreeMap<u32, usize>,
+    /// Call counts by entity method name
+    entity_methods: BTreeMap<String, usize>,
+    /// Packets with a type ID the parser doesn't know
+    unknown_packets: usize,
+    /// Packets with a known type ID which failed to decode
+    invalid_packets: usize,
+    samples: Vec<PacketSample>,
+    /// Error which stopped parsing partway through the replay
+    parse_error: Option<String>,
+}
+
+impl AnalyzerMut for ParseDiagnostics {
+    fn process_mut(&mut self, packet: &Packet) {
+        self.packets += 1;
+        *self.packet_types.entry(packet.packet_type).or_default() += 1;
+
+        let kind = match &packet.payload {
+            PacketType::EntityMethod(method) => {
+                *self.entity_methods.entry(method.method.to_string()).or_default() += 1;
+                return;
+            }
+            PacketType::Unknown(_) => {
+                self.unknown_packets += 1;
+                "Unknown packet type"
+            }
+            PacketType::Invalid(_) => {
+                self.invalid_packets += 1;
+                "Failed to decode"
+            }
+            _ => return,
+        };
+
+        if self.samples.len() < MAX_SAMPLES {
+            self.samples.push(PacketSample {
+                clock: packet.clock,
+                packet_type: packet.packet_type,
+                kind,
+                bytes: packet.raw[..packet.raw.len().min(MAX_SAMPLE_BYTES)].to_vec(),
+                len: packet.raw.len(),
+            });
+        }
+    }
+
+    fn finish(&mut self) {}
+}
+
+/// Offset, hex and ASCII columns, 16 bytes per line
+fn hex_dump(bytes: &[u8]) -> String {
+    let mut dump = String::new();
+    for (i, line) in bytes.chunks(16).enumerate() {
+        let _ = write!(dump, "{:08x}  ", i * 16);
+        for byte in line {
+            let _ = write!(dump, "{:02x} ", byte);
+        }
+        dump.push_str(&"   ".repeat(16 - line.len()));
+        dump.push(' ');
+        dump.extend(line.iter().map(|byte| if byte.is_ascii_graphic() || *byte == b' ' { *byte as char } else { '.' }));
+        dump.push('\n');
+    }
+
+    dump
+}
+
+impl ParseDiagnostics {
+    pub fn collect(path: &Path, metadata_provider: Arc<GameMetadataProvider>) -> Self {
+        let mut diagnostics = ParseDiagnostics {
+            file_name: replay_key(path),
+            client_version: String::new(),
+            packets: 0,
+            packet_types: BTreeMap::new(),
+            entity_methods: BTreeMap::new(),
+            unknown_packets: 0,
+            invalid_packets: 0,
+            samples: Vec::new(),
+            parse_error: None,
+        };
+
+        let replay_file = match ReplayFile::from_file(path) {
+            Ok(replay_file) => replay_file,
+            Err(e) => {
+                diagnostics.parse_error = Some(format!("The replay file couldn't be read: {:?}", e));
+                return diagnostics;
+            }
+        };
+        diagnostics.client_version = replay_file.meta.clientVersionFromExe.clone();
+
+        let mut parser = Parser::new(metadata_provider.entity_specs());
+        if let Err(e) = parser.parse_packets_mut(&replay_file.packet_data, &mut diagnostics) {
+            diagnostics.parse_error = Some(format!("{:?}", e));
+        }
+        diagnostics.finish();
+
+        diagnostics
+    }
+
+    /// Plain text report to paste into a bug report
+    pub fn to_report(&self) -> String {
+        let mut report = String::new();
+        let _ = writeln!(report, "Replay: {}", self.file_name);
+        let _ = writeln!(report, "Client version: {}", self.client_version);
+        let _ = writeln!(report, "Packets: {}", self.packets);
+        let _ = writeln!(report, "Unknown packets: {}", self.unknown_packets);
+        let _ = writeln!(report, "Packets which failed to decode: {}", self.invalid_packets);
+        if let Some(parse_error) = &self.parse_error {
+            let _ = writeln!(report, "Parsing stopped: {}", parse_error);
+        }
+
+        let _ = writeln!(report, "\nPacket types:");
+        for (packet_type, count) in &self.packet_types {
+            let _ = writeln!(report, "  0x{:02x}: {}", packet_type, count);
+        }
+        let _ = writeln!(report, "\nEntity methods:");
+        for (method, count) in &self.entity_methods {
+            let _ = writeln!(report, "  {}: {}", method, count);
+        }
+        for sample in &self.samples {
+            let _ = writeln!(
+                report,
+                "\n{} at {:.1}s, type 0x{:02x}, {} bytes:\n{}",
+                sample.kind,
+                sample.clock,
+                sample.packet_type,
+                sample.len,
+                hex_dump(&sample.bytes)
+            );
+        }
+
+        report
+    }
+}
+
+/// State of the parse diagnostics window
+pub struct ParseDiagnosticsWindow {
+    pub file_name: String,
+    pub diagnostics: Option<ParseDiagnostics>,
+}
+
+fn count_table(ui: &mut egui::Ui, id_salt: &str, rows: Vec<(String, usize)>) {
+    ui.push_id(id_salt, |ui| {
+        TableBuilder::new(ui)
+            .striped(true)
+            .max_scroll_height(200.0)
+            .column(Column::remainder())
+            .column(Column::auto())
+            .body(|body| {
+                body.rows(18.0, rows.len(), |mut row| {
+                    let (name, count) = &rows[row.index()];
+                    row.col(|ui| {
+                        ui.monospace(name.as_str());
+                    });
+                    row.col(|ui| {
+                        ui.label(count.to_string());
+                    });
+                });
+            });
+    });
+}
+
+impl ToolkitTabViewer<'_> {
+    pub fn start_parse_diagnostics(&mut self, path: &Path) {
+        let Some(metadata_provider) = self
+            .tab_state
+            .world_of_warships_data
+            .as_ref()
+            .and_then(|wows_data| wows_data.read().game_metadata.clone())
+        else {
+            return;
+        };
+
+        crate::update_background_task!(
+            self.tab_state.background_task,
+            Some(task::start_collecting_parse_diagnostics(path.to_path_buf(), metadata_provider))
+        );
+        self.tab_state.parse_diagnostics_window = Some(ParseDiagnosticsWindow {
+            file_name: replay_key(path),
+            diagnostics: None,
+        });
+    }
+
+    pub fn build_parse_diagnostics_window(&mut self, ctx: &egui::Context) {
+        let Some(window) = self.tab_state.parse_diagnostics_window.as_ref() else {
+            return;
+        };
+
+        let mut is_open = true;
+        egui::Window::new(format!("{} Parse Diagnostics", icons::BUG))
+            .open(&mut is_open)
+            .default_size((650.0, 500.0))
+            .show(ctx, |ui| {
+                ui.label(window.file_name.as_str());
+                let Some(diagnostics) = window.diagnostics.as_ref() else {
+                    ui.label("Parsing replay...");
+                    return;
+                };
+
+                ui.label(format!("Client version {}", diagnostics.client_version));
+                ui.label(format!(
+                    "{} packets, {} with an unknown type, {} failed to decode",
+                    diagnostics.packets, diagnostics.unknown_packets, diagnostics.invalid_packets
+                ));
+                if let Some(parse_error) = &diagnostics.parse_error {
+                    ui.colored_label(ui.visuals().warn_fg_color, format!("{} Parsing stopped: {}", icons::WARNING, parse_error));
+                }
+                ui.horizontal(|ui| {
+                    if ui.button(format!("{} Copy Report", icons::COPY)).clicked() {
+                        ui.output_mut(|output| output.copied_text = diagnostics.to_report());
+                    }
+                    if ui.button(format!("{} Save Report...", icons::FLOPPY_DISK)).clicked() {
+                        let file_name = format!("{} diagnostics.txt", diagnostics.file_name);
+                        if let Some(path) = rfd::FileDialog::new().add_filter("Text", &["txt"]).set_file_name(file_name).save_file() {
+                            let result = std::fs::write(&path, diagnostics.to_report());
+                            *self.tab_state.timed_message.write() = Some(TimedMessage::file_exported(path, result));
+                        }
+                    }
+                });
+                ui.separator();
+
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    egui::CollapsingHeader::new(format!("Packet Types ({})", diagnostics.packet_types.len())).show(ui, |ui| {
+                        let rows = diagnostics
+                            .packet_types
+                            .iter()
+                            .map(|(packet_type, count)| (format!("0x{:02x}", packet_type), *count))
+                            .collect();
+                        count_table(ui, "parse_diagnostics_packet_types", rows);
+                    });
+                    egui::CollapsingHeader::new(format!("Entity Methods ({})", diagnostics.entity_methods.len())).show(ui, |ui| {
+                        let rows = diagnostics.entity_methods.iter().map(|(method, count)| (method.clone(), *count)).collect();
+                        count_table(ui, "parse_diagnostics_entity_methods", rows);
+                    });
+                    egui::CollapsingHeader::new(format!("Unreadable Packets ({})", diagnostics.unknown_packets + diagnostics.invalid_packets))
+                        .default_open(!diagnostics.samples.is_empty())
+                        .show(ui, |ui| {
+                            if diagnostics.samples.is_empty() {
+                                ui.label("Every packet was read.");
+                            }
+                            for (i, sample) in diagnostics.samples.iter().enumerate() {
+                                egui::CollapsingHeader::new(format!(
+                                    "{} at {:.1}s, type 0x{:02x}, {} bytes",
+                                    sample.kind, sample.clock, sample.packet_type, sample.len
+                                ))
+                                .id_salt(("parse_diagnostics_sample", i))
+                                .show(ui, |ui| {
+                                    let dump = hex_dump(&sample.bytes);
+                                    if ui.button(format!("{} Copy", icons::COPY)).clicked() {
+                                        ui.output_mut(|output| output.copied_text = dump.clone());
+                                    }
+                                    ui.monospace(dump);
+                                });
+                            }
+                            if diagnostics.unknown_packets + diagnostics.invalid_packets > diagnostics.samples.len() {
+                                ui.weak(format!("Only the first {} are kept", MAX_SAMPLES));
+                            }
+                        });
+                });
+            });
+
+        if !is_open {
+            self.tab_state.parse_diagnostics_window = None;
+        }
+    }
+}
@@ -0,0 +1,218 @@
+use std::{collections::BTreeMap, fmt::Write, path::PathBuf};
+
+use chrono::NaiveDateTime;
+use egui_extras::{Column, TableBuilder};
+use itertools::Itertools;
+use wows_replays::ReplayFile;
+use wowsunpack::{data::ResourceLoader, game_params::provider::GameMetadataProvider};
+
+use crate::{
+    app::{TimedMessage, ToolkitTabViewer},
+    icons,
+    tournament::REPLAY_DATE_FORMAT,
+};
+
+const CSV_DATE_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Quotes a CSV field if it needs to be
+pub(crate) fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Everything known about one player across the extracted replays
+#[derive(Default)]
+pub struct ExtractedPlayer {
+    pub battles: u32,
+    /// Battles on the recording player's team
+    pub as_ally: u32,
+    pub as_enemy: u32,
+    /// Battles played in each ship
+    pub ships: BTreeMap<String, u32>,
+    pub first_seen: Option<NaiveDateTime>,
+    pub last_seen: Option<NaiveDateTime>,
+}
+
+impl ExtractedPlayer {
+    fn ships_summary(&self) -> String {
+        self.ships
+            .iter()
+            .sorted_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)))
+            .map(|(ship, battles)| format!("{} ({})", ship, battles))
+            .join(", ")
+    }
+}
+
+/// Players found in a folder of replays, deduplicated by name. Only replay metadata is read, so
+/// replays from any game version can be used.
+#[derive(Default)]
+pub struct PlayerExtraction {
+    pub folder: PathBuf,
+    pub replays: usize,
+    /// Replays which couldn't be read
+    pub skipped: usize,
+    pub players: BTreeMap<String, ExtractedPlayer>,
+}
+
+impl PlayerExtraction {
+    pub fn new(folder: PathBuf) -> Self {
+        Self { folder, ..Default::default() }
+    }
+
+    pub fn add_replay(&mut self, replay_file: &ReplayFile, metadata_provider: Option<&GameMetadataProvider>) {
+        let meta = &replay_file.meta;
+        let played_at = NaiveDateTime::parse_from_str(&meta.dateTime, REPLAY_DATE_FORMAT).ok();
+        self.replays += 1;
+
+        for vehicle in &meta.vehicles {
+            let ship = metadata_provider
+                .and_then(|metadata_provider| {
+                    let id = metadata_provider.param_localization_id(vehicle.shipId as u32)?;
+                    metadata_provider.localized_name_from_id(id)
+                })
+                .unwrap_or_else(|| vehicle.shipId.to_string());
+
+            let player = self.players.entry(vehicle.name.clone()).or_default();
+            player.battles += 1;
+            if vehicle.relation == 2 {
+                player.as_enemy += 1;
+            } else {
+                player.as_ally += 1;
+            }
+            *player.ships.entry(ship).or_default() += 1;
+            if let Some(played_at) = played_at {
+                player.first_seen = Some(player.first_seen.map_or(played_at, |first_seen| first_seen.min(played_at)));
+                player.last_seen = Some(player.last_seen.map_or(played_at, |last_seen| last_seen.max(played_at)));
+            }
+        }
+    }
+
+    pub fn to_csv(&self) -> String {
+        let format_date = |date: Option<NaiveDateTime>| date.map(|date| date.format(CSV_DATE_FORMAT).to_string()).unwrap_or_default();
+
+        let mut csv = String::from("Player,Battles,As Ally,As Enemy,First Seen,Last Seen,Ships\n");
+        for (name, player) in &self.players {
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{},{},{}",
+                csv_field(name),
+                player.battles,
+                player.as_ally,
+                player.as_enemy,
+                format_date(player.first_seen),
+                format_date(player.last_seen),
+                csv_field(&player.ships_summary())
+            );
+        }
+
+        csv
+    }
+}
+
+/// State of the player extraction window
+#[derive(Default)]
+pub struct PlayerExtractionWindow {
+    pub extraction: Option<PlayerExtraction>,
+    filter: String,
+}
+
+impl ToolkitTabViewer<'_> {
+    pub fn build_player_extraction_window(&mut self, ctx: &egui::Context) {
+        let Some(window) = self.tab_state.player_extraction_window.as_mut() else {
+            return;
+        };
+
+        let mut is_open = true;
+        egui::Window::new(format!("{} Extracted Players", icons::USERS_THREE))
+            .open(&mut is_open)
+            .default_size((700.0, 500.0))
+            .show(ctx, |ui| {
+                let Some(extraction) = window.extraction.as_ref() else {
+                    ui.label("Reading replays...");
+                    return;
+                };
+
+                ui.label(format!(
+                    "{} players across {} replays in {}",
+                    extraction.players.len(),
+                    extraction.replays,
+                    extraction.folder.display()
+                ));
+                if extraction.skipped > 0 {
+                    ui.label(format!("{} replays couldn't be read and were skipped", extraction.skipped));
+                }
+
+                ui.horizontal(|ui| {
+                    let filter_label = ui.label("Player Filter");
+                    ui.text_edit_singleline(&mut window.filter).labelled_by(filter_label.id);
+                    if ui.button(format!("{} Copy CSV", icons::COPY)).clicked() {
+                        ui.output_mut(|output| output.copied_text = extraction.to_csv());
+                        *self.tab_state.timed_message.write() = Some(TimedMessage::new(format!("{} Copied player list", icons::CHECK_CIRCLE)));
+                    }
+                    if ui.button(format!("{} Save CSV...", icons::FLOPPY_DISK)).clicked() {
+                        if let Some(path) = rfd::FileDialog::new().add_filter("CSV", &["csv"]).set_file_name("players.csv").save_file() {
+                            let result = std::fs::write(&path, extraction.to_csv());
+                            *self.tab_state.timed_message.write() = Some(TimedMessage::file_exported(path, result));
+                        }
+                    }
+                });
+
+                let filter_lower = window.filter.to_lowercase();
+                let players: Vec<_> = extraction
+                    .players
+                    .iter()
+                    .filter(|(name, _)| name.to_lowercase().contains(&filter_lower))
+                    .sorted_by(|a, b| b.1.battles.cmp(&a.1.battles).then(a.0.cmp(b.0)))
+                    .collect();
+
+                TableBuilder::new(ui)
+                    .striped(true)
+                    .resizable(true)
+                    .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+                    .column(Column::initial(150.0).clip(true))
+                    .column(Column::initial(60.0))
+                    .column(Column::initial(60.0))
+                    .column(Column::initial(60.0))
+                    .column(Column::initial(130.0))
+                    .column(Column::remainder().clip(true))
+                    .header(20.0, |mut header| {
+                        for title in ["Player", "Battles", "As Ally", "As Enemy", "Last Seen", "Ships"] {
+                            header.col(|ui| {
+                                ui.strong(title);
+                            });
+                        }
+                    })
+                    .body(|body| {
+                        body.rows(20.0, players.len(), |mut row| {
+                            let (name, player) = players[row.index()];
+                            row.col(|ui| {
+                                ui.label(name.as_str());
+                            });
+                            row.col(|ui| {
+                                ui.label(player.battles.to_string());
+                            });
+                            row.col(|ui| {
+                                ui.label(player.as_ally.to_string());
+                            });
+                            row.col(|ui| {
+                                ui.label(player.as_enemy.to_string());
+                            });
+                            row.col(|ui| {
+                                ui.label(player.last_seen.map(|date| date.format(CSV_DATE_FORMAT).to_string()).unwrap_or_default());
+                            });
+                            row.col(|ui| {
+                                let ships = player.ships_summary();
+                                ui.label(ships.as_str()).on_hover_text(ships.as_str());
+                            });
+                        });
+                    });
+            });
+
+        if !is_open {
+            self.tab_state.player_extraction_window = None;
+        }
+    }
+}
@@ -3,8 +3,8 @@ use std::{
     sync::Arc,
 };
 
-use crate::{icons, task};
-use chrono::{DateTime, Duration, Local, NaiveDateTime, TimeZone, Utc};
+use crate::{icons, task, undo::Undo};
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
 use egui::{Color32, RichText};
 use egui_extras::{Column, TableBuilder};
 use itertools::Itertools;
@@ -12,7 +12,13 @@ use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
 use wows_replays::ReplayFile;
 
-use crate::{app::ToolkitTabViewer, replay_parser::Replay};
+use crate::{
+    app::{TimedMessage, ToolkitTabViewer},
+    legacy_import::{self, ImportedPlayer},
+    replay_parser::Replay,
+    tracker_charts::{week_start, TrackerCharts, WeeklyActivity},
+    usage_stats, util,
+};
 
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct PlayerTracker {
@@ -21,6 +27,13 @@ pub struct PlayerTracker {
     filter_time_period: TimePeriod,
     sort_order: SortedBy,
     player_filter: String,
+    #[serde(default)]
+    only_clanmates: bool,
+    /// Player whose details window is open
+    #[serde(skip)]
+    selected_player: Option<i64>,
+    #[serde(skip)]
+    charts: TrackerCharts,
 }
 
 impl PlayerTracker {
@@ -49,9 +62,16 @@ impl PlayerTracker {
                     continue;
                 }
 
+                let seen_as = tracked_player.name_history.entry(player.name().to_string()).or_insert(NameSeen {
+                    first_seen: timestamp,
+                    last_seen: timestamp,
+                });
+                seen_as.first_seen = seen_as.first_seen.min(timestamp);
+                seen_as.last_seen = seen_as.last_seen.max(timestamp);
+
                 let mut update_metadata = false;
 
-                if let Some(last_seen) = tracked_player.timestamps.first() {
+                if let Some(last_seen) = tracked_player.timestamps.last() {
                     if *last_seen < timestamp {
                         update_metadata = true;
                     }
@@ -81,6 +101,217 @@ impl PlayerTracker {
             }
         }
     }
+
+    /// Merges players exported by another tool into the tracker. Returns how many players were added and how
+    /// many existing players were updated.
+    pub fn import_players(&mut self, players: Vec<ImportedPlayer>) -> (usize, usize) {
+        let now = Local::now();
+        let mut added = HashSet::new();
+        let mut updated = HashSet::new();
+        for imported in players {
+            let is_new = !self.tracked_players.contains_key(&imported.db_id);
+            let tracked_player = self.tracked_players.entry(imported.db_id).or_default();
+            if let Some(arena_id) = imported.arena_id {
+                if !tracked_player.arena_ids.insert(arena_id) {
+                    continue;
+                }
+            }
+            if is_new {
+                added.insert(imported.db_id);
+            } else if !added.contains(&imported.db_id) {
+                updated.insert(imported.db_id);
+            }
+
+            // Players only show up in the tracker once they've been seen at some point
+            let timestamp = match imported.seen {
+                Some(seen) => seen,
+                None if tracked_player.timestamps.is_empty() => now,
+                None => *tracked_player.timestamps.last().expect("timestamps is not empty"),
+            };
+            let is_latest = tracked_player.timestamps.last().map(|last_seen| *last_seen <= timestamp).unwrap_or(true);
+            if tracked_player.timestamps.insert(timestamp) {
+                self.tracked_players_by_time.entry(timestamp).or_default().push(imported.db_id);
+            }
+
+            tracked_player.db_id = imported.db_id;
+            if !imported.name.is_empty() {
+                let seen_as = tracked_player.name_history.entry(imported.name.clone()).or_insert(NameSeen {
+                    first_seen: timestamp,
+                    last_seen: timestamp,
+                });
+                seen_as.first_seen = seen_as.first_seen.min(timestamp);
+                seen_as.last_seen = seen_as.last_seen.max(timestamp);
+
+                if is_latest || tracked_player.last_name.is_empty() {
+                    if !tracked_player.last_name.is_empty() && tracked_player.last_name != imported.name {
+                        tracked_player.names.insert(tracked_player.last_name.clone());
+                    }
+                    tracked_player.last_name = imported.name;
+                } else if tracked_player.last_name != imported.name {
+                    tracked_player.names.insert(imported.name);
+                }
+            }
+            if is_latest && !imported.clan.is_empty() {
+                tracked_player.clan = imported.clan;
+            }
+            if imported.clan_id != 0 && (is_latest || tracked_player.clan_id == 0) {
+                tracked_player.clan_id = imported.clan_id;
+            }
+            let notes = imported.notes.trim();
+            if !notes.is_empty() && !tracked_player.notes.contains(notes) {
+                if !tracked_player.notes.is_empty() {
+                    tracked_player.notes.push_str("; ");
+                }
+                tracked_player.notes.push_str(notes);
+            }
+        }
+
+        (added.len(), updated.len())
+    }
+
+    /// Returns tracked players in this replay who the user has left notes on
+    pub fn flagged_players_in_replay(&self, replay: &Replay) -> Vec<&TrackedPlayer> {
+        let Some(report) = replay.battle_report.as_ref() else {
+            return Vec::new();
+        };
+
+        report
+            .players()
+            .iter()
+            .filter_map(|player| self.tracked_players.get(&player.db_id()))
+            .filter(|tracked_player| !tracked_player.notes.trim().is_empty())
+            .collect()
+    }
+
+    /// Removes every tracked player, returning them so they can be restored
+    pub fn take_tracked_players(&mut self) -> TrackerSnapshot {
+        TrackerSnapshot {
+            tracked_players_by_time: std::mem::take(&mut self.tracked_players_by_time),
+            tracked_players: std::mem::take(&mut self.tracked_players),
+        }
+    }
+
+    pub fn restore_tracked_players(&mut self, snapshot: TrackerSnapshot) {
+        self.tracked_players_by_time = snapshot.tracked_players_by_time;
+        self.tracked_players = snapshot.tracked_players;
+    }
+
+    /// Battles and tracked player encounters, grouped by the week they happened in
+    pub fn weekly_activity(&self) -> BTreeMap<NaiveDate, WeeklyActivity> {
+        let mut activity: BTreeMap<NaiveDate, WeeklyActivity> = BTreeMap::new();
+        for (timestamp, players) in &self.tracked_players_by_time {
+            let week = activity.entry(week_start(timestamp.date_naive())).or_default();
+            week.battles += 1;
+            week.encounters += players.len();
+        }
+
+        activity
+    }
+
+    pub fn tracked_player(&self, db_id: i64) -> Option<&TrackedPlayer> {
+        self.tracked_players.get(&db_id)
+    }
+
+    pub fn tracked_players(&self) -> impl Iterator<Item = &TrackedPlayer> {
+        self.tracked_players.values()
+    }
+
+    /// Opens the details window for the given player
+    pub fn select_player(&mut self, db_id: i64) {
+        self.selected_player = Some(db_id);
+    }
+
+    /// Menu for rating a player the user played with in the battle `arena_id`. Each battle
+    /// holds at most one rating per player.
+    pub fn build_rating_menu(&mut self, ui: &mut egui::Ui, db_id: i64, arena_id: i64) {
+        let Some(player) = self.tracked_players.get_mut(&db_id) else {
+            ui.label("Player isn't in the player tracker");
+            return;
+        };
+
+        let existing = player.ratings.iter().position(|rating| rating.arena_id == arena_id);
+        for category in RatingCategory::ALL {
+            ui.horizontal(|ui| {
+                for positive in [true, false] {
+                    let icon = if positive { icons::THUMBS_UP } else { icons::THUMBS_DOWN };
+                    let selected = existing
+                        .map(|idx| player.ratings[idx].category == category && player.ratings[idx].positive == positive)
+                        .unwrap_or(false);
+                    if ui.selectable_label(selected, icon).clicked() {
+                        let rating = PlayerRating {
+                            arena_id,
+                            rated_at: Local::now(),
+                            positive,
+                            category,
+                        };
+                        if let Some(idx) = existing {
+                            player.ratings[idx] = rating;
+                        } else {
+                            player.ratings.push(rating);
+                        }
+                        ui.close_menu();
+                    }
+                }
+                ui.label(category.name());
+            });
+        }
+
+        if let Some(idx) = existing {
+            ui.separator();
+            if ui.button(format!("{} Remove Rating", icons::TRASH)).clicked() {
+                player.ratings.remove(idx);
+                ui.close_menu();
+            }
+        }
+    }
+}
+
+/// Tracked players removed from the tracker
+pub struct TrackerSnapshot {
+    tracked_players_by_time: BTreeMap<chrono::DateTime<Local>, Vec<i64>>,
+    tracked_players: HashMap<i64, TrackedPlayer>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RatingCategory {
+    Teamwork,
+    Skill,
+    Communication,
+    Sportsmanship,
+}
+
+impl RatingCategory {
+    const ALL: [RatingCategory; 4] = [
+        RatingCategory::Teamwork,
+        RatingCategory::Skill,
+        RatingCategory::Communication,
+        RatingCategory::Sportsmanship,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            RatingCategory::Teamwork => "Teamwork",
+            RatingCategory::Skill => "Skill",
+            RatingCategory::Communication => "Communication",
+            RatingCategory::Sportsmanship => "Sportsmanship",
+        }
+    }
+}
+
+/// The user's +1/-1 for a player after a battle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlayerRating {
+    arena_id: i64,
+    rated_at: DateTime<Local>,
+    positive: bool,
+    category: RatingCategory,
+}
+
+/// When a player was seen using a particular name
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct NameSeen {
+    first_seen: DateTime<Local>,
+    last_seen: DateTime<Local>,
 }
 
 #[derive(Debug, Default, Serialize, Deserialize)]
@@ -94,6 +325,68 @@ pub struct TrackedPlayer {
     arena_ids: BTreeSet<i64>,
     #[serde(default)]
     notes: String,
+    /// Every name this account has been seen with. Players tracked before this was
+    /// recorded only have their old names in `names`.
+    #[serde(default)]
+    name_history: HashMap<String, NameSeen>,
+    #[serde(default)]
+    ratings: Vec<PlayerRating>,
+}
+
+impl TrackedPlayer {
+    pub fn last_name(&self) -> &str {
+        &self.last_name
+    }
+
+    pub fn db_id(&self) -> i64 {
+        self.db_id
+    }
+
+    pub fn clan(&self) -> &str {
+        &self.clan
+    }
+
+    pub fn notes(&self) -> &str {
+        &self.notes
+    }
+
+    /// Number of battles this player has been seen in
+    pub fn encounters(&self) -> usize {
+        self.arena_ids.len()
+    }
+
+    /// Sum of the user's ratings for this player
+    pub fn karma(&self) -> i64 {
+        self.ratings.iter().map(|rating| if rating.positive { 1 } else { -1 }).sum()
+    }
+
+    /// Shows the player's karma, with the individual ratings on hover. Shows nothing if the player hasn't been rated.
+    pub fn build_karma_badge(&self, ui: &mut egui::Ui) {
+        if self.ratings.is_empty() {
+            return;
+        }
+
+        let karma = self.karma();
+        let color = match karma {
+            karma if karma > 0 => Color32::LIGHT_GREEN,
+            karma if karma < 0 => Color32::LIGHT_RED,
+            _ => ui.visuals().text_color(),
+        };
+        let hover_text = self
+            .ratings
+            .iter()
+            .rev()
+            .map(|rating| {
+                format!(
+                    "{} {} {}",
+                    rating.rated_at.format("%Y-%m-%d"),
+                    if rating.positive { icons::THUMBS_UP } else { icons::THUMBS_DOWN },
+                    rating.category.name()
+                )
+            })
+            .join("\n");
+        ui.label(RichText::new(format!("{:+}", karma)).color(color)).on_hover_text(hover_text);
+    }
 }
 
 #[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
@@ -212,14 +505,23 @@ impl Default for TimePeriod {
 
 impl ToolkitTabViewer<'_> {
     pub fn build_player_tracker_tab(&mut self, ui: &mut egui::Ui) {
+        self.build_scouting_window(ui.ctx());
+        self.build_player_extraction_window(ui.ctx());
+        self.build_matchmaking_window(ui.ctx());
+        if self.tab_state.is_locked() {
+            self.build_unlock_prompt(ui);
+            ui.disable();
+        }
+
+        let region = self.tab_state.settings.region;
+        let clan = &self.tab_state.settings.clan;
         let mut player_tracker_settings = self.tab_state.settings.player_tracker.write();
         let player_tracker_settings = &mut *player_tracker_settings;
         let filter_lower = player_tracker_settings.player_filter.to_ascii_lowercase();
         ui.vertical(|ui| {
             ui.horizontal(|ui| {
-                if ui.button("Clear Stats").clicked() {
-                    player_tracker_settings.tracked_players.clear();
-                    player_tracker_settings.tracked_players_by_time.clear();
+                if ui.button("Clear Stats").on_hover_text("Can be undone from the Edit menu").clicked() {
+                    self.tab_state.undo_stack.push(Undo::ClearTracker(player_tracker_settings.take_tracked_players()));
                 }
 
                 let selected = &mut player_tracker_settings.filter_time_period;
@@ -231,8 +533,9 @@ impl ToolkitTabViewer<'_> {
                         ui.selectable_value(selected, TimePeriod::LastMonth, "Past Month");
                         ui.selectable_value(selected, TimePeriod::AllTime, "All Time");
                     });
-                ui.label("Player Filter");
-                ui.text_edit_singleline(&mut player_tracker_settings.player_filter);
+                let filter_label = ui.label("Player Filter");
+                ui.text_edit_singleline(&mut player_tracker_settings.player_filter).labelled_by(filter_label.id);
+                ui.checkbox(&mut player_tracker_settings.only_clanmates, "Only Clanmates");
                 if let Some(replay_files) = self.tab_state.replay_files.as_ref() {
                     if let Some(wows_data) = self.tab_state.world_of_warships_data.as_ref() {
                         if ui.button("Populate Data From Replays").clicked() {
@@ -245,8 +548,48 @@ impl ToolkitTabViewer<'_> {
                                 ))
                             );
                         }
+                        if ui.button(format!("{} Scouting Report...", icons::BINOCULARS)).clicked() {
+                            self.tab_state.scouting_window.get_or_insert_with(Default::default);
+                        }
+                        if ui
+                            .button(format!("{} Matchmaking...", icons::USERS_FOUR))
+                            .on_hover_text("Tier spread, carrier, radar and division frequency across the listed replays")
+                            .clicked()
+                        {
+                            crate::update_background_task!(
+                                self.tab_state.background_task,
+                                Some(task::start_analyzing_matchmaking(replay_files.keys().cloned().collect(), Arc::clone(wows_data)))
+                            );
+                            self.tab_state.matchmaking_window = Some(Default::default());
+                        }
+                    }
+                }
+                if ui
+                    .button(format!("{} Extract Players...", icons::USERS_THREE))
+                    .on_hover_text("List every player found in a folder of replays, with counts, ships and dates")
+                    .clicked()
+                {
+                    if let Some(folder) = rfd::FileDialog::new().pick_folder() {
+                        crate::update_background_task!(
+                            self.tab_state.background_task,
+                            Some(task::start_extracting_players(folder, self.tab_state.world_of_warships_data.clone()))
+                        );
+                        self.tab_state.player_extraction_window = Some(Default::default());
+                    }
+                }
+                if ui
+                    .button(format!("{} Import...", icons::FILE_ARROW_DOWN))
+                    .on_hover_text("Add players from a CSV or JSON export made by another tool, like MatchMaking Monitor or WoWS Stats & Numbers")
+                    .clicked()
+                {
+                    if let Some(message) = legacy_import::import_players(player_tracker_settings) {
+                        *self.tab_state.timed_message.write() = Some(TimedMessage::new(message));
                     }
                 }
+                if ui.button(format!("{} Charts...", icons::CHART_BAR)).clicked() {
+                    usage_stats::record_feature("Player tracker charts");
+                    player_tracker_settings.charts.open = true;
+                }
             });
 
             ui.add_space(10.0);
@@ -262,6 +605,7 @@ impl ToolkitTabViewer<'_> {
                 .column(Column::initial(90.0).clip(true))
                 .column(Column::initial(130.0).clip(true))
                 .column(Column::initial(200.0).clip(true))
+                .column(Column::initial(50.0).clip(true))
                 .column(Column::remainder())
                 .min_scrolled_height(0.0);
 
@@ -336,6 +680,9 @@ impl ToolkitTabViewer<'_> {
                     header.col(|ui| {
                         ui.strong("Aliases");
                     });
+                    header.col(|ui| {
+                        ui.strong("Karma");
+                    });
                     header.col(|ui| {
                         ui.strong("Notes");
                     });
@@ -355,13 +702,16 @@ impl ToolkitTabViewer<'_> {
                     };
 
                     let tracked_players = &mut player_tracker_settings.tracked_players;
+                    let selected_player = &mut player_tracker_settings.selected_player;
+                    let only_clanmates = player_tracker_settings.only_clanmates;
                     let players = tracked_players
                         .iter_mut()
+                        .filter(|(id, player)| !only_clanmates || clan.is_clanmate(**id, &player.clan))
                         .filter(|(id, player)| {
                             if !player_tracker_settings.player_filter.is_empty() {
                                 player_range.contains(id)
                                     && (player.clan.to_ascii_lowercase().contains(&filter_lower)
-                                        || player.last_name.contains(&filter_lower)
+                                        || player.last_name.to_ascii_lowercase().contains(&filter_lower)
                                         || player.names.iter().position(|name| name.to_ascii_lowercase().contains(&filter_lower)).is_some())
                             } else {
                                 player_range.contains(id)
@@ -447,12 +797,44 @@ impl ToolkitTabViewer<'_> {
 
                             row.col(|ui| {
                                 ui.label(&player.clan);
+                                if clan.is_clanmate(*player_id, &player.clan) {
+                                    ui.label(icons::USERS_THREE).on_hover_text("Clanmate");
+                                }
                             });
                             row.col(|ui| {
                                 let text = RichText::new(&player.last_name);
                                 let text = if let Some(color) = encounters_color { text.color(color) } else { text };
 
-                                ui.label(text);
+                                let response = ui.add(egui::Label::new(text).sense(egui::Sense::click())).on_hover_text("Click to show name history");
+                                if response.clicked() {
+                                    *selected_player = Some(*player_id);
+                                }
+                                response.context_menu(|ui| {
+                                    if ui.button("Show Details").clicked() {
+                                        *selected_player = Some(*player_id);
+                                        ui.close_menu();
+                                    }
+
+                                    let Some(region) = region else {
+                                        ui.label("Choose your region in Settings to open player links");
+                                        return;
+                                    };
+
+                                    if ui.button("Open WoWs Numbers Page").clicked() {
+                                        ui.ctx()
+                                            .open_url(egui::OpenUrl::new_tab(util::build_wows_numbers_url(region, *player_id, &player.last_name)));
+                                        ui.close_menu();
+                                    }
+                                    if ui.button("Open Tomato.gg Page").clicked() {
+                                        ui.ctx()
+                                            .open_url(egui::OpenUrl::new_tab(util::build_tomato_gg_url(region, *player_id, &player.last_name)));
+                                        ui.close_menu();
+                                    }
+                                    if player.clan_id != 0 && ui.button("Open Clan Page").clicked() {
+                                        ui.ctx().open_url(egui::OpenUrl::new_tab(region.clan_url(player.clan_id)));
+                                        ui.close_menu();
+                                    }
+                                });
                             });
                             row.col(|ui| {
                                 ui.label(player_id.to_string());
@@ -468,11 +850,14 @@ impl ToolkitTabViewer<'_> {
                                 ui.label(text);
                             });
                             row.col(|ui| {
-                                ui.label(format!("{}", player.timestamps.first().unwrap().format("%Y-%m-%d %H:%M:%S")));
+                                ui.label(format!("{}", player.timestamps.last().unwrap().format("%Y-%m-%d %H:%M:%S")));
                             });
                             row.col(|ui| {
                                 ui.label(player.names.iter().join(", "));
                             });
+                            row.col(|ui| {
+                                player.build_karma_badge(ui);
+                            });
                             row.col(|ui| {
                                 ui.text_edit_singleline(&mut player.notes);
                             });
@@ -480,5 +865,84 @@ impl ToolkitTabViewer<'_> {
                     }
                 });
         });
+
+        build_player_details_window(ui.ctx(), player_tracker_settings);
+        if player_tracker_settings.charts.open {
+            let activity = player_tracker_settings.weekly_activity();
+            player_tracker_settings.charts.show(ui.ctx(), &activity);
+        }
+    }
+}
+
+fn build_player_details_window(ctx: &egui::Context, player_tracker: &mut PlayerTracker) {
+    let Some(player) = player_tracker.selected_player.and_then(|db_id| player_tracker.tracked_players.get(&db_id)) else {
+        return;
+    };
+
+    let mut is_open = true;
+    egui::Window::new(format!("{} {}", icons::DETECTIVE, player.last_name))
+        .open(&mut is_open)
+        .show(ctx, |ui| {
+            ui.label(format!("WG ID: {}", player.db_id));
+            if !player.clan.is_empty() {
+                ui.label(format!("Clan: [{}]", player.clan));
+            }
+            if let (Some(first), Some(last)) = (player.timestamps.first(), player.timestamps.last()) {
+                ui.label(format!(
+                    "Seen in {} battles between {} and {}",
+                    player.arena_ids.len(),
+                    first.format("%Y-%m-%d"),
+                    last.format("%Y-%m-%d")
+                ));
+            }
+
+            ui.add_space(5.0);
+            ui.strong("Name History");
+            TableBuilder::new(ui)
+                .striped(true)
+                .vscroll(false)
+                .column(Column::initial(150.0))
+                .column(Column::auto())
+                .column(Column::auto())
+                .header(20.0, |mut header| {
+                    header.col(|ui| {
+                        ui.strong("Name");
+                    });
+                    header.col(|ui| {
+                        ui.strong("First Seen");
+                    });
+                    header.col(|ui| {
+                        ui.strong("Last Seen");
+                    });
+                })
+                .body(|mut body| {
+                    let history = player.name_history.iter().sorted_by_key(|(_name, seen)| std::cmp::Reverse(seen.last_seen));
+                    // Aliases recorded before name history was tracked have no timestamps
+                    let untimed = player.names.iter().filter(|name| !player.name_history.contains_key(*name));
+
+                    for (name, seen) in history.map(|(name, seen)| (name, Some(seen))).chain(untimed.map(|name| (name, None))) {
+                        body.row(18.0, |mut row| {
+                            row.col(|ui| {
+                                ui.label(name);
+                            });
+                            row.col(|ui| {
+                                ui.label(
+                                    seen.map(|seen| seen.first_seen.format("%Y-%m-%d %H:%M").to_string())
+                                        .unwrap_or_else(|| "Unknown".to_string()),
+                                );
+                            });
+                            row.col(|ui| {
+                                ui.label(
+                                    seen.map(|seen| seen.last_seen.format("%Y-%m-%d %H:%M").to_string())
+                                        .unwrap_or_else(|| "Unknown".to_string()),
+                                );
+                            });
+                        });
+                    }
+                });
+        });
+
+    if !is_open {
+        player_tracker.selected_player = None;
     }
 }
@@ -0,0 +1,141 @@
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream},
+    sync::mpsc,
+    thread,
+    time::Duration,
+};
+
+use reqwest::Url;
+use tracing::{debug, error};
+
+pub const SCHEME: &str = "wows-toolkit";
+
+/// Port the first running instance listens on so that later launches can hand it their link
+const INSTANCE_PORT: u16 = 47862;
+
+/// A parsed `wows-toolkit://open?replay=...&tab=...` link
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeepLink {
+    /// A path or URL pointing to a replay which should be opened
+    pub replay: Option<String>,
+    /// Name of the tab which should be focused
+    pub tab: Option<String>,
+}
+
+impl DeepLink {
+    pub fn parse(link: &str) -> Option<Self> {
+        let url = Url::parse(link.trim()).ok()?;
+        if url.scheme() != SCHEME || url.host_str() != Some("open") {
+            return None;
+        }
+
+        let mut deep_link = DeepLink { replay: None, tab: None };
+        for (key, value) in url.query_pairs() {
+            match key.as_ref() {
+                "replay" => deep_link.replay = Some(value.into_owned()),
+                "tab" => deep_link.tab = Some(value.into_owned()),
+                _ => {}
+            }
+        }
+
+        Some(deep_link)
+    }
+
+    /// Finds a deep link in the process arguments, which is where the OS places it when launching us
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Option<Self> {
+        args.into_iter().skip(1).find_map(|arg| DeepLink::parse(&arg))
+    }
+
+    fn to_link(&self) -> String {
+        let mut url = Url::parse(&format!("{}://open", SCHEME)).expect("deep link base URL is valid");
+        {
+            let mut query = url.query_pairs_mut();
+            if let Some(replay) = &self.replay {
+                query.append_pair("replay", replay);
+            }
+            if let Some(tab) = &self.tab {
+                query.append_pair("tab", tab);
+            }
+        }
+
+        url.to_string()
+    }
+}
+
+/// Hands `link` to an already-running instance. Returns `true` if another instance accepted it.
+pub fn forward_to_running_instance(link: &DeepLink) -> bool {
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, INSTANCE_PORT));
+    let Ok(mut stream) = TcpStream::connect_timeout(&addr, Duration::from_millis(500)) else {
+        return false;
+    };
+
+    writeln!(stream, "{}", link.to_link()).is_ok()
+}
+
+/// Listens for links forwarded by other instances. The link this process was launched with (if any)
+/// is delivered first.
+pub fn listen(ctx: egui::Context) -> mpsc::Receiver<DeepLink> {
+    let (tx, rx) = mpsc::channel();
+
+    if let Some(link) = DeepLink::from_args(std::env::args()) {
+        let _ = tx.send(link);
+    }
+
+    match TcpListener::bind((Ipv4Addr::LOCALHOST, INSTANCE_PORT)) {
+        Ok(listener) => {
+            let _join_handle = thread::spawn(move || {
+                for stream in listener.incoming().flatten() {
+                    let mut line = String::new();
+                    if BufReader::new(stream).read_line(&mut line).is_err() {
+                        continue;
+                    }
+
+                    debug!("received deep link from another instance: {}", line.trim());
+                    if let Some(link) = DeepLink::parse(&line) {
+                        if tx.send(link).is_err() {
+                            break;
+                        }
+                        ctx.request_repaint();
+                    }
+                }
+            });
+        }
+        Err(e) => {
+            error!("failed to listen for deep links: {:?}", e);
+        }
+    }
+
+    rx
+}
+
+/// Registers this executable as the handler for `wows-toolkit://` links for the current user
+#[cfg(target_os = "windows")]
+pub fn register() -> std::io::Result<()> {
+    use std::process::Command;
+
+    let exe = std::env::current_exe()?;
+    let key = format!(r"HKCU\Software\Classes\{}", SCHEME);
+    let command = format!("\"{}\" \"%1\"", exe.display());
+
+    let entries: [(String, Option<&str>, &str); 3] = [
+        (key.clone(), None, "URL:WoWs Toolkit"),
+        (key.clone(), Some("URL Protocol"), ""),
+        (format!(r"{}\shell\open\command", key), None, command.as_str()),
+    ];
+
+    for (key, value_name, data) in entries {
+        let mut reg = Command::new("reg");
+        reg.args(["add", key.as_str()]);
+        match value_name {
+            Some(name) => reg.args(["/v", name]),
+            None => reg.arg("/ve"),
+        };
+        let status = reg.args(["/d", data, "/f"]).status()?;
+        if !status.success() {
+            return Err(std::io::Error::new(std::io::ErrorKind::Other, format!("reg add {} failed with {}", key, status)));
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+
+/// Game server region, used for links and API calls when a replay doesn't tell us the realm
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Region {
+    Eu,
+    Na,
+    Asia,
+    /// Lesta's Russian servers (Mir Korabley)
+    Lesta,
+}
+
+impl Region {
+    pub const ALL: [Region; 4] = [Region::Eu, Region::Na, Region::Asia, Region::Lesta];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Region::Eu => "Europe",
+            Region::Na => "North America",
+            Region::Asia => "Asia",
+            Region::Lesta => "Russia (Lesta)",
+        }
+    }
+
+    /// Realm as reported by replays
+    pub fn realm(&self) -> &'static str {
+        match self {
+            Region::Eu => "eu",
+            Region::Na => "na",
+            Region::Asia => "asia",
+            Region::Lesta => "ru",
+        }
+    }
+
+    pub fn from_realm(realm: &str) -> Option<Region> {
+        Region::ALL.into_iter().find(|region| region.realm().eq_ignore_ascii_case(realm))
+    }
+
+    /// Host for the Wargaming (or Lesta) public API
+    pub fn api_host(&self) -> &'static str {
+        match self {
+            Region::Eu => "api.worldofwarships.eu",
+            Region::Na => "api.worldofwarships.com",
+            Region::Asia => "api.worldofwarships.asia",
+            Region::Lesta => "api.korabli.su",
+        }
+    }
+
+    fn clans_host(&self) -> &'static str {
+        match self {
+            Region::Eu => "clans.worldofwarships.eu",
+            Region::Na => "clans.worldofwarships.com",
+            Region::Asia => "clans.worldofwarships.asia",
+            Region::Lesta => "clans.korabli.su",
+        }
+    }
+
+    /// Language game text should default to when none has been chosen
+    pub fn default_locale(&self) -> &'static str {
+        match self {
+            Region::Eu | Region::Na | Region::Asia => "en",
+            Region::Lesta => "ru",
+        }
+    }
+
+    pub fn clan_url(&self, clan_id: i64) -> String {
+        format!("https://{}/clan-profile/{}", self.clans_host(), clan_id)
+    }
+}
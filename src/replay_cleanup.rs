@@ -0,0 +1,231 @@
+use std::path::PathBuf;
+
+use chrono::{Duration, Local, NaiveDateTime};
+use egui_extras::{Column, TableBuilder};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app::{TimedMessage, ToolkitTabViewer},
+    collections::{ReplayFilter, ReplaySummary},
+    icons, storage,
+    tournament::REPLAY_DATE_FORMAT,
+    undo::Operation,
+};
+
+/// Which replays a cleanup deletes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CleanupRules {
+    pub older_than_days: u32,
+    /// Matched against the game type and scenario. Empty matches every replay.
+    pub game_mode: String,
+    pub keep_in_collections: bool,
+    /// Keeps replays which were given a title or have a linked video
+    pub keep_annotated: bool,
+}
+
+impl Default for CleanupRules {
+    fn default() -> Self {
+        Self {
+            older_than_days: 90,
+            game_mode: String::new(),
+            keep_in_collections: true,
+            keep_annotated: true,
+        }
+    }
+}
+
+/// A replay the rules would delete
+pub struct CleanupCandidate {
+    path: PathBuf,
+    label: String,
+    played_at: NaiveDateTime,
+    size: u64,
+}
+
+/// State of the replay cleanup window
+#[derive(Default)]
+pub struct ReplayCleanupWindow {
+    /// Size of the replays folder and how many replays are listed, measured when the window opened
+    folder_usage: Option<(u64, usize)>,
+    /// Replays matching the rules as of the last preview
+    preview: Option<Vec<CleanupCandidate>>,
+}
+
+impl ToolkitTabViewer<'_> {
+    /// Replays in the listing which match the cleanup rules, oldest first
+    fn cleanup_candidates(&self) -> Vec<CleanupCandidate> {
+        let settings = &self.tab_state.settings;
+        let rules = &settings.replay_cleanup;
+        let (Some(replay_files), Some(metadata_provider)) = (
+            self.tab_state.replay_files.as_ref(),
+            self.tab_state
+                .world_of_warships_data
+                .as_ref()
+                .and_then(|wows_data| wows_data.read().game_metadata.clone()),
+        ) else {
+            return Vec::new();
+        };
+
+        let cutoff = Local::now().naive_local() - Duration::days(rules.older_than_days as i64);
+        let mode_filter = ReplayFilter {
+            game_mode: rules.game_mode.clone(),
+            ..Default::default()
+        };
+        let mut candidates: Vec<CleanupCandidate> = replay_files
+            .iter()
+            .filter_map(|(path, replay)| {
+                let replay = replay.read();
+                let played_at = NaiveDateTime::parse_from_str(&replay.replay_file.meta.dateTime, REPLAY_DATE_FORMAT).ok()?;
+                if played_at >= cutoff {
+                    return None;
+                }
+
+                let summary = ReplaySummary::new(&replay, &metadata_provider);
+                if !mode_filter.matches(&summary) {
+                    return None;
+                }
+                if rules.keep_in_collections && settings.replay_collections.iter().any(|collection| collection.filter.matches(&summary)) {
+                    return None;
+                }
+                let file_name = path.file_name()?.to_string_lossy();
+                if rules.keep_annotated && (settings.replay_titles.contains_key(file_name.as_ref()) || settings.linked_videos.contains_key(file_name.as_ref())) {
+                    return None;
+                }
+
+                Some(CleanupCandidate {
+                    path: path.clone(),
+                    label: summary.label(),
+                    played_at,
+                    size: std::fs::metadata(path).map(|metadata| metadata.len()).unwrap_or_default(),
+                })
+            })
+            .collect();
+        candidates.sort_by_key(|candidate| candidate.played_at);
+
+        candidates
+    }
+
+    pub fn open_replay_cleanup(&mut self) {
+        let replays_dir = self.tab_state.world_of_warships_data.as_ref().map(|wows_data| wows_data.read().replays_dir.clone());
+        let replays = self.tab_state.replay_files.as_ref().map(|replay_files| replay_files.len()).unwrap_or_default();
+        self.tab_state.replay_cleanup_window = Some(ReplayCleanupWindow {
+            folder_usage: replays_dir.map(|replays_dir| (storage::disk_usage(&replays_dir), replays)),
+            preview: None,
+        });
+    }
+
+    pub fn build_replay_cleanup_window(&mut self, ctx: &egui::Context) {
+        if self.tab_state.replay_cleanup_window.is_none() {
+            return;
+        }
+
+        let mut is_open = !self.tab_state.is_locked();
+        let mut preview = false;
+        let mut delete = None;
+        egui::Window::new(format!("{} Clean Up Replays", icons::BROOM))
+            .open(&mut is_open)
+            .default_size((600.0, 450.0))
+            .show(ctx, |ui| {
+                let Some(window) = self.tab_state.replay_cleanup_window.as_ref() else {
+                    return;
+                };
+                match window.folder_usage {
+                    Some((size, replays)) => {
+                        ui.label(format!(
+                            "The replays folder uses {} across {} replays.",
+                            humansize::format_size(size, humansize::DECIMAL),
+                            replays
+                        ));
+                    }
+                    None => {
+                        ui.label("Game data must be loaded to find the replays folder.");
+                    }
+                }
+
+                let rules = &mut self.tab_state.settings.replay_cleanup;
+                let mut changed = false;
+                ui.horizontal(|ui| {
+                    ui.label("Delete Replays Older Than");
+                    changed |= ui.add(egui::DragValue::new(&mut rules.older_than_days).range(1..=3650).suffix(" days")).changed();
+                });
+                ui.horizontal(|ui| {
+                    let label = ui.label("Game Mode Contains");
+                    changed |= ui
+                        .add(egui::TextEdit::singleline(&mut rules.game_mode).hint_text("Any"))
+                        .labelled_by(label.id)
+                        .on_hover_text("e.g. Co-op or Operation. Leave empty to include every game mode.")
+                        .changed();
+                });
+                changed |= ui.checkbox(&mut rules.keep_in_collections, "Keep Replays in a Collection").changed();
+                changed |= ui.checkbox(&mut rules.keep_annotated, "Keep Replays With a Title or Linked Video").changed();
+                // A preview for other rules shouldn't be what gets deleted
+                if changed {
+                    if let Some(window) = self.tab_state.replay_cleanup_window.as_mut() {
+                        window.preview = None;
+                    }
+                }
+
+                ui.separator();
+                let Some(candidates) = self.tab_state.replay_cleanup_window.as_ref().and_then(|window| window.preview.as_ref()) else {
+                    if ui.button(format!("{} Preview", icons::EYE)).clicked() {
+                        preview = true;
+                    }
+                    return;
+                };
+
+                let total_size: u64 = candidates.iter().map(|candidate| candidate.size).sum();
+                ui.horizontal(|ui| {
+                    ui.label(format!(
+                        "{} replays ({}) match these rules.",
+                        candidates.len(),
+                        humansize::format_size(total_size, humansize::DECIMAL)
+                    ));
+                    if ui
+                        .add_enabled(!candidates.is_empty(), egui::Button::new(format!("{} Move to Trash", icons::TRASH)))
+                        .on_hover_text("Can be undone from the Edit menu. Trashed replays are kept until the storage settings clear them.")
+                        .clicked()
+                    {
+                        delete = Some(candidates.iter().map(|candidate| candidate.path.clone()).collect::<Vec<_>>());
+                    }
+                });
+
+                TableBuilder::new(ui)
+                    .striped(true)
+                    .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+                    .column(Column::remainder().clip(true))
+                    .column(Column::auto())
+                    .body(|body| {
+                        body.rows(20.0, candidates.len(), |mut row| {
+                            let candidate = &candidates[row.index()];
+                            row.col(|ui| {
+                                ui.label(candidate.label.as_str()).on_hover_text(candidate.path.to_string_lossy().into_owned());
+                            });
+                            row.col(|ui| {
+                                ui.label(humansize::format_size(candidate.size, humansize::DECIMAL));
+                            });
+                        });
+                    });
+            });
+
+        if preview {
+            let candidates = self.cleanup_candidates();
+            if let Some(window) = self.tab_state.replay_cleanup_window.as_mut() {
+                window.preview = Some(candidates);
+            }
+        }
+        if let Some(paths) = delete {
+            let count = paths.len();
+            self.tab_state.perform(Operation::TrashReplays(paths));
+            *self.tab_state.timed_message.write() = Some(TimedMessage::new(format!(
+                "{} Moved {} replays to the trash. Undo from the Edit menu to restore them.",
+                icons::TRASH,
+                count
+            )));
+            self.open_replay_cleanup();
+        }
+        if !is_open {
+            self.tab_state.replay_cleanup_window = None;
+        }
+    }
+}
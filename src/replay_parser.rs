@@ -16,13 +16,12 @@ use tracing::debug;
 
 use wows_replays::{
     analyzer::{
-        battle_controller::{BattleController, BattleReport, ChatChannel, GameMessage, Player},
+        battle_controller::{BattleController, BattleReport, ChatChannel, GameMessage, Player, VehicleEntity},
         AnalyzerMut,
     },
     ReplayFile,
 };
 
-use itertools::Itertools;
 use wowsunpack::{
     data::ResourceLoader,
     game_params::{provider::GameMetadataProvider, types::Species},
@@ -30,14 +29,23 @@ use wowsunpack::{
 
 use crate::{
     app::{ReplayParserTabState, ToolkitTabViewer},
+    collections::{ReplayFilter, ReplaySummary},
+    contribution,
     error::ToolkitError,
+    expected_values,
+    integrity::ClockTracker,
+    loadout::Loadout,
     plaintext_viewer::{self, FileType},
-    util::{self, build_ship_config_url, build_short_ship_config_url, build_wows_numbers_url, player_color_for_team_relation, separate_number},
+    region::Region,
+    scouting::results_value,
+    task,
+    undo::Operation,
+    util::{self, build_ship_config_url, build_short_ship_config_url, build_wows_numbers_url, separate_number},
 };
 
 const CHAT_VIEW_WIDTH: f32 = 500.0;
-const XP_INDEX: usize = 389;
-const DAMAGE_INDEX: usize = 412;
+pub(crate) const XP_INDEX: usize = 389;
+pub(crate) const DAMAGE_INDEX: usize = 412;
 
 const DAMAGE_AP: usize = 147;
 const DAMAGE_SAP: usize = 148;
@@ -48,10 +56,34 @@ const DAMAGE_NORMAL_TORPS: usize = 153;
 const DAMAGE_DEEP_WATER_TORPS: usize = 154;
 const DAMAGE_FIRE: usize = 166;
 const DAMAGE_FLOODS: usize = 167;
+/// Results indices of the damage dealt by each source, with a label for the damage breakdown
+pub(crate) const DAMAGE_SOURCES: [(usize, &str); 9] = [
+    (DAMAGE_AP, "AP"),
+    (DAMAGE_SAP, "SAP"),
+    (DAMAGE_HE, "HE"),
+    (DAMAGE_HE_SECONDARIES, "HE Sec"),
+    (DAMAGE_SAP_SECONDARIES, "SAP Sec"),
+    (DAMAGE_NORMAL_TORPS, "Torps"),
+    (DAMAGE_DEEP_WATER_TORPS, "Deep Water Torps"),
+    (DAMAGE_FIRE, "Fire"),
+    (DAMAGE_FLOODS, "Flood"),
+];
+pub(crate) const SPOTTING_DAMAGE_INDEX: usize = 398;
+pub(crate) const ARTILLERY_POTENTIAL_DAMAGE: usize = 402;
+const _TORPEDO_POTENTIAL_DAMAGE: usize = 403; // may not be accurate?
+pub(crate) const AIRSTRIKE_POTENTIAL_DAMAGE: usize = 404;
 
 
 pub type SharedReplayParserTabState = Arc<Mutex<ReplayParserTabState>>;
 
+/// The replays in the order they're listed, kept so the toolbar doesn't have to sort and filter them every frame
+pub struct ReplayListing {
+    /// Filter of the collection which was selected when the listing was built
+    filter: Option<ReplayFilter>,
+    /// Each replay along with whether it's in the selected collection
+    replays: Vec<(Arc<RwLock<Replay>>, bool)>,
+}
+
 pub struct Replay {
     pub replay_file: ReplayFile,
 
@@ -59,11 +91,45 @@ pub struct Replay {
 
     pub battle_report: Option<BattleReport>,
 
+    /// How long the battle ran in seconds, from the clock of its last packet. Set along with the battle
+    /// report by analyses over many replays.
+    pub battle_length: Option<f32>,
+
     pub divisions: HashMap<u32, char>,
 
     pub remaining_div_identifiers: String,
 }
 
+/// Why a player looks like they were AFK or a bot, if they do
+fn inactivity_reason(entity: &VehicleEntity) -> Option<&'static str> {
+    if entity.player()?.did_disconnect() {
+        return Some("Player disconnected from the match");
+    }
+
+    // Results are only in the replay if the recording player stayed until the end
+    let results = entity.results_info()?.as_array()?;
+    let damage = results.get(DAMAGE_INDEX)?.as_i64()?;
+    let spotting_damage = results.get(SPOTTING_DAMAGE_INDEX)?.as_i64()?;
+    if damage == 0 && spotting_damage == 0 {
+        return Some("Player dealt no damage and spotted nothing");
+    }
+
+    None
+}
+
+/// Number of players on the recording player's team and on the enemy team who don't look AFK
+fn effective_team_sizes(report: &BattleReport) -> (usize, usize) {
+    report
+        .player_entities()
+        .iter()
+        .filter(|entity| inactivity_reason(entity).is_none())
+        .filter_map(|entity| entity.player())
+        .fold(
+            (0, 0),
+            |(allies, enemies), player| if player.relation() > 1 { (allies, enemies + 1) } else { (allies + 1, enemies) },
+        )
+}
+
 fn player_name_with_clan(player: &Player) -> Cow<'_, str> {
     if player.clan().is_empty() {
         Cow::Borrowed(player.name())
@@ -78,38 +144,81 @@ impl Replay {
             replay_file,
             resource_loader,
             battle_report: None,
+            battle_length: None,
             divisions: HashMap::new(),
             remaining_div_identifiers: "ABCDEFGHIJKLMNOPQRSTUVWXYZ".chars().rev().collect(),
         }
     }
+
+    /// Build number the replay was recorded on
+    pub fn build(&self) -> Option<&str> {
+        self.replay_file.meta.clientVersionFromExe.split(',').nth(3).map(str::trim)
+    }
+
+    /// Whether the replay was recorded on a newer build than `game_version`, so it's parsed with older entity
+    /// definitions and its results may be incomplete
+    pub fn is_newer_than(&self, game_version: usize) -> bool {
+        self.build()
+            .and_then(|build| build.parse::<usize>().ok())
+            .map(|build| build > game_version)
+            .unwrap_or(false)
+    }
+
+    /// Parses the replay, refusing replays which weren't recorded on `expected_build`
     pub fn parse(&self, expected_build: &str) -> Result<BattleReport, ToolkitError> {
+        self.parse_with(expected_build, false).map(|(report, _battle_length)| report)
+    }
+
+    /// Like [Replay::parse], but also returns how long the battle ran in seconds
+    pub fn parse_timed(&self, expected_build: &str) -> Result<(BattleReport, f32), ToolkitError> {
+        self.parse_with(expected_build, false)
+    }
+
+    /// Like [Replay::parse], but replays from a newer build are parsed with the loaded entity definitions
+    /// instead of being refused. Only meant for viewing a single replay, which is marked as approximate,
+    /// since results from these shouldn't end up in totals.
+    pub fn parse_approximate(&self, expected_build: &str) -> Result<BattleReport, ToolkitError> {
+        self.parse_with(expected_build, true).map(|(report, _battle_length)| report)
+    }
+
+    fn parse_with(&self, expected_build: &str, allow_newer: bool) -> Result<(BattleReport, f32), ToolkitError> {
         let version_parts: Vec<_> = self.replay_file.meta.clientVersionFromExe.split(',').collect();
         assert!(version_parts.len() == 4);
         if version_parts[3] != expected_build {
-            return Err(ToolkitError::ReplayVersionMismatch {
-                game_version: expected_build.to_string(),
-                replay_version: version_parts[3].to_string(),
-            });
+            // On patch day replays can be newer than the loaded game data. Entity definitions rarely change
+            // much between builds, so those can be parsed with the nearest specs available.
+            let is_newer = expected_build.parse::<usize>().map(|game_version| self.is_newer_than(game_version)).unwrap_or(false);
+            if !(allow_newer && is_newer) {
+                return Err(ToolkitError::ReplayVersionMismatch {
+                    game_version: expected_build.to_string(),
+                    replay_version: version_parts[3].to_string(),
+                });
+            }
+
+            debug!("parsing replay from build {} with specs from build {}", version_parts[3], expected_build);
         }
 
         // Parse packets
         let packet_data = &self.replay_file.packet_data;
-        let mut controller = BattleController::new(&self.replay_file.meta, self.resource_loader.as_ref());
+        let mut controller = ClockTracker {
+            analyzer: BattleController::new(&self.replay_file.meta, self.resource_loader.as_ref()),
+            last_clock: 0.0,
+        };
         let mut p = wows_replays::packet2::Parser::new(self.resource_loader.entity_specs());
 
         let report = match p.parse_packets_mut(packet_data, &mut controller) {
             Ok(()) => {
                 controller.finish();
-                controller.build_report()
+                controller.analyzer.build_report()
             }
             Err(e) => {
                 debug!("{:?}", e);
                 controller.finish();
-                controller.build_report()
+                controller.analyzer.build_report()
             }
         };
 
-        Ok(report)
+        Ok((report, controller.last_clock))
     }
     pub fn assign_divs(&mut self) {
         if let Some(report) = self.battle_report.as_ref() {
@@ -146,6 +255,7 @@ impl ToolkitTabViewer<'_> {
 
     fn build_replay_player_list(&self, replay_file: &Replay, report: &BattleReport, ui: &mut egui::Ui) {
         let is_dark_mode = ui.visuals().dark_mode;
+        let linked_video = self.current_linked_video();
         let table = TableBuilder::new(ui)
             .striped(true)
             .resizable(true)
@@ -161,6 +271,8 @@ impl ToolkitTabViewer<'_> {
                 }
             })
             .column(Column::initial(100.0).clip(true))
+            // Loadout
+            .column(Column::initial(150.0).clip(true))
             .pipe(|table| {
                 if self.tab_state.settings.replay_settings.show_observed_damage {
                     table.column(Column::initial(115.0).clip(true))
@@ -173,6 +285,15 @@ impl ToolkitTabViewer<'_> {
             .column(Column::initial(115.0).clip(true))
             // Time lived
             .column(Column::initial(90.0).clip(true))
+            // Contribution
+            .column(Column::initial(100.0).clip(true))
+            .pipe(|table| {
+                if self.tab_state.expected_values.is_some() {
+                    table.column(Column::initial(80.0).clip(true))
+                } else {
+                    table
+                }
+            })
             .column(Column::initial(100.0).clip(true))
             .column(Column::remainder())
             .min_scrolled_height(0.0);
@@ -196,6 +317,9 @@ impl ToolkitTabViewer<'_> {
                 header.col(|ui| {
                     ui.strong("Ship Name");
                 });
+                header.col(|ui| {
+                    ui.strong(format!("Loadout {}", icons::INFO)).on_hover_text("Hover over a player's loadout to see their modules, upgrades, consumables and signal flags. Flags change how much damage and XP a player gets, so keep them in mind when comparing players.");
+                });
                 if self.tab_state.settings.replay_settings.show_observed_damage {
                     header.col(|ui| {
                         ui.strong(format!("Observed Damage {}", icons::INFO)).on_hover_text(
@@ -221,6 +345,18 @@ impl ToolkitTabViewer<'_> {
                 header.col(|ui| {
                     ui.strong("Time Lived");
                 });
+                header.col(|ui| {
+                    ui.strong(format!("Contribution {}", icons::INFO)).on_hover_text(
+                        "Weighted damage, spotting damage, tanking and survival compared to other players of the same ship class in this battle. 100 is average for the class. The weights can be changed in the settings.",
+                    );
+                });
+                if self.tab_state.expected_values.is_some() {
+                    header.col(|ui| {
+                        ui.strong(format!("Rating {}", icons::INFO)).on_hover_text(
+                            "PR-like rating from the imported expected values. Battle results don't include frags or the outcome, so only damage compared to the ship's expected damage is rated.",
+                        );
+                    });
+                }
                 header.col(|ui| {
                     ui.strong("Allocated Skills");
                 });
@@ -229,14 +365,18 @@ impl ToolkitTabViewer<'_> {
                 });
             })
             .body(|mut body| {
+                let team_palette = &self.tab_state.settings.team_palette;
                 let mut sorted_players = report.player_entities().to_vec();
                 sorted_players.sort_unstable_by_key(|item| {
                     let player = item.player().unwrap();
                     (player.relation(), player.vehicle().species(), player.entity_id())
                 });
-                for entity in &sorted_players {
+                let contribution_scores = contribution::contribution_scores(&sorted_players, &self.tab_state.settings.replay_settings.contribution_weights);
+                let mvp = contribution::battle_mvp(&contribution_scores);
+                for (i, entity) in sorted_players.iter().enumerate() {
                     let player = entity.player().unwrap();
-                    let player_color = player_color_for_team_relation(player.relation(), is_dark_mode);
+                    let custom_color = team_palette.player_color(player.name());
+                    let player_color = custom_color.unwrap_or_else(|| team_palette.color_for_relation(player.relation(), is_dark_mode));
                     let ship = player.vehicle();
 
                     body.row(30.0, |mut ui| {
@@ -250,18 +390,18 @@ impl ToolkitTabViewer<'_> {
                                 })
                                 .unwrap_or_else(|| "unk".to_string());
                             if let Some(icon) = self.ship_class_icon_from_species(ship.species().expect("ship has no species")) {
-                                let mut color = match player.relation() {
-                                    0 => Color32::GOLD,
-                                    1 => Color32::LIGHT_GREEN,
-                                    _ => Color32::LIGHT_RED,
-                                };
+                                let mut color = team_palette.color_for_relation(player.relation(), true);
 
                                 if let Some(self_player) = sorted_players[0].player() {
                                     if player.team_id() == self_player.team_id() {
-                                        color = Color32::GOLD;
+                                        color = team_palette.color_for_relation(0, true);
                                     }
                                 }
 
+                                if let Some(custom_color) = custom_color {
+                                    color = custom_color;
+                                }
+
                                 let image = Image::new(ImageSource::Bytes {
                                     uri: icon.path.clone().into(),
                                     // the icon size is <1k, this clone is fairly cheap
@@ -285,17 +425,19 @@ impl ToolkitTabViewer<'_> {
                                 ui.label(format!("({})", div));
                             }
                             ui.label(RichText::new(player_name_with_clan(player)).color(name_color));
+                            if self.tab_state.settings.clan.is_clanmate(player.db_id(), player.clan()) {
+                                ui.label(icons::USERS_THREE).on_hover_text("Clanmate");
+                            }
+                            if let Some(tracked_player) = self.tab_state.settings.player_tracker.read().tracked_player(player.db_id()) {
+                                tracked_player.build_karma_badge(ui);
+                            }
                             if player.is_hidden() {
                                 ui.label(icons::EYE_SLASH).on_hover_text("Player has a hidden profile");
                             }
-                            let disconnect_hover_text = if player.did_disconnect() {
-                                Some("Player disconnected from the match")
-                            } else {
-                                None
-                            };
-
-                            if let Some(disconnect_text) = disconnect_hover_text {
-                                ui.label(icons::PLUGS).on_hover_text(disconnect_text);
+                            if player.did_disconnect() {
+                                ui.label(icons::PLUGS).on_hover_text("Player disconnected from the match");
+                            } else if let Some(inactivity_text) = inactivity_reason(entity) {
+                                ui.label(icons::BED).on_hover_text(inactivity_text);
                             }
                         });
                         ui.col(|ui| {
@@ -329,6 +471,15 @@ impl ToolkitTabViewer<'_> {
                             ui.label(ship_name);
                         });
 
+                        ui.col(|ui| {
+                            if let Some(metadata_provider) = self.metadata_provider() {
+                                let loadout = Loadout::new(entity, &metadata_provider);
+                                ui.label(loadout.summary()).on_hover_text(RichText::new(loadout.details()).font(FontId::monospace(12.0)));
+                            } else {
+                                ui.label("-");
+                            }
+                        });
+
                         if self.tab_state.settings.replay_settings.show_observed_damage {
                             ui.col(|ui| {
                                 ui.label(separate_number(entity.damage(), self.tab_state.settings.locale.as_ref().map(|s| s.as_ref())));
@@ -341,19 +492,7 @@ impl ToolkitTabViewer<'_> {
 
                                 if let Some(damage_number) =  info_array[DAMAGE_INDEX].as_number().and_then(|number| number.as_i64()) {
                                     // Grab other damage numbers
-                                    let damage_stats = [
-                                        (DAMAGE_AP, "AP"),
-                                        (DAMAGE_SAP, "SAP"),
-                                        (DAMAGE_HE, "HE"),
-                                        (DAMAGE_HE_SECONDARIES, "HE Sec"),
-                                        (DAMAGE_SAP_SECONDARIES, "SAP Sec"),
-                                        (DAMAGE_NORMAL_TORPS, "Torps"),
-                                        (DAMAGE_DEEP_WATER_TORPS, "Deep Water Torps"),
-                                        (DAMAGE_FIRE, "Fire"),
-                                        (DAMAGE_FLOODS, "Flood")
-                                    ];
-
-                                    let breakdowns: Vec<String> = damage_stats.iter().filter_map(|(idx, description)| {
+                                    let breakdowns: Vec<String> = DAMAGE_SOURCES.iter().filter_map(|(idx, description)| {
                                         info_array[*idx].as_number().and_then(|number| number.as_i64()).map(|num| {
                                             let num =  separate_number(num, self.tab_state.settings.locale.as_ref().map(|s| s.as_ref()));
                                             format!("{:<16}: {}", description, num)
@@ -390,10 +529,6 @@ impl ToolkitTabViewer<'_> {
                         });
                         // Potential damage
                         ui.col(|ui| {
-                            const ARTILLERY_POTENTIAL_DAMAGE: usize = 402;
-                            const _TORPEDO_POTENTIAL_DAMAGE: usize = 403; // may not be accurate?
-                            const AIRSTRIKE_POTENTIAL_DAMAGE: usize = 404;
-
                             if let Some(damage_numbers) = entity.results_info().and_then(|info| info.as_array().map(|info_array| &info_array[ARTILLERY_POTENTIAL_DAMAGE..=AIRSTRIKE_POTENTIAL_DAMAGE])) {
                                 let total_pot = damage_numbers.iter().map(|num| num.as_f64()).fold(0, |accum, num| accum + num.map(|f| f as u64).unwrap_or_default());
                                 let hover_string = format!("Artillery: {}\nTorpedo: {}\nPlanes: {}",
@@ -417,6 +552,34 @@ impl ToolkitTabViewer<'_> {
                             }
                         });
 
+                        ui.col(|ui| {
+                            if let Some(score) = contribution_scores[i] {
+                                if mvp == Some(i) {
+                                    ui.label(RichText::new(format!("{} {:.0}", icons::TROPHY, score)).color(Color32::GOLD)).on_hover_text("Battle MVP");
+                                } else {
+                                    ui.label(format!("{:.0}", score));
+                                }
+                            } else {
+                                ui.label("-");
+                            }
+                        });
+
+                        if let Some(expected_values) = self.tab_state.expected_values.as_ref() {
+                            ui.col(|ui| {
+                                let ship_id = ship.id().to_string();
+                                let damage = results_value(entity.results_info(), DAMAGE_INDEX);
+                                if let Some((rating, expected)) = damage.and_then(|damage| Some((expected_values.battle_rating(&ship_id, damage)?, expected_values.ship(&ship_id)?))) {
+                                    let hover_text = format!(
+                                        "Expected damage: {:.0}\nExpected frags: {:.2}\nExpected win rate: {:.1}%",
+                                        expected.average_damage_dealt, expected.average_frags, expected.win_rate
+                                    );
+                                    ui.label(RichText::new(format!("{:.0}", rating)).color(expected_values::rating_color(rating))).on_hover_text(hover_text);
+                                } else {
+                                    ui.label("-");
+                                }
+                            });
+                        }
+
                         let species = ship.species().expect("ship has no species?");
                         let (skill_points, num_skills, highest_tier, num_tier_1_skills) = entity
                             .commander_skills()
@@ -476,17 +639,22 @@ impl ToolkitTabViewer<'_> {
 
                                 ui.separator();
 
+                                // Fall back to the configured region for realms we don't know
+                                let player_region = entity
+                                    .player()
+                                    .and_then(|player| Some((Region::from_realm(player.realm()).or(self.tab_state.settings.region)?, player)));
+
                                 if ui.small_button(format!("{} Open Tomato.gg Page", icons::SHARE)).clicked() {
-                                    if let Some(url) = build_tomato_gg_url(entity) {
-                                        ui.ctx().open_url(OpenUrl::new_tab(url));
+                                    if let Some((region, player)) = player_region {
+                                        ui.ctx().open_url(OpenUrl::new_tab(build_tomato_gg_url(region, player.db_id(), player.name())));
                                     }
 
                                     ui.close_menu();
                                 }
 
                                 if ui.small_button(format!("{} Open WoWs Numbers Page", icons::SHARE)).clicked() {
-                                    if let Some(url) = build_wows_numbers_url(entity) {
-                                        ui.ctx().open_url(OpenUrl::new_tab(url));
+                                    if let Some((region, player)) = player_region {
+                                        ui.ctx().open_url(OpenUrl::new_tab(build_wows_numbers_url(region, player.db_id(), player.name())));
                                     }
 
                                     ui.close_menu();
@@ -494,6 +662,23 @@ impl ToolkitTabViewer<'_> {
 
                                 ui.separator();
 
+                                if let (Some(video), Some(death_info)) = (linked_video, entity.death_info()) {
+                                    if ui.small_button(format!("{} Copy Death Time in Video", icons::FILM_STRIP)).clicked() {
+                                        self.copy_video_timestamp(ui, video, death_info.time_lived());
+                                        ui.close_menu();
+                                    }
+
+                                    ui.separator();
+                                }
+
+                                if player.relation() != 0 && !self.tab_state.is_locked() {
+                                    ui.menu_button(format!("{} Rate Player", icons::THUMBS_UP), |ui| {
+                                        self.tab_state.settings.player_tracker.write().build_rating_menu(ui, player.db_id(), report.arena_id());
+                                    });
+
+                                    ui.separator();
+                                }
+
                                 if ui.small_button(format!("{} View Raw Player Metadata", icons::BUG)).clicked() {
                                     let pretty_meta = serde_json::to_string_pretty(player).expect("failed to serialize player");
                                     let viewer = plaintext_viewer::PlaintextFileViewer {
@@ -540,8 +725,10 @@ impl ToolkitTabViewer<'_> {
 
 
             let is_dark_mode = ui.visuals().dark_mode;
-            let name_color = if let Some(relation) = sender_relation {
-                player_color_for_team_relation(*relation, is_dark_mode)
+            let name_color = if let Some(custom_color) = self.tab_state.settings.team_palette.player_color(sender_name) {
+                custom_color
+            } else if let Some(relation) = sender_relation {
+                self.tab_state.settings.team_palette.color_for_relation(*relation, is_dark_mode)
             } else {
                 Color32::GRAY
             };
@@ -565,13 +752,7 @@ impl ToolkitTabViewer<'_> {
                         Color32::BLACK
                     }
                 }
-                ChatChannel::Team => {
-                    if is_dark_mode {
-                        Color32::LIGHT_GREEN
-                    } else {
-                        Color32::DARK_GREEN
-                    }
-                }
+                ChatChannel::Team => self.tab_state.settings.team_palette.color_for_relation(1, is_dark_mode),
             };
 
             job.append(
@@ -596,12 +777,33 @@ impl ToolkitTabViewer<'_> {
         if let Some(report) = replay_file.battle_report.as_ref() {
             let self_entity = report.self_entity();
             let self_player = self_entity.player().unwrap();
+            let replay_title = self.current_replay_title();
             ui.horizontal(|ui| {
+                if let Some(replay_title) = replay_title {
+                    let title = ui.strong(replay_title.title.as_str());
+                    if !replay_title.description.trim().is_empty() {
+                        title.on_hover_text(replay_title.description.as_str());
+                    }
+                }
                 ui.label(player_name_with_clan(self_player));
                 ui.label(report.game_type());
                 ui.label(report.version().to_path());
+                let game_version = self.tab_state.world_of_warships_data.as_ref().map(|wows_data| wows_data.read().game_version);
+                if let Some(game_version) = game_version.filter(|game_version| replay_file.is_newer_than(*game_version)) {
+                    let hover_text = format!(
+                        "Recorded on build {}, which is newer than the loaded game data (build {}). It was read with the older \
+                         entity definitions, so some values may be missing or wrong until game data for the new build is loaded.",
+                        replay_file.build().unwrap_or_default(),
+                        game_version
+                    );
+                    ui.label(RichText::new(format!("{} Approximate", icons::WARNING)).color(ui.visuals().warn_fg_color))
+                        .on_hover_text(hover_text);
+                }
                 ui.label(report.game_mode());
                 ui.label(report.map_name());
+                let (allies, enemies) = effective_team_sizes(report);
+                ui.label(format!("Effective Team Size: {} vs {}", allies, enemies))
+                    .on_hover_text("Players who disconnected, or who dealt no damage and spotted nothing, aren't counted");
                 if report.battle_results().is_some() {
                     let mut team_damage = 0;
                     let mut red_team_damage = 0;
@@ -628,7 +830,7 @@ impl ToolkitTabViewer<'_> {
                         &separate_number(team_damage, self.tab_state.settings.locale.as_ref().map(|s| s.as_ref())),
                         0.0,
                         TextFormat {
-                            color: Color32::LIGHT_GREEN,
+                            color: self.tab_state.settings.team_palette.color_for_relation(1, true),
                             ..Default::default()
                         },
                     );
@@ -637,7 +839,7 @@ impl ToolkitTabViewer<'_> {
                         &separate_number(red_team_damage, self.tab_state.settings.locale.as_ref().map(|s| s.as_ref())),
                         0.0,
                         TextFormat {
-                            color: Color32::LIGHT_RED,
+                            color: self.tab_state.settings.team_palette.color_for_relation(2, true),
                             ..Default::default()
                         },
                     );
@@ -656,7 +858,10 @@ impl ToolkitTabViewer<'_> {
                 ui.menu_button("Export Chat", |ui| {
                     if ui.small_button(format!("{} Save To File", icons::FLOPPY_DISK)).clicked() {
                         if let Some(path) = rfd::FileDialog::new()
-                            .set_file_name(format!("{} {} {} - Game Chat.txt", report.game_type(), report.game_mode(), report.map_name()))
+                            .set_file_name(util::sanitize_file_name(&match replay_title {
+                                Some(replay_title) => format!("{} - Game Chat.txt", replay_title.title),
+                                None => format!("{} {} {} - Game Chat.txt", report.game_type(), report.game_mode(), report.map_name()),
+                            }))
                             .save_file()
                         {
                             if let Ok(mut file) = std::fs::File::create(path) {
@@ -751,41 +956,37 @@ impl ToolkitTabViewer<'_> {
     }
 
     fn build_file_listing(&mut self, ui: &mut egui::Ui) {
+        self.build_collection_editor(ui.ctx());
+        self.build_replay_title_editor(ui.ctx());
+        self.build_linked_video_editor(ui.ctx());
         ui.vertical(|ui| {
             egui::Grid::new("replay_files_grid").num_columns(1).striped(true).show(ui, |ui| {
-                if let Some(mut files) = self
-                    .tab_state
-                    .replay_files
-                    .as_ref()
-                    .map(|files| files.iter().map(|(x, y)| (x.clone(), y.clone())).collect::<Vec<_>>())
-                {
-                    // Sort by filename -- WoWs puts the date first in a sortable format
-                    files.sort_by(|a, b| b.0.cmp(&a.0));
+                if let Some(files) = self.sorted_replay_files() {
                     let metadata_provider = self.metadata_provider().unwrap();
-                    for (path, replay) in files {
-                        let label = {
-                            let file = replay.read();
-                            let meta = &file.replay_file.meta;
-                            let player_vehicle = meta.vehicles.iter().find(|vehicle| vehicle.relation == 0);
-                            let vehicle_name = player_vehicle
-                                .and_then(|vehicle| metadata_provider.param_localization_id(vehicle.shipId as u32))
-                                .and_then(|id| metadata_provider.localized_name_from_id(id))
-                                .unwrap_or_else(|| "Spectator".to_string());
-                            let map_id = format!("IDS_{}", meta.mapName.to_uppercase());
-                            let map_name = metadata_provider.localized_name_from_id(&map_id).unwrap_or_else(|| meta.mapName.clone());
-
-                            let mode = metadata_provider
-                                .localized_name_from_id(&format!("IDS_{}", meta.gameType.to_ascii_uppercase()))
-                                .expect("failed to get game type translation");
-
-                            let scenario = metadata_provider
-                                .localized_name_from_id(&format!("IDS_SCENARIO_{}", meta.scenario.to_ascii_uppercase()))
-                                .expect("failed to get scenario translation");
-
-                            let time = meta.dateTime.as_str();
-
-                            [vehicle_name.as_str(), map_name.as_str(), scenario.as_str(), mode.as_str(), time].iter().join(" - ")
-                        };
+                    let accessibility_mode = self.tab_state.settings.accessibility_mode;
+                    let locked = self.tab_state.is_locked();
+                    let summaries: Vec<ReplaySummary> = files.iter().map(|(_path, replay)| ReplaySummary::new(&replay.read(), &metadata_provider)).collect();
+
+                    let collections = &self.tab_state.settings.replay_collections;
+                    let matching_counts: Vec<usize> = collections
+                        .iter()
+                        .map(|collection| summaries.iter().filter(|summary| collection.filter.matches(summary)).count())
+                        .collect();
+                    let selected_filter = self
+                        .tab_state
+                        .selected_collection
+                        .and_then(|i| collections.get(i))
+                        .map(|collection| collection.filter.clone());
+                    ui.vertical(|ui| self.build_collections_list(ui, &matching_counts));
+                    ui.end_row();
+                    ui.separator();
+                    ui.end_row();
+
+                    for ((path, replay), summary) in files.into_iter().zip(summaries) {
+                        if selected_filter.as_ref().map(|filter| !filter.matches(&summary)).unwrap_or(false) {
+                            continue;
+                        }
+                        let (label, hover_text) = self.replay_listing_label(&path, summary.label());
 
                         let mut label_text = egui::RichText::new(label.as_str());
                         if let Some(current_replay) = self.tab_state.current_replay.as_ref() {
@@ -794,7 +995,7 @@ impl ToolkitTabViewer<'_> {
                             }
                         }
 
-                        let label = ui.add(Label::new(label_text).selectable(false).sense(Sense::click())).on_hover_text(label.as_str());
+                        let label = ui.add(Label::new(label_text).selectable(false).sense(Sense::click())).on_hover_text(hover_text);
                         label.context_menu(|ui| {
                             if ui.button("Copy Path").clicked() {
                                 ui.output_mut(|output| output.copied_text = path.to_string_lossy().into_owned());
@@ -804,9 +1005,42 @@ impl ToolkitTabViewer<'_> {
                                 util::open_file_explorer(&path);
                                 ui.close_menu();
                             }
+                            if ui.add_enabled(!locked, egui::Button::new(format!("{} Rename...", icons::PENCIL_SIMPLE))).clicked() {
+                                self.edit_replay_title(&path);
+                                ui.close_menu();
+                            }
+                            if ui.add_enabled(!locked, egui::Button::new(format!("{} Link Video...", icons::FILM_STRIP))).clicked() {
+                                self.edit_linked_video(&path);
+                                ui.close_menu();
+                            }
+                            if ui.button(format!("{} Verify Integrity", icons::SEAL_CHECK)).clicked() {
+                                self.start_integrity_check(&path);
+                                ui.close_menu();
+                            }
+                            if ui
+                                .button(format!("{} Parse Diagnostics", icons::BUG))
+                                .on_hover_text("Packet and entity method counts, with hex dumps of packets that couldn't be read")
+                                .clicked()
+                            {
+                                self.start_parse_diagnostics(&path);
+                                ui.close_menu();
+                            }
+                            if ui.button(format!("{} Compare Battles in This Ship...", icons::SCALES)).clicked() {
+                                self.start_ship_comparison(&replay);
+                                ui.close_menu();
+                            }
+                            if ui
+                                .add_enabled(!locked, egui::Button::new(format!("{} Delete", icons::TRASH)))
+                                .on_hover_text("Can be undone from the Edit menu")
+                                .clicked()
+                            {
+                                self.tab_state.perform(Operation::TrashReplay(path.clone()));
+                                ui.close_menu();
+                            }
                         });
 
-                        if label.double_clicked() {
+                        let opened_with_keyboard = accessibility_mode && label.has_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                        if label.double_clicked() || opened_with_keyboard {
                             if let Some(wows_data) = self.tab_state.world_of_warships_data.as_ref() {
                                 update_background_task!(self.tab_state.background_task, wows_data.read().load_replay(replay.clone()));
                             }
@@ -818,12 +1052,103 @@ impl ToolkitTabViewer<'_> {
         });
     }
 
+    /// Replay files in the order they're listed
+    fn sorted_replay_files(&self) -> Option<Vec<(PathBuf, Arc<RwLock<Replay>>)>> {
+        let mut files = self
+            .tab_state
+            .replay_files
+            .as_ref()
+            .map(|files| files.iter().map(|(x, y)| (x.clone(), y.clone())).collect::<Vec<_>>())?;
+        // Sort by filename -- WoWs puts the date first in a sortable format
+        files.sort_by(|a, b| b.0.cmp(&a.0));
+
+        Some(files)
+    }
+
+    /// The listing order, rebuilt if the replays or the selected collection's filter changed since it was last built
+    fn replay_listing(&mut self) -> Option<&ReplayListing> {
+        let filter = self
+            .tab_state
+            .selected_collection
+            .and_then(|i| self.tab_state.settings.replay_collections.get(i))
+            .map(|collection| collection.filter.clone());
+        if self.tab_state.replay_listing.as_ref().map(|listing| listing.filter != filter).unwrap_or(true) {
+            let metadata_provider = self.metadata_provider()?;
+            let replays = self
+                .sorted_replay_files()?
+                .into_iter()
+                .map(|(_path, replay)| {
+                    let in_collection = filter
+                        .as_ref()
+                        .map(|filter| filter.matches(&ReplaySummary::new(&replay.read(), &metadata_provider)))
+                        .unwrap_or(true);
+                    (replay, in_collection)
+                })
+                .collect();
+            self.tab_state.replay_listing = Some(ReplayListing { filter, replays });
+        }
+
+        self.tab_state.replay_listing.as_ref()
+    }
+
+    /// The replay listed right after (or before) the current one, skipping replays outside the selected collection
+    fn adjacent_replay(&mut self, forward: bool) -> Option<Arc<RwLock<Replay>>> {
+        let current_replay = self.tab_state.current_replay.clone()?;
+        let replays = &self.replay_listing()?.replays;
+        let position = replays.iter().position(|(replay, _)| Arc::ptr_eq(replay, &current_replay))?;
+        let adjacent = if forward {
+            replays[position + 1..].iter().find(|(_, in_collection)| *in_collection)
+        } else {
+            replays[..position].iter().rev().find(|(_, in_collection)| *in_collection)
+        };
+
+        adjacent.map(|(replay, _)| Arc::clone(replay))
+    }
+
     pub fn clear_chat(&mut self, _replay: Arc<RwLock<Replay>>) {
         self.tab_state.replay_parser_tab.lock().game_chat.clear();
     }
 
+    /// Builds the window for downloading and opening a replay from a URL
+    fn build_replay_url_window(&mut self, ctx: &egui::Context) {
+        let Some(url) = self.tab_state.replay_url_window.as_mut() else {
+            return;
+        };
+
+        let mut is_open = true;
+        let mut start_download = false;
+        let save_downloaded_replays = &mut self.tab_state.save_downloaded_replays;
+        egui::Window::new("Open Replay From URL").open(&mut is_open).collapsible(false).show(ctx, |ui| {
+            ui.label("Paste a direct link to a .wowsreplay file");
+            let response = ui.add(egui::TextEdit::singleline(url).hint_text("https://").desired_width(400.0));
+            let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+
+            ui.checkbox(save_downloaded_replays, "Save to replays folder")
+                .on_hover_text("Keep the downloaded replay alongside your own replays instead of in a temporary folder");
+
+            let can_download = !url.trim().is_empty();
+            let download_button = egui::Button::new(format!("{} Download and Open", icons::DOWNLOAD_SIMPLE));
+            let clicked = ui.add_enabled(can_download, download_button).clicked();
+            start_download = clicked || (submitted && can_download);
+        });
+
+        if start_download {
+            let destination_dir = self.tab_state.downloaded_replays_dir();
+            if let Some(url) = self.tab_state.replay_url_window.take() {
+                update_background_task!(self.tab_state.background_task, Some(task::start_downloading_replay(url, destination_dir)));
+            }
+        } else if !is_open {
+            self.tab_state.replay_url_window = None;
+        }
+    }
+
     /// Builds the replay parser tab
     pub fn build_replay_parser_tab(&mut self, ui: &mut egui::Ui) {
+        self.build_replay_url_window(ui.ctx());
+        self.build_comparison_window(ui.ctx());
+        self.build_integrity_window(ui.ctx());
+        self.build_parse_diagnostics_window(ui.ctx());
+
         ui.vertical(|ui| {
             ui.horizontal(|ui| {
                 if ui.button(format!("{} Manually Open Replay File...", icons::FOLDER_OPEN)).clicked() {
@@ -841,6 +1166,10 @@ impl ToolkitTabViewer<'_> {
                     }
                 }
 
+                if ui.button(format!("{} Open Replay From URL...", icons::LINK)).clicked() {
+                    self.tab_state.replay_url_window.get_or_insert_with(String::new);
+                }
+
                 // Only show the live game button if the replays dir exists
                 if let Some(_replays_dir) = self.replays_dir() {
                     if ui.button(format!("{} Load Live Game", icons::DETECTIVE)).clicked() {
@@ -850,6 +1179,34 @@ impl ToolkitTabViewer<'_> {
                     }
 
                     ui.checkbox(&mut self.tab_state.auto_load_latest_replay, "Autoload Latest Replay");
+                    ui.checkbox(&mut self.tab_state.monitor_live_battles, "Load Live Battles")
+                        .on_hover_text("Loads each battle as soon as it starts. Turned on when the game is launched from here.");
+                }
+
+                if !self.tab_state.settings.wows_dir.is_empty() {
+                    let waiting = self.tab_state.is_waiting_for_game();
+                    let launch_button = ui
+                        .add_enabled(!waiting, egui::Button::new(format!("{} Launch Game", icons::ROCKET_LAUNCH)))
+                        .on_hover_text("Starts World of Warships, then loads battles automatically once it's running")
+                        .on_disabled_hover_text("Waiting for World of Warships to start...");
+                    if launch_button.clicked() {
+                        self.tab_state.launch_game(ui.ctx());
+                    }
+                }
+
+                if self.tab_state.current_replay.is_some() {
+                    ui.separator();
+                    for (forward, text, hover_text) in [
+                        (false, format!("{} Previous Replay", icons::CARET_LEFT), "Load the replay listed above this one"),
+                        (true, format!("Next Replay {}", icons::CARET_RIGHT), "Load the replay listed below this one"),
+                    ] {
+                        let adjacent_replay = self.adjacent_replay(forward);
+                        if ui.add_enabled(adjacent_replay.is_some(), egui::Button::new(text)).on_hover_text(hover_text).clicked() {
+                            if let (Some(replay), Some(wows_data)) = (adjacent_replay, self.tab_state.world_of_warships_data.as_ref()) {
+                                update_background_task!(self.tab_state.background_task, wows_data.read().load_replay(replay));
+                            }
+                        }
+                    }
                 }
             });
 
@@ -0,0 +1,116 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{app::ToolkitTabViewer, icons, usage_stats};
+
+/// A user-provided name for a replay. Kept in the settings rather than the replay file, keyed by the
+/// replay's file name so it follows the replay if the replays directory moves.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReplayTitle {
+    pub title: String,
+    pub description: String,
+}
+
+impl ReplayTitle {
+    fn is_empty(&self) -> bool {
+        self.title.trim().is_empty() && self.description.trim().is_empty()
+    }
+}
+
+/// Key per-replay data kept in the settings is stored under
+pub fn replay_key(path: &Path) -> String {
+    path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default()
+}
+
+/// State of the window used to rename a replay
+pub struct ReplayTitleEditor {
+    key: String,
+    title: ReplayTitle,
+}
+
+impl ToolkitTabViewer<'_> {
+    /// Title the user gave the replay at `path`, if any
+    pub fn replay_title(&self, path: &Path) -> Option<&ReplayTitle> {
+        self.tab_state
+            .settings
+            .replay_titles
+            .get(&replay_key(path))
+            .filter(|title| !title.title.trim().is_empty())
+    }
+
+    /// Path of the replay open in the replay viewer, if it's one from the listing
+    pub fn current_replay_path(&self) -> Option<&PathBuf> {
+        let current_replay = self.tab_state.current_replay.as_ref()?;
+        let (path, _) = self.tab_state.replay_files.as_ref()?.iter().find(|(_, replay)| Arc::ptr_eq(replay, current_replay))?;
+        Some(path)
+    }
+
+    /// Title of the replay open in the replay viewer, if the user gave it one
+    pub fn current_replay_title(&self) -> Option<&ReplayTitle> {
+        self.replay_title(self.current_replay_path()?)
+    }
+
+    /// Label and hover text for a replay in the listing. Replays the user named are shown by their
+    /// title, with the original name and the description on hover.
+    pub fn replay_listing_label(&self, path: &Path, original_label: String) -> (String, String) {
+        match self.replay_title(path) {
+            Some(title) if title.description.trim().is_empty() => (title.title.clone(), original_label),
+            Some(title) => (title.title.clone(), format!("{}\n\n{}", original_label, title.description)),
+            None => (original_label.clone(), original_label),
+        }
+    }
+
+    pub fn edit_replay_title(&mut self, path: &Path) {
+        let key = replay_key(path);
+        let title = self.tab_state.settings.replay_titles.get(&key).cloned().unwrap_or_default();
+        self.tab_state.replay_title_editor = Some(ReplayTitleEditor { key, title });
+    }
+
+    pub fn build_replay_title_editor(&mut self, ctx: &egui::Context) {
+        let Some(editor) = self.tab_state.replay_title_editor.as_mut() else {
+            return;
+        };
+
+        let mut is_open = true;
+        let mut save = false;
+        egui::Window::new(format!("{} Rename Replay", icons::PENCIL_SIMPLE))
+            .open(&mut is_open)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.label(editor.key.as_str());
+                egui::Grid::new("replay_title_editor_grid").num_columns(2).show(ui, |ui| {
+                    let label = ui.label("Title");
+                    ui.add(egui::TextEdit::singleline(&mut editor.title.title).hint_text("Game 3 vs [KRKN]"))
+                        .labelled_by(label.id);
+                    ui.end_row();
+
+                    let label = ui.label("Description");
+                    ui.add(egui::TextEdit::multiline(&mut editor.title.description).hint_text("Hill flank throw"))
+                        .labelled_by(label.id);
+                    ui.end_row();
+                });
+
+                ui.label("Leave the title empty to show the replay's original name.");
+                save = ui.button("Save").clicked();
+            });
+
+        if save {
+            if let Some(editor) = self.tab_state.replay_title_editor.take() {
+                let titles = &mut self.tab_state.settings.replay_titles;
+                if editor.title.is_empty() {
+                    titles.remove(&editor.key);
+                } else {
+                    usage_stats::record_feature("Rename replay");
+                    titles.insert(editor.key, editor.title);
+                }
+            }
+        } else if !is_open {
+            self.tab_state.replay_title_editor = None;
+        }
+    }
+}
@@ -0,0 +1,319 @@
+use std::{
+    fmt::Write,
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Duration, Local, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::{
+    app::{TabState, ToolkitTabViewer},
+    backup::{self, BACKUP_EXTENSION},
+    collections::ReplaySummary,
+    error::ToolkitError,
+    icons,
+    notifications::NotificationAction,
+    player_extraction::csv_field,
+    storage,
+    tournament::REPLAY_DATE_FORMAT,
+    undo::{trash_dir, TRASH_DATE_FORMAT},
+};
+
+/// Replays deleted from the listing are kept this long before cache cleanup removes them
+const TRASH_RETENTION_DAYS: i64 = 30;
+
+/// A recurring job run while the app is open
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScheduledJob {
+    ExportBattles,
+    BackUpTracker,
+    CleanCache,
+}
+
+impl ScheduledJob {
+    const ALL: [ScheduledJob; 3] = [ScheduledJob::ExportBattles, ScheduledJob::BackUpTracker, ScheduledJob::CleanCache];
+
+    fn name(&self) -> &'static str {
+        match self {
+            ScheduledJob::ExportBattles => "Export New Battles",
+            ScheduledJob::BackUpTracker => "Back Up Player Tracker",
+            ScheduledJob::CleanCache => "Clean Up Caches",
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        match self {
+            ScheduledJob::ExportBattles => "Writes a CSV of the battles played since the last successful export to the export folder",
+            ScheduledJob::BackUpTracker => "Writes a backup of your settings and player tracker to the export folder, where it isn't rotated out like automatic backups",
+            ScheduledJob::CleanCache => "Trims downloaded replays to the size limit and removes replays deleted more than 30 days ago",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JobSchedule {
+    pub enabled: bool,
+    pub interval_hours: u64,
+    pub last_run: Option<DateTime<Local>>,
+    /// Outcome of the last run, shown in settings
+    pub last_status: Option<String>,
+}
+
+impl JobSchedule {
+    fn new(interval_hours: u64) -> Self {
+        Self {
+            enabled: false,
+            interval_hours,
+            last_run: None,
+            last_status: None,
+        }
+    }
+
+    fn is_due(&self) -> bool {
+        self.enabled
+            && self
+                .last_run
+                .map(|last_run| Local::now() - last_run > Duration::hours(self.interval_hours.max(1) as i64))
+                .unwrap_or(true)
+    }
+}
+
+impl Default for JobSchedule {
+    fn default() -> Self {
+        Self::new(24)
+    }
+}
+
+/// Recurring jobs and when they last ran
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScheduledJobs {
+    /// Where battle exports and tracker backups are written
+    pub export_dir: Option<PathBuf>,
+    pub export_battles: JobSchedule,
+    pub back_up_tracker: JobSchedule,
+    pub clean_cache: JobSchedule,
+    /// Battles played up to this time have been exported. Only moved forward when an export succeeds,
+    /// so battles from a failed run are picked up by the next one.
+    pub exported_until: Option<DateTime<Local>>,
+}
+
+impl Default for ScheduledJobs {
+    fn default() -> Self {
+        Self {
+            export_dir: None,
+            export_battles: JobSchedule::new(24),
+            back_up_tracker: JobSchedule::new(24 * 7),
+            clean_cache: JobSchedule::new(24),
+            exported_until: None,
+        }
+    }
+}
+
+impl ScheduledJobs {
+    fn schedule(&self, job: ScheduledJob) -> &JobSchedule {
+        match job {
+            ScheduledJob::ExportBattles => &self.export_battles,
+            ScheduledJob::BackUpTracker => &self.back_up_tracker,
+            ScheduledJob::CleanCache => &self.clean_cache,
+        }
+    }
+
+    fn schedule_mut(&mut self, job: ScheduledJob) -> &mut JobSchedule {
+        match job {
+            ScheduledJob::ExportBattles => &mut self.export_battles,
+            ScheduledJob::BackUpTracker => &mut self.back_up_tracker,
+            ScheduledJob::CleanCache => &mut self.clean_cache,
+        }
+    }
+
+    fn export_dir(&self) -> Result<&Path, ToolkitError> {
+        self.export_dir.as_deref().ok_or(ToolkitError::NoExportDirectory)
+    }
+}
+
+/// Deletes trashed replays older than [TRASH_RETENTION_DAYS], returning the paths which were deleted
+fn empty_old_trash() -> Vec<PathBuf> {
+    let Ok(read_dir) = std::fs::read_dir(trash_dir()) else {
+        return Vec::new();
+    };
+
+    let cutoff = Local::now().naive_local() - Duration::days(TRASH_RETENTION_DAYS);
+    let mut deleted = Vec::new();
+    for entry in read_dir.flatten() {
+        let file_name = entry.file_name().to_string_lossy().into_owned();
+        let trashed_at = file_name
+            .split_once('_')
+            .and_then(|(trashed_at, _)| NaiveDateTime::parse_from_str(trashed_at, TRASH_DATE_FORMAT).ok());
+        if trashed_at.map(|trashed_at| trashed_at < cutoff).unwrap_or(false) {
+            debug!("removing old trashed replay {:?}", entry.path());
+            if std::fs::remove_file(entry.path()).is_ok() {
+                deleted.push(entry.path());
+            }
+        }
+    }
+
+    deleted
+}
+
+impl TabState {
+    /// Runs every enabled job whose interval has passed
+    pub fn run_due_jobs(&mut self) {
+        for job in ScheduledJob::ALL {
+            // Battles can't be listed until game data and the replays in it are loaded
+            if job == ScheduledJob::ExportBattles && self.replay_files.is_none() {
+                continue;
+            }
+            if self.settings.scheduled_jobs.schedule(job).is_due() {
+                self.run_job(job);
+            }
+        }
+    }
+
+    /// Runs `job` now, recording the outcome in its schedule and the notification center
+    pub fn run_job(&mut self, job: ScheduledJob) {
+        debug!("running scheduled job {:?}", job);
+        let started = Local::now();
+        let result = match job {
+            ScheduledJob::ExportBattles => self.export_new_battles(),
+            ScheduledJob::BackUpTracker => self.export_tracker_backup(),
+            ScheduledJob::CleanCache => Ok(self.clean_caches()),
+        };
+
+        if job == ScheduledJob::ExportBattles && result.is_ok() {
+            self.settings.scheduled_jobs.exported_until = Some(started);
+        }
+
+        let (icon, status, action) = match result {
+            Ok((status, path)) => (icons::CHECK_CIRCLE, status, path.map(NotificationAction::ShowFile)),
+            Err(e) => (icons::WARNING, format!("Failed: {}", e), None),
+        };
+        self.notifications.push(format!("{} {}: {}", icon, job.name(), status), action);
+
+        let schedule = self.settings.scheduled_jobs.schedule_mut(job);
+        schedule.last_run = Some(Local::now());
+        schedule.last_status = Some(status);
+    }
+
+    /// Writes the battles played since the last successful export. Returns a summary and the file written, if any.
+    fn export_new_battles(&self) -> Result<(String, Option<PathBuf>), ToolkitError> {
+        let jobs = &self.settings.scheduled_jobs;
+        let export_dir = jobs.export_dir()?;
+        let schedule = &jobs.export_battles;
+        // Settings from before the watermark was kept only have the last run
+        let since = jobs
+            .exported_until
+            .or(schedule.last_run)
+            .unwrap_or_else(|| Local::now() - Duration::hours(schedule.interval_hours.max(1) as i64))
+            .naive_local();
+
+        let mut battles: Vec<(NaiveDateTime, String)> = Vec::new();
+        for (path, replay) in self.replay_files.iter().flatten() {
+            let replay = replay.read();
+            let meta = &replay.replay_file.meta;
+            let Some(played_at) = NaiveDateTime::parse_from_str(&meta.dateTime, REPLAY_DATE_FORMAT)
+                .ok()
+                .filter(|played_at| *played_at > since)
+            else {
+                continue;
+            };
+
+            let summary = ReplaySummary::new(&replay, &replay.resource_loader);
+            let file_name = path.file_name().map(|file_name| file_name.to_string_lossy().into_owned()).unwrap_or_default();
+            battles.push((
+                played_at,
+                format!(
+                    "{},{},{},{},{},{}",
+                    played_at.format("%Y-%m-%d %H:%M:%S"),
+                    csv_field(&summary.game_mode),
+                    csv_field(&summary.map_name),
+                    csv_field(&meta.playerName),
+                    csv_field(&summary.ship_name),
+                    csv_field(&file_name)
+                ),
+            ));
+        }
+
+        if battles.is_empty() {
+            return Ok(("No new battles".to_string(), None));
+        }
+
+        battles.sort_by_key(|(played_at, _)| *played_at);
+        let mut csv = String::from("Date,Game Type,Map,Player,Ship,Replay\n");
+        for (_, row) in &battles {
+            let _ = writeln!(csv, "{}", row);
+        }
+
+        let path = export_dir.join(format!("battles-{}.csv", Local::now().format("%Y%m%d-%H%M%S")));
+        std::fs::write(&path, csv)?;
+
+        Ok((format!("Exported {} battles", battles.len()), Some(path)))
+    }
+
+    fn export_tracker_backup(&self) -> Result<(String, Option<PathBuf>), ToolkitError> {
+        let export_dir = self.settings.scheduled_jobs.export_dir()?;
+        let path = export_dir.join(format!("tracker-{}.{}", Local::now().format("%Y%m%d-%H%M%S"), BACKUP_EXTENSION));
+        backup::export_backup(&self.settings, &path)?;
+
+        Ok(("Backed up settings and player tracker".to_string(), Some(path)))
+    }
+
+    fn clean_caches(&mut self) -> (String, Option<PathBuf>) {
+        let downloaded_replays_dir = storage::downloaded_replays_temp_dir();
+        let trash_dir = trash_dir();
+        let usage = || storage::disk_usage(&downloaded_replays_dir) + storage::disk_usage(&trash_dir);
+
+        let before = usage();
+        storage::evict_least_recently_used(&downloaded_replays_dir, self.settings.max_downloaded_replays_mb * 1_000_000, None);
+        let deleted = empty_old_trash();
+        self.undo_stack.forget_trashed(&deleted);
+        let freed = before.saturating_sub(usage());
+
+        (format!("Freed {}", humansize::format_size(freed, humansize::DECIMAL)), None)
+    }
+}
+
+impl ToolkitTabViewer<'_> {
+    pub fn build_scheduled_jobs_settings(&mut self, ui: &mut egui::Ui) {
+        ui.label("Jobs run while the app is open. A job that was due while the app was closed runs shortly after it starts.");
+        let jobs = &mut self.tab_state.settings.scheduled_jobs;
+        ui.horizontal(|ui| {
+            ui.label("Export Folder");
+            match jobs.export_dir.as_ref() {
+                Some(export_dir) => ui.label(export_dir.display().to_string()),
+                None => ui.weak("Not chosen"),
+            };
+            if ui.button(format!("{} Choose...", icons::FOLDER_OPEN)).clicked() {
+                if let Some(folder) = rfd::FileDialog::new().pick_folder() {
+                    jobs.export_dir = Some(folder);
+                }
+            }
+        });
+
+        let mut run_now = None;
+        egui::Grid::new("scheduled_jobs_grid").num_columns(4).striped(true).show(ui, |ui| {
+            for job in ScheduledJob::ALL {
+                let schedule = jobs.schedule_mut(job);
+                ui.checkbox(&mut schedule.enabled, job.name()).on_hover_text(job.description());
+                ui.horizontal(|ui| {
+                    ui.label("Every");
+                    ui.add(egui::DragValue::new(&mut schedule.interval_hours).range(1..=24 * 30).suffix(" h"));
+                });
+                match (schedule.last_run, schedule.last_status.as_ref()) {
+                    (Some(last_run), Some(status)) => ui.label(format!("{}: {}", last_run.format("%Y-%m-%d %H:%M"), status)),
+                    _ => ui.weak("Never run"),
+                };
+                if ui.button(format!("{} Run Now", icons::PLAY)).clicked() {
+                    run_now = Some(job);
+                }
+                ui.end_row();
+            }
+        });
+
+        if let Some(job) = run_now {
+            self.tab_state.run_job(job);
+        }
+    }
+}
@@ -0,0 +1,276 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Write,
+    sync::Arc,
+};
+
+use itertools::Itertools;
+use wowsunpack::game_params::{
+    provider::GameMetadataProvider,
+    types::{GameParamProvider, Param},
+};
+
+use crate::{
+    app::{TimedMessage, ToolkitTabViewer},
+    icons,
+    player_tracker::PlayerTracker,
+    replay_parser::{Replay, DAMAGE_INDEX, XP_INDEX},
+    task,
+};
+
+/// Game type of clan battle replays
+pub const CLAN_BATTLE_GAME_TYPE: &str = "ClanBattle";
+
+/// How many entries each section of the report lists
+const TOP_N: usize = 10;
+
+#[derive(Default)]
+pub struct ScoutedPlayer {
+    pub name: String,
+    pub battles: usize,
+    pub total_damage: i64,
+    pub total_base_xp: i64,
+    pub ships: BTreeMap<String, usize>,
+    /// Times the player tracker has seen this player in any game mode
+    pub encounters: usize,
+    pub notes: String,
+}
+
+impl ScoutedPlayer {
+    fn average_damage(&self) -> i64 {
+        self.total_damage / self.battles.max(1) as i64
+    }
+
+    fn average_base_xp(&self) -> i64 {
+        self.total_base_xp / self.battles.max(1) as i64
+    }
+}
+
+/// What a clan tends to do in clan battles, aggregated from the user's replays
+#[derive(Default)]
+pub struct ScoutingReport {
+    pub clan_tag: String,
+    pub battles: usize,
+    pub maps: HashMap<String, usize>,
+    pub ships: HashMap<String, usize>,
+    pub lineups: HashMap<Vec<String>, usize>,
+    /// Ship name -> upgrade loadout -> times seen
+    pub builds: BTreeMap<String, HashMap<Vec<String>, usize>>,
+    pub players: HashMap<i64, ScoutedPlayer>,
+}
+
+fn ship_name(metadata: &GameMetadataProvider, param: &Param) -> String {
+    metadata
+        .localized_name_from_param(param)
+        .map(ToString::to_string)
+        .unwrap_or_else(|| param.index().to_string())
+}
+
+pub(crate) fn results_value(info: Option<&serde_json::Value>, index: usize) -> Option<i64> {
+    info.and_then(|info| info.as_array())
+        .and_then(|info| info.get(index))
+        .and_then(|value| value.as_i64())
+}
+
+impl ScoutingReport {
+    pub fn new(clan_tag: String) -> Self {
+        Self { clan_tag, ..Default::default() }
+    }
+
+    /// Adds a parsed replay to the report if the target clan played in it
+    pub fn add_battle(&mut self, replay: &Replay) {
+        let Some(report) = replay.battle_report.as_ref() else {
+            return;
+        };
+        let metadata: &GameMetadataProvider = &replay.resource_loader;
+
+        let clan_entities: Vec<_> = report
+            .player_entities()
+            .iter()
+            .filter(|entity| entity.player().map(|player| player.clan().eq_ignore_ascii_case(&self.clan_tag)).unwrap_or(false))
+            .collect();
+        if clan_entities.is_empty() {
+            return;
+        }
+
+        self.battles += 1;
+        *self.maps.entry(report.map_name().to_string()).or_default() += 1;
+
+        let mut lineup = Vec::with_capacity(clan_entities.len());
+        for entity in clan_entities {
+            let Some(player) = entity.player() else {
+                continue;
+            };
+            let ship = ship_name(metadata, player.vehicle());
+            lineup.push(ship.clone());
+            *self.ships.entry(ship.clone()).or_default() += 1;
+
+            let upgrades: Vec<String> = entity
+                .props()
+                .ship_config()
+                .modernization()
+                .iter()
+                .filter_map(|id| metadata.game_param_by_id(*id))
+                .map(|param| ship_name(metadata, &param))
+                .sorted()
+                .collect();
+            if !upgrades.is_empty() {
+                *self.builds.entry(ship.clone()).or_default().entry(upgrades).or_default() += 1;
+            }
+
+            let scouted = self.players.entry(player.db_id()).or_default();
+            scouted.name = player.name().to_string();
+            scouted.battles += 1;
+            scouted.total_damage += results_value(entity.results_info(), DAMAGE_INDEX).unwrap_or_default();
+            scouted.total_base_xp += results_value(entity.results_info(), XP_INDEX).unwrap_or_default();
+            *scouted.ships.entry(ship).or_default() += 1;
+        }
+
+        lineup.sort();
+        *self.lineups.entry(lineup).or_default() += 1;
+    }
+
+    /// Fills in what the player tracker knows about each scouted player
+    pub fn add_tracker_info(&mut self, tracker: &PlayerTracker) {
+        for (db_id, scouted) in &mut self.players {
+            if let Some(tracked) = tracker.tracked_player(*db_id) {
+                scouted.encounters = tracked.encounters();
+                scouted.notes = tracked.notes().to_string();
+            }
+        }
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut md = String::new();
+        let _ = writeln!(md, "# Scouting Report: [{}]\n", self.clan_tag);
+        let _ = writeln!(md, "Based on {} clan battles.\n", self.battles);
+
+        let _ = writeln!(md, "## Maps\n");
+        for (map, count) in self.maps.iter().sorted_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0))).take(TOP_N) {
+            let _ = writeln!(md, "- {}: {}", map, count);
+        }
+
+        let _ = writeln!(md, "\n## Lineups\n");
+        for (lineup, count) in self.lineups.iter().sorted_by(|a, b| b.1.cmp(a.1)).take(TOP_N) {
+            let _ = writeln!(md, "- {}x: {}", count, lineup.join(", "));
+        }
+
+        let _ = writeln!(md, "\n## Ships\n");
+        for (ship, count) in self.ships.iter().sorted_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0))).take(TOP_N) {
+            let _ = writeln!(md, "- {}: {}", ship, count);
+        }
+
+        let _ = writeln!(md, "\n## Common Builds\n");
+        for (ship, builds) in &self.builds {
+            if let Some((upgrades, count)) = builds.iter().max_by_key(|(_, count)| **count) {
+                let _ = writeln!(md, "- **{}** ({}x): {}", ship, count, upgrades.join(", "));
+            }
+        }
+
+        let _ = writeln!(md, "\n## Players\n");
+        let _ = writeln!(md, "| Player | Battles | Avg. Damage | Avg. Base XP | Ships | Times Seen | Notes |");
+        let _ = writeln!(md, "|---|---|---|---|---|---|---|");
+        for player in self.players.values().sorted_by(|a, b| b.average_damage().cmp(&a.average_damage())) {
+            let ships = player.ships.iter().sorted_by(|a, b| b.1.cmp(a.1)).take(3).map(|(ship, _)| ship).join(", ");
+            let notes = player.notes.replace('|', "/").replace('\n', " ");
+            let _ = writeln!(
+                md,
+                "| {} | {} | {} | {} | {} | {} | {} |",
+                player.name,
+                player.battles,
+                player.average_damage(),
+                player.average_base_xp(),
+                ships,
+                player.encounters,
+                notes
+            );
+        }
+
+        md
+    }
+}
+
+/// State of the scouting report window
+#[derive(Default)]
+pub struct ScoutingWindow {
+    pub clan_tag: String,
+    pub markdown: Option<String>,
+}
+
+impl ToolkitTabViewer<'_> {
+    pub fn build_scouting_window(&mut self, ctx: &egui::Context) {
+        let Some(window) = self.tab_state.scouting_window.as_mut() else {
+            return;
+        };
+
+        let mut is_open = true;
+        let mut generate = false;
+        egui::Window::new(format!("{} Clan Scouting Report", icons::BINOCULARS))
+            .open(&mut is_open)
+            .default_size((600.0, 500.0))
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    let label = ui.label("Clan Tag");
+                    ui.add(egui::TextEdit::singleline(&mut window.clan_tag).desired_width(80.0)).labelled_by(label.id);
+                    generate = ui
+                        .add_enabled(!window.clan_tag.trim().is_empty(), egui::Button::new("Build Report"))
+                        .on_hover_text("Uses the clan battle replays in your replays folder")
+                        .clicked();
+                });
+
+                let Some(markdown) = window.markdown.as_mut() else {
+                    return;
+                };
+
+                ui.horizontal(|ui| {
+                    if ui.button(format!("{} Copy Markdown", icons::COPY)).clicked() {
+                        ui.output_mut(|output| output.copied_text = markdown.clone());
+                    }
+                    if ui.button(format!("{} Save Markdown...", icons::FLOPPY_DISK)).clicked() {
+                        let file_name = format!("{} scouting report.md", window.clan_tag.trim());
+                        if let Some(path) = rfd::FileDialog::new().add_filter("Markdown", &["md"]).set_file_name(file_name).save_file() {
+                            let result = std::fs::write(&path, markdown.as_bytes());
+                            *self.tab_state.timed_message.write() = Some(TimedMessage::file_exported(path, result));
+                        }
+                    }
+                });
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    ui.add(egui::TextEdit::multiline(markdown).code_editor().desired_width(f32::INFINITY));
+                });
+            });
+
+        if generate {
+            let clan_tag = window.clan_tag.trim().to_string();
+            let replays: Vec<_> = self
+                .tab_state
+                .replay_files
+                .as_ref()
+                .map(|replays| {
+                    replays
+                        .iter()
+                        .filter(|(_, replay)| replay.read().replay_file.meta.gameType == CLAN_BATTLE_GAME_TYPE)
+                        .map(|(path, _)| path.clone())
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            if replays.is_empty() {
+                *self.tab_state.timed_message.write() = Some(TimedMessage::new(format!("{} No clan battle replays found", icons::WARNING)));
+            } else if let Some(wows_data) = self.tab_state.world_of_warships_data.as_ref() {
+                crate::update_background_task!(
+                    self.tab_state.background_task,
+                    Some(task::start_building_scouting_report(
+                        clan_tag,
+                        replays,
+                        Arc::clone(wows_data),
+                        Arc::clone(&self.tab_state.settings.player_tracker)
+                    ))
+                );
+            }
+        }
+
+        if !is_open {
+            self.tab_state.scouting_window = None;
+        }
+    }
+}
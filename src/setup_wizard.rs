@@ -0,0 +1,208 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    app::{self, TabState},
+    icons, update_background_task,
+};
+
+/// Locations the game is commonly installed to by the Wargaming Game Center and Steam
+const COMMON_INSTALL_DIRS: [&str; 6] = [
+    "C:\\Games\\World_of_Warships",
+    "C:\\Games\\World_of_Warships_NA",
+    "C:\\Games\\World_of_Warships_EU",
+    "C:\\Games\\World_of_Warships_ASIA",
+    "C:\\Program Files (x86)\\Steam\\steamapps\\common\\World of Warships",
+    "C:\\Program Files\\Steam\\steamapps\\common\\World of Warships",
+];
+
+#[derive(Clone, Copy, PartialEq)]
+enum SetupStep {
+    GameDirectory,
+    Replays,
+    Tabs,
+    Privacy,
+}
+
+impl SetupStep {
+    fn next(self) -> Option<Self> {
+        match self {
+            SetupStep::GameDirectory => Some(SetupStep::Replays),
+            SetupStep::Replays => Some(SetupStep::Tabs),
+            SetupStep::Tabs => Some(SetupStep::Privacy),
+            SetupStep::Privacy => None,
+        }
+    }
+
+    fn previous(self) -> Option<Self> {
+        match self {
+            SetupStep::GameDirectory => None,
+            SetupStep::Replays => Some(SetupStep::GameDirectory),
+            SetupStep::Tabs => Some(SetupStep::Replays),
+            SetupStep::Privacy => Some(SetupStep::Tabs),
+        }
+    }
+}
+
+/// Guided setup shown on first launch, and afterwards from the File menu
+pub struct SetupWizard {
+    step: SetupStep,
+    detected_dirs: Vec<PathBuf>,
+}
+
+fn is_wows_dir(path: &Path) -> bool {
+    path.exists() && path.join("bin").exists()
+}
+
+impl Default for SetupWizard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SetupWizard {
+    pub fn new() -> Self {
+        SetupWizard {
+            step: SetupStep::GameDirectory,
+            detected_dirs: COMMON_INSTALL_DIRS.iter().map(PathBuf::from).filter(|path| is_wows_dir(path)).collect(),
+        }
+    }
+
+    fn load_game_dir(tab_state: &mut TabState, path: PathBuf) {
+        tab_state.settings.wows_dir = path.to_string_lossy().into_owned();
+        tab_state.prevent_changing_wows_dir();
+        update_background_task!(tab_state.background_task, Some(tab_state.load_game_data(path)));
+    }
+
+    fn build_game_directory_step(&mut self, ui: &mut egui::Ui, tab_state: &mut TabState) {
+        ui.label("Where is World of Warships installed?");
+
+        ui.add_enabled_ui(tab_state.can_change_wows_dir, |ui| {
+            for dir in &self.detected_dirs {
+                let is_selected = Path::new(&tab_state.settings.wows_dir) == dir.as_path();
+                if ui.radio(is_selected, dir.display().to_string()).clicked() && !is_selected {
+                    Self::load_game_dir(tab_state, dir.clone());
+                }
+            }
+            if self.detected_dirs.is_empty() {
+                ui.label(format!("{} Couldn't find the game in any of the usual places", icons::INFO));
+            }
+
+            if ui.button(format!("{} Choose Another Folder...", icons::FOLDER_OPEN)).clicked() {
+                if let Some(folder) = rfd::FileDialog::new().pick_folder() {
+                    Self::load_game_dir(tab_state, folder);
+                }
+            }
+        });
+
+        if tab_state.world_of_warships_data.is_some() {
+            ui.label(format!("{} Game data loaded from {}", icons::CHECK_CIRCLE, tab_state.settings.wows_dir));
+        } else if !tab_state.settings.wows_dir.is_empty() && !is_wows_dir(Path::new(&tab_state.settings.wows_dir)) {
+            ui.label(format!(
+                "{} {} doesn't look like a World of Warships folder",
+                icons::WARNING,
+                tab_state.settings.wows_dir
+            ));
+        }
+    }
+
+    fn build_replays_step(&mut self, ui: &mut egui::Ui, tab_state: &mut TabState) {
+        let Some(replays_dir) = tab_state.world_of_warships_data.as_ref().map(|wows_data| wows_data.read().replays_dir.clone()) else {
+            ui.label("Select your game folder first so the replays folder can be found.");
+            return;
+        };
+
+        ui.label(format!("Replays are read from {}", replays_dir.display()));
+        let replay_count = std::fs::read_dir(&replays_dir)
+            .map(|read_dir| {
+                read_dir
+                    .flatten()
+                    .filter(|entry| entry.path().extension().map(|ext| ext == "wowsreplay").unwrap_or(false))
+                    .count()
+            })
+            .unwrap_or(0);
+
+        if replay_count > 0 {
+            ui.label(format!("{} Found {} replays", icons::CHECK_CIRCLE, replay_count));
+        } else {
+            ui.label(format!(
+                "{} No replays were found. Make sure replay recording is enabled in the game's settings, then play a battle.",
+                icons::WARNING
+            ));
+        }
+
+        ui.horizontal(|ui| {
+            ui.add_enabled_ui(tab_state.can_change_wows_dir, |ui| {
+                if ui.button(format!("{} Use a Different Replays Folder...", icons::FOLDER_OPEN)).clicked() {
+                    if let Some(folder) = rfd::FileDialog::new().pick_folder() {
+                        tab_state.settings.custom_replays_dir = Some(folder);
+                        let wows_dir = PathBuf::from(&tab_state.settings.wows_dir);
+                        Self::load_game_dir(tab_state, wows_dir);
+                    }
+                }
+                if tab_state.settings.custom_replays_dir.is_some() && ui.button("Use Game Default").clicked() {
+                    tab_state.settings.custom_replays_dir = None;
+                    let wows_dir = PathBuf::from(&tab_state.settings.wows_dir);
+                    Self::load_game_dir(tab_state, wows_dir);
+                }
+            });
+        });
+    }
+
+    fn build_tabs_step(&mut self, ui: &mut egui::Ui, tab_state: &mut TabState) {
+        ui.label("Which tools would you like to see? Settings are always shown. You can change this later in Settings.");
+
+        // The dock is rebuilt from the hidden tabs once the wizard is finished
+        app::build_tab_checkboxes(ui, &mut tab_state.settings.hidden_tabs);
+    }
+
+    fn build_privacy_step(&mut self, ui: &mut egui::Ui, tab_state: &mut TabState) {
+        ui.label("WoWs Toolkit can use the network for the following. You can change these later in Settings.");
+        ui.checkbox(&mut tab_state.settings.check_for_updates, "Check GitHub for updates on startup");
+        ui.checkbox(&mut tab_state.settings.send_replay_data, "Send builds from Random Battles replays to ShipBuilds.com")
+            .on_hover_text("Each player's ship build and account ID are sent. Names and chat are not.");
+    }
+
+    /// Draws the wizard. Returns `true` once the user has finished or skipped setup.
+    pub fn show(&mut self, ctx: &egui::Context, tab_state: &mut TabState) -> bool {
+        let mut finished = false;
+
+        egui::Window::new(format!("{} Welcome to WoWs Toolkit", icons::GEAR_FINE))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                match self.step {
+                    SetupStep::GameDirectory => self.build_game_directory_step(ui, tab_state),
+                    SetupStep::Replays => self.build_replays_step(ui, tab_state),
+                    SetupStep::Tabs => self.build_tabs_step(ui, tab_state),
+                    SetupStep::Privacy => self.build_privacy_step(ui, tab_state),
+                }
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if let Some(previous) = self.step.previous() {
+                        if ui.button("Back").clicked() {
+                            self.step = previous;
+                        }
+                    }
+                    match self.step.next() {
+                        Some(next) => {
+                            if ui.button("Next").clicked() {
+                                self.step = next;
+                            }
+                        }
+                        None => {
+                            if ui.button(format!("{} Finish", icons::CHECK_CIRCLE)).clicked() {
+                                finished = true;
+                            }
+                        }
+                    }
+                    if ui.button("Skip Setup").clicked() {
+                        finished = true;
+                    }
+                });
+            });
+
+        finished
+    }
+}
@@ -0,0 +1,168 @@
+use std::{collections::HashMap, sync::Arc};
+
+use egui::{Image, ImageSource, PopupCloseBehavior, Vec2};
+use parking_lot::RwLock;
+use wowsunpack::{
+    data::ResourceLoader,
+    game_params::{
+        provider::GameMetadataProvider,
+        types::{GameParamProvider, Species},
+    },
+};
+
+use crate::{
+    collections::SHIP_CLASSES,
+    icons,
+    util::ship_tier,
+    wows_data::{ShipIcon, WorldOfWarshipsData},
+};
+
+const TIER_NUMERALS: [&str; 11] = ["I", "II", "III", "IV", "V", "VI", "VII", "VIII", "IX", "X", "★"];
+
+/// A ship which can be picked, with what's shown for it in the picker
+#[derive(Clone)]
+pub struct ShipEntry {
+    pub name: String,
+    pub tier: u32,
+    pub nation: String,
+    pub species: Species,
+    /// Localized class name, e.g. `Destroyer`
+    pub class: String,
+}
+
+impl ShipEntry {
+    fn matches(&self, search: &str) -> bool {
+        [self.name.as_str(), self.nation.as_str(), self.class.as_str(), tier_numeral(self.tier)]
+            .iter()
+            .any(|text| text.to_lowercase().contains(search))
+    }
+}
+
+fn tier_numeral(tier: u32) -> &'static str {
+    TIER_NUMERALS.get((tier as usize).wrapping_sub(1)).copied().unwrap_or("?")
+}
+
+/// Every playable ship in the game data, sorted by tier and name
+fn build_ship_catalog(metadata_provider: &GameMetadataProvider) -> Vec<ShipEntry> {
+    let mut ships: Vec<ShipEntry> = metadata_provider
+        .params()
+        .iter()
+        .filter_map(|param| {
+            let species = param.species().filter(|species| SHIP_CLASSES.contains(species))?;
+            let tier = ship_tier(param)?;
+            // Test and event ships have no translated name
+            let name = metadata_provider.localized_name_from_param(param)?.to_string();
+            let class_name: &'static str = species.clone().into();
+            let class = metadata_provider
+                .localized_name_from_id(&format!("IDS_{}", class_name.to_uppercase()))
+                .unwrap_or_else(|| class_name.to_string());
+
+            Some(ShipEntry {
+                name,
+                tier,
+                nation: param.nation().replace('_', " "),
+                species,
+                class,
+            })
+        })
+        .collect();
+    ships.sort_by(|a, b| a.tier.cmp(&b.tier).then_with(|| a.name.cmp(&b.name)));
+    ships.dedup_by(|a, b| a.name == b.name);
+
+    ships
+}
+
+/// Every playable ship in the game data, cached per loaded game data since building it walks every GameParam
+pub fn ship_catalog(ctx: &egui::Context, metadata_provider: &Arc<GameMetadataProvider>) -> Arc<Vec<ShipEntry>> {
+    let catalog_id = egui::Id::new(("ship_picker_catalog", Arc::as_ptr(metadata_provider) as usize));
+    ctx.data_mut(|data| {
+        data.get_temp_mut_or_insert_with(catalog_id, || Arc::new(build_ship_catalog(metadata_provider)))
+            .clone()
+    })
+}
+
+/// A ship name field with a searchable list of ships next to it, showing each ship's class icon,
+/// tier, nation and class. Free text can still be typed for partial matches.
+pub struct ShipPicker<'a> {
+    id_salt: &'a str,
+    ship_name: &'a mut String,
+    wows_data: Option<&'a Arc<RwLock<WorldOfWarshipsData>>>,
+}
+
+impl<'a> ShipPicker<'a> {
+    pub fn new(id_salt: &'a str, ship_name: &'a mut String, wows_data: Option<&'a Arc<RwLock<WorldOfWarshipsData>>>) -> Self {
+        Self { id_salt, ship_name, wows_data }
+    }
+
+    /// Returns the text field's response so it can be labelled
+    pub fn show(self, ui: &mut egui::Ui) -> egui::Response {
+        ui.horizontal(|ui| {
+            let text_response = ui.text_edit_singleline(&mut *self.ship_name);
+
+            let wows_data = self.wows_data.map(|wows_data| wows_data.read());
+            let metadata_provider = wows_data.as_ref().and_then(|wows_data| wows_data.game_metadata.clone());
+            let button = ui
+                .add_enabled(metadata_provider.is_some(), egui::Button::new(icons::LIST_MAGNIFYING_GLASS))
+                .on_hover_text("Pick a Ship");
+            let popup_id = ui.make_persistent_id((self.id_salt, "ship_picker_popup"));
+            if button.clicked() {
+                ui.memory_mut(|memory| memory.toggle_popup(popup_id));
+            }
+
+            let (Some(wows_data), Some(metadata_provider)) = (wows_data.as_ref(), metadata_provider) else {
+                return text_response;
+            };
+
+            egui::popup_below_widget(ui, popup_id, &button, PopupCloseBehavior::CloseOnClickOutside, |ui| {
+                ui.set_min_width(320.0);
+                let catalog = ship_catalog(ui.ctx(), &metadata_provider);
+
+                let search_id = popup_id.with("search");
+                let mut search: String = ui.ctx().data_mut(|data| data.get_temp(search_id).unwrap_or_default());
+                let search_response = ui.add(egui::TextEdit::singleline(&mut search).hint_text("Search by name, tier, nation or class"));
+                search_response.request_focus();
+                let search_lower = search.trim().to_lowercase();
+
+                egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                    for ship in catalog.iter().filter(|ship| ship.matches(&search_lower)) {
+                        let clicked = ui
+                            .horizontal(|ui| {
+                                ship_class_icon(ui, &wows_data.ship_icons, &ship.species);
+                                ui.add_sized([28.0, 18.0], egui::Label::new(tier_numeral(ship.tier)));
+                                let clicked = ui.selectable_label(*self.ship_name == ship.name, ship.name.as_str()).clicked();
+                                ui.weak(format!("{} {}", ship.nation, ship.class));
+                                clicked
+                            })
+                            .inner;
+                        if clicked {
+                            *self.ship_name = ship.name.clone();
+                            search.clear();
+                            ui.memory_mut(|memory| memory.close_popup());
+                        }
+                    }
+                });
+
+                ui.ctx().data_mut(|data| data.insert_temp(search_id, search));
+            });
+
+            text_response
+        })
+        .inner
+    }
+}
+
+fn ship_class_icon(ui: &mut egui::Ui, ship_icons: &HashMap<Species, Arc<ShipIcon>>, species: &Species) {
+    let Some(icon) = ship_icons.get(species) else {
+        ui.add_space(18.0);
+        return;
+    };
+
+    let image = Image::new(ImageSource::Bytes {
+        uri: icon.path.clone().into(),
+        bytes: icon.data.clone().into(),
+    })
+    .tint(ui.visuals().text_color())
+    .fit_to_exact_size((18.0, 18.0).into())
+    .rotate(90.0_f32.to_radians(), Vec2::splat(0.5));
+    ui.add(image);
+}
@@ -0,0 +1,181 @@
+use std::{
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use egui_extras::{Column, TableBuilder};
+use tracing::debug;
+
+use crate::{
+    app::ToolkitTabViewer, backup::backups_dir, crash_report::crash_log_path, game_params::game_params_bin_path, icons, tournament::submissions_path, undo::trash_dir,
+};
+
+/// Something the toolkit writes to disk which is safe to delete
+pub struct StorageEntry {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub path: PathBuf,
+    pub size: u64,
+}
+
+pub fn downloaded_replays_temp_dir() -> PathBuf {
+    std::env::temp_dir().join("wows_toolkit_replays")
+}
+
+/// Total size of a file, or of every file below a directory
+pub fn disk_usage(path: &Path) -> u64 {
+    let Ok(metadata) = std::fs::metadata(path) else {
+        return 0;
+    };
+
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+
+    std::fs::read_dir(path)
+        .map(|read_dir| read_dir.flatten().map(|entry| disk_usage(&entry.path())).sum())
+        .unwrap_or_default()
+}
+
+pub fn storage_entries() -> Vec<StorageEntry> {
+    [
+        (
+            "Game Params Cache",
+            "Rebuilt from the game files the next time game data is loaded",
+            game_params_bin_path(),
+        ),
+        (
+            "Downloaded Replays",
+            "Replays opened from a URL which weren't saved to the replays folder",
+            downloaded_replays_temp_dir(),
+        ),
+        ("Crash Log", "Left behind when the app crashes", crash_log_path()),
+        (
+            "Deleted Replays",
+            "Replays deleted from the listing. Clearing this means they can no longer be restored with undo.",
+            trash_dir(),
+        ),
+        (
+            "Settings Backups",
+            "Periodic backups of your settings and player tracker. Clearing this removes every backup.",
+            backups_dir(),
+        ),
+        (
+            "Tournament Submissions",
+            "Replays scored for the tournament. They're scored again the next time the tournament's replays are scored.",
+            submissions_path(),
+        ),
+    ]
+    .into_iter()
+    .map(|(name, description, path)| StorageEntry {
+        name,
+        description,
+        size: disk_usage(&path),
+        path,
+    })
+    .collect()
+}
+
+pub fn clear(path: &Path) -> std::io::Result<()> {
+    if path.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else if path.exists() {
+        std::fs::remove_file(path)
+    } else {
+        Ok(())
+    }
+}
+
+/// Deletes the least recently used files in `dir` until it's no larger than `max_size` bytes. `keep` is never
+/// deleted, so a file which was just added can be opened afterwards.
+pub fn evict_least_recently_used(dir: &Path, max_size: u64, keep: Option<&Path>) {
+    let Ok(read_dir) = std::fs::read_dir(dir) else {
+        return;
+    };
+
+    let mut files: Vec<(PathBuf, u64, SystemTime)> = read_dir
+        .flatten()
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            if !metadata.is_file() {
+                return None;
+            }
+            let last_used = metadata.accessed().or_else(|_| metadata.modified()).unwrap_or(SystemTime::UNIX_EPOCH);
+            Some((entry.path(), metadata.len(), last_used))
+        })
+        .collect();
+
+    let mut total: u64 = files.iter().map(|(_, size, _)| size).sum();
+    files.sort_by_key(|(_, _, last_used)| *last_used);
+
+    for (path, size, _) in files {
+        if total <= max_size {
+            break;
+        }
+        if Some(path.as_path()) == keep {
+            continue;
+        }
+
+        debug!("evicting {:?} from cache", path);
+        if std::fs::remove_file(&path).is_ok() {
+            total -= size;
+        }
+    }
+}
+
+impl ToolkitTabViewer<'_> {
+    pub fn build_storage_settings(&mut self, ui: &mut egui::Ui) {
+        let settings = &mut self.tab_state.settings;
+        ui.horizontal(|ui| {
+            ui.label("Keep Parsed Replays in Memory");
+            ui.add(egui::DragValue::new(&mut settings.max_parsed_replays).range(1..=500))
+                .on_hover_text("Older replays are re-parsed when opened again");
+        });
+        ui.horizontal(|ui| {
+            ui.label("Downloaded Replays Limit (MB)");
+            ui.add(egui::DragValue::new(&mut settings.max_downloaded_replays_mb).range(1..=10_000));
+        });
+
+        let entries = self.tab_state.storage_entries.get_or_insert_with(storage_entries);
+        let mut cleared = false;
+        TableBuilder::new(ui)
+            .vscroll(false)
+            .column(Column::auto())
+            .column(Column::auto())
+            .column(Column::auto())
+            .body(|mut body| {
+                for entry in entries.iter() {
+                    body.row(20.0, |mut row| {
+                        row.col(|ui| {
+                            ui.label(entry.name).on_hover_text(entry.description);
+                        });
+                        row.col(|ui| {
+                            ui.label(humansize::format_size(entry.size, humansize::DECIMAL))
+                                .on_hover_text(entry.path.to_string_lossy().into_owned());
+                        });
+                        row.col(|ui| {
+                            if ui.add_enabled(entry.size > 0, egui::Button::new(format!("{} Clear", icons::TRASH))).clicked() {
+                                if let Err(e) = clear(&entry.path) {
+                                    debug!("failed to clear {:?}: {:?}", entry.path, e);
+                                }
+                                cleared = true;
+                            }
+                        });
+                    });
+                }
+            });
+
+        ui.horizontal(|ui| {
+            if ui.button(format!("{} Refresh", icons::ARROW_CLOCKWISE)).clicked() || cleared {
+                self.tab_state.storage_entries = None;
+            }
+            if ui
+                .button(format!("{} Clean Up Replays...", icons::BROOM))
+                .on_hover_text("Move old replays out of the game's replays folder")
+                .clicked()
+            {
+                self.open_replay_cleanup();
+            }
+        });
+    }
+}
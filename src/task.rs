@@ -4,21 +4,22 @@ use std::{
     io::Cursor,
     path::{Path, PathBuf},
     sync::{
-        atomic::{AtomicBool, Ordering},
+        atomic::{AtomicBool, AtomicU64, Ordering},
         mpsc::{self, TryRecvError},
         Arc, Mutex,
     },
     thread,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
+use cpu_time::ThreadTime;
 use gettext::Catalog;
-use image::EncodableLayout;
 use language_tags::LanguageTag;
 use octocrab::models::repos::Asset;
 use parking_lot::RwLock;
+use percent_encoding::percent_decode_str;
 use reqwest::Url;
-use tokio::runtime::Runtime;
+use sha2::{Digest, Sha256};
 use tracing::{debug, error};
 use wows_replays::ReplayFile;
 use wowsunpack::{
@@ -32,22 +33,79 @@ use wowsunpack::{
 use zip::ZipArchive;
 
 use crate::{
-    build_tracker,
+    build_tracker, clan,
+    comparison::ShipComparison,
+    connectivity::Connectivity,
+    download::{self, Download, DownloadProgress},
     error::ToolkitError,
+    event_bus::{BattleEvent, EventBus},
+    expected_values::ExpectedValues,
     game_params::load_game_params,
+    icons,
+    integrity::IntegrityReport,
+    map_stats::MapStats,
+    matchmaking::MatchmakingAnalysis,
+    parse_diagnostics::ParseDiagnostics,
+    player_extraction::PlayerExtraction,
     player_tracker::{self, PlayerTracker},
+    region::Region,
     replay_parser::Replay,
+    scouting::ScoutingReport,
+    tournament::{Tournament, TournamentSubmission},
+    util,
     wows_data::{self, ShipIcon, WorldOfWarshipsData},
 };
 
-pub struct DownloadProgress {
-    downloaded: u64,
-    total: u64,
-}
+/// Tells tasks of the same kind apart
+static NEXT_TASK_ID: AtomicU64 = AtomicU64::new(0);
 
 pub struct BackgroundTask {
-    pub receiver: mpsc::Receiver<Result<BackgroundTaskCompletion, ToolkitError>>,
+    pub receiver: mpsc::Receiver<TaskOutcome>,
     pub kind: BackgroundTaskKind,
+    pub id: u64,
+}
+
+/// How long a background task's work took, measured on the thread doing it
+#[derive(Debug, Clone, Copy)]
+pub struct TaskTiming {
+    /// Time the thread spent on the CPU, if the platform can tell us
+    pub cpu_time: Option<Duration>,
+    pub wall_time: Duration,
+}
+
+/// What a background task's thread sends back once its work is done
+#[derive(Debug)]
+pub struct TaskOutcome {
+    pub result: Result<BackgroundTaskCompletion, ToolkitError>,
+    /// `None` if the task's thread went away without reporting back
+    pub timing: Option<TaskTiming>,
+}
+
+fn busy_indicator(ui: &mut egui::Ui, performance_mode: bool) {
+    if performance_mode {
+        // Spinners repaint every frame, so instead just check back on the task a few times a second
+        ui.label(icons::HOURGLASS);
+        ui.ctx().request_repaint_after(Duration::from_millis(250));
+    } else {
+        ui.spinner();
+    }
+}
+
+/// Status row for a download: a progress bar once data is arriving, otherwise a busy indicator
+fn download_status(ui: &mut egui::Ui, performance_mode: bool, rx: &mpsc::Receiver<DownloadProgress>, last_progress: &mut Option<DownloadProgress>, label: &str) {
+    while let Ok(progress) = rx.try_recv() {
+        *last_progress = Some(progress);
+    }
+
+    match last_progress {
+        Some(progress) => {
+            ui.add(progress.progress_bar(label));
+        }
+        None => {
+            busy_indicator(ui, performance_mode);
+            ui.label(format!("{}...", label));
+        }
+    }
 }
 
 pub enum BackgroundTaskKind {
@@ -58,43 +116,133 @@ pub enum BackgroundTaskKind {
         last_progress: Option<DownloadProgress>,
     },
     PopulatePlayerInspectorFromReplays,
+    ScoringTournament,
+    DownloadingReplay {
+        /// Kept so a failed download can be retried
+        url: String,
+        rx: mpsc::Receiver<DownloadProgress>,
+        last_progress: Option<DownloadProgress>,
+    },
+    SyncingClanRoster,
+    BuildingScoutingReport,
+    BuildingShipComparison,
+    VerifyingReplay,
+    ExtractingPlayers,
+    AnalyzingMatchmaking,
+    CollectingParseDiagnostics,
+    CollectingMapStats,
+}
+
+impl BackgroundTaskKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            BackgroundTaskKind::LoadingData => "Loading game data",
+            BackgroundTaskKind::LoadingReplay => "Loading replay",
+            BackgroundTaskKind::Updating { .. } => "Downloading update",
+            BackgroundTaskKind::PopulatePlayerInspectorFromReplays => "Populating player inspector",
+            BackgroundTaskKind::ScoringTournament => "Scoring tournament",
+            BackgroundTaskKind::DownloadingReplay { .. } => "Downloading replay",
+            BackgroundTaskKind::SyncingClanRoster => "Syncing clan roster",
+            BackgroundTaskKind::BuildingScoutingReport => "Building scouting report",
+            BackgroundTaskKind::BuildingShipComparison => "Comparing battles",
+            BackgroundTaskKind::VerifyingReplay => "Verifying replay",
+            BackgroundTaskKind::ExtractingPlayers => "Extracting players",
+            BackgroundTaskKind::AnalyzingMatchmaking => "Analyzing matchmaking",
+            BackgroundTaskKind::CollectingParseDiagnostics => "Collecting parse diagnostics",
+            BackgroundTaskKind::CollectingMapStats => "Collecting map stats",
+        }
+    }
 }
 
 impl BackgroundTask {
-    pub fn build_description(&mut self, ui: &mut egui::Ui) -> Option<Result<BackgroundTaskCompletion, ToolkitError>> {
+    /// Runs `work` on its own thread, timing it there
+    pub fn spawn(kind: BackgroundTaskKind, work: impl FnOnce() -> Result<BackgroundTaskCompletion, ToolkitError> + Send + 'static) -> Self {
+        let (tx, rx) = mpsc::channel();
+        let _join_handle = std::thread::spawn(move || {
+            let cpu_started = ThreadTime::try_now().ok();
+            let started = Instant::now();
+            let result = work();
+            let timing = TaskTiming {
+                cpu_time: cpu_started.and_then(|cpu_started| cpu_started.try_elapsed().ok()),
+                wall_time: started.elapsed(),
+            };
+
+            let _ = tx.send(TaskOutcome { result, timing: Some(timing) });
+        });
+
+        BackgroundTask {
+            receiver: rx,
+            kind,
+            id: NEXT_TASK_ID.fetch_add(1, Ordering::Relaxed),
+        }
+    }
+
+    pub fn build_description(&mut self, ui: &mut egui::Ui, performance_mode: bool) -> Option<TaskOutcome> {
         match self.receiver.try_recv() {
-            Ok(result) => Some(result),
+            Ok(outcome) => Some(outcome),
             Err(TryRecvError::Empty) => {
                 match &mut self.kind {
                     BackgroundTaskKind::LoadingData => {
-                        ui.spinner();
+                        busy_indicator(ui, performance_mode);
                         ui.label("Loading game data...");
                     }
                     BackgroundTaskKind::LoadingReplay => {
-                        ui.spinner();
+                        busy_indicator(ui, performance_mode);
                         ui.label("Loading replay...");
                     }
                     BackgroundTaskKind::Updating { rx, last_progress } => {
-                        match rx.try_recv() {
-                            Ok(progress) => {
-                                *last_progress = Some(progress);
-                            }
-                            Err(TryRecvError::Empty) => {}
-                            Err(TryRecvError::Disconnected) => {}
-                        }
-
-                        if let Some(progress) = last_progress {
-                            ui.add(egui::ProgressBar::new(progress.downloaded as f32 / progress.total as f32).text("Downloading Update"));
-                        }
+                        download_status(ui, performance_mode, rx, last_progress, "Downloading Update");
                     }
                     BackgroundTaskKind::PopulatePlayerInspectorFromReplays => {
-                        ui.spinner();
+                        busy_indicator(ui, performance_mode);
                         ui.label("Populating player inspector from historical replays...");
                     }
+                    BackgroundTaskKind::ScoringTournament => {
+                        busy_indicator(ui, performance_mode);
+                        ui.label("Scoring tournament replays...");
+                    }
+                    BackgroundTaskKind::DownloadingReplay { rx, last_progress, .. } => {
+                        download_status(ui, performance_mode, rx, last_progress, "Downloading Replay");
+                    }
+                    BackgroundTaskKind::SyncingClanRoster => {
+                        busy_indicator(ui, performance_mode);
+                        ui.label("Syncing clan roster...");
+                    }
+                    BackgroundTaskKind::BuildingScoutingReport => {
+                        busy_indicator(ui, performance_mode);
+                        ui.label("Building scouting report...");
+                    }
+                    BackgroundTaskKind::BuildingShipComparison => {
+                        busy_indicator(ui, performance_mode);
+                        ui.label("Comparing battles...");
+                    }
+                    BackgroundTaskKind::VerifyingReplay => {
+                        busy_indicator(ui, performance_mode);
+                        ui.label("Verifying replay...");
+                    }
+                    BackgroundTaskKind::ExtractingPlayers => {
+                        busy_indicator(ui, performance_mode);
+                        ui.label("Extracting players from replays...");
+                    }
+                    BackgroundTaskKind::AnalyzingMatchmaking => {
+                        busy_indicator(ui, performance_mode);
+                        ui.label("Analyzing matchmaking...");
+                    }
+                    BackgroundTaskKind::CollectingParseDiagnostics => {
+                        busy_indicator(ui, performance_mode);
+                        ui.label("Collecting parse diagnostics...");
+                    }
+                    BackgroundTaskKind::CollectingMapStats => {
+                        busy_indicator(ui, performance_mode);
+                        ui.label("Collecting map stats...");
+                    }
                 }
                 None
             }
-            Err(TryRecvError::Disconnected) => Some(Err(ToolkitError::BackgroundTaskCompleted)),
+            Err(TryRecvError::Disconnected) => Some(TaskOutcome {
+                result: Err(ToolkitError::BackgroundTaskCompleted),
+                timing: None,
+            }),
         }
     }
 }
@@ -110,6 +258,16 @@ pub enum BackgroundTaskCompletion {
     },
     UpdateDownloaded(PathBuf),
     PopulatePlayerInspectorFromReplays,
+    TournamentScored,
+    ReplayDownloaded(PathBuf),
+    ClanRosterSynced(HashSet<i64>),
+    ScoutingReportBuilt(ScoutingReport),
+    ShipComparisonBuilt(ShipComparison),
+    ReplayVerified(IntegrityReport),
+    PlayersExtracted(PlayerExtraction),
+    MatchmakingAnalyzed(MatchmakingAnalysis),
+    ParseDiagnosticsCollected(ParseDiagnostics),
+    MapStatsCollected(MapStats),
 }
 
 impl std::fmt::Debug for BackgroundTaskCompletion {
@@ -124,6 +282,16 @@ impl std::fmt::Debug for BackgroundTaskCompletion {
             Self::ReplayLoaded { replay } => f.debug_struct("ReplayLoaded").field("replay", &"<...>").finish(),
             Self::UpdateDownloaded(arg0) => f.debug_tuple("UpdateDownloaded").field(arg0).finish(),
             Self::PopulatePlayerInspectorFromReplays => f.write_str("PopulatePlayerInspectorFromReplays"),
+            Self::TournamentScored => f.write_str("TournamentScored"),
+            Self::ReplayDownloaded(arg0) => f.debug_tuple("ReplayDownloaded").field(arg0).finish(),
+            Self::ClanRosterSynced(members) => f.debug_tuple("ClanRosterSynced").field(&members.len()).finish(),
+            Self::ScoutingReportBuilt(report) => f.debug_struct("ScoutingReportBuilt").field("battles", &report.battles).finish(),
+            Self::ShipComparisonBuilt(comparison) => f.debug_struct("ShipComparisonBuilt").field("battles", &comparison.battles.len()).finish(),
+            Self::ReplayVerified(report) => f.debug_tuple("ReplayVerified").field(&report.file_name).finish(),
+            Self::PlayersExtracted(extraction) => f.debug_struct("PlayersExtracted").field("players", &extraction.players.len()).finish(),
+            Self::MatchmakingAnalyzed(analysis) => f.debug_struct("MatchmakingAnalyzed").field("battles", &analysis.battles.len()).finish(),
+            Self::ParseDiagnosticsCollected(diagnostics) => f.debug_tuple("ParseDiagnosticsCollected").field(&diagnostics.file_name).finish(),
+            Self::MapStatsCollected(stats) => f.debug_struct("MapStatsCollected").field("battles", &stats.battles()).finish(),
         }
     }
 }
@@ -189,67 +357,66 @@ fn current_build_from_preferences(path: &Path) -> Option<String> {
     Some(version_str.to_string())
 }
 
-pub fn load_wows_files(wows_directory: PathBuf, locale: &str) -> Result<BackgroundTaskCompletion, crate::error::ToolkitError> {
-    let mut idx_files = Vec::new();
-    let bin_dir = wows_directory.join("bin");
-    if !wows_directory.exists() || !bin_dir.exists() {
-        debug!("WoWs or WoWs bin directory does not exist");
-        return Err(crate::error::ToolkitError::InvalidWowsDirectory(wows_directory.to_path_buf()));
-    }
+/// Build the game last ran, according to preferences.xml
+pub fn preferences_build(wows_directory: &Path) -> Option<usize> {
+    current_build_from_preferences(&wows_directory.join("preferences.xml")).and_then(|version| version.split(',').nth(3).and_then(|build| build.parse().ok()))
+}
 
-    let mut latest_build = None;
-    let mut replays_dir = wows_directory.join("replays");
-
-    // Check to see if we can get a build from the preferences file
-    let prefs_file = wows_directory.join("preferences.xml");
-    if prefs_file.exists() {
-        // Try getting the version string from the preferences file
-        if let Some(version_str) = current_build_from_preferences(&prefs_file) {
-            let parts: Vec<&str> = version_str.split(',').collect();
-            if let Some(build_num) = parts.get(3) {
-                latest_build = build_num.parse().ok();
-            }
+/// Build the game will run: the one in preferences.xml, otherwise the newest one installed
+pub fn installed_build(wows_directory: &Path) -> Option<usize> {
+    preferences_build(wows_directory).or_else(|| latest_installed_build(wows_directory))
+}
 
-            // We want to build the version string without the build component to get the replays dir
-            let friendly_build = parts[..=2].join(".");
-            let friendly_build_with_extra_component = friendly_build.clone() + ".0";
+/// Highest build number in the game's `bin` directory
+fn latest_installed_build(wows_directory: &Path) -> Option<usize> {
+    read_dir(wows_directory.join("bin"))
+        .ok()?
+        .flatten()
+        .filter(|file| file.file_type().map(|ty| ty.is_dir()).unwrap_or(false))
+        .filter_map(|file| file.file_name().to_str().and_then(|name| name.parse::<usize>().ok()))
+        .max()
+}
 
-            for temp_replays_dir in [replays_dir.join(friendly_build), replays_dir.join(friendly_build_with_extra_component)] {
-                debug!("Looking for build-specific replays dir at {:?}", temp_replays_dir);
-                if temp_replays_dir.exists() {
-                    replays_dir = temp_replays_dir;
-                    break;
-                }
-            }
-        }
+/// Directory the game saves replays to: `custom_replays_dir` if set, otherwise the build-specific directory for the
+/// build in preferences.xml if there is one, otherwise `replays`
+pub fn replays_dir(wows_directory: &Path, custom_replays_dir: Option<PathBuf>) -> PathBuf {
+    if let Some(custom_replays_dir) = custom_replays_dir {
+        return custom_replays_dir;
     }
 
-    if latest_build.is_none() {
-        for file in read_dir(wows_directory.join("bin"))? {
-            if file.is_err() {
-                continue;
-            }
+    let replays_dir = wows_directory.join("replays");
+    let Some(version_str) = current_build_from_preferences(&wows_directory.join("preferences.xml")) else {
+        return replays_dir;
+    };
 
-            let file = file.unwrap();
-            if let Ok(ty) = file.file_type() {
-                if ty.is_file() {
-                    continue;
-                }
+    // We want to build the version string without the build component to get the replays dir
+    let parts: Vec<&str> = version_str.split(',').collect();
+    let friendly_build = parts[..=2].join(".");
+    let friendly_build_with_extra_component = friendly_build.clone() + ".0";
 
-                if let Some(build_num) = file.file_name().to_str().and_then(|name| name.parse::<usize>().ok()) {
-                    if latest_build.is_none() || latest_build.map(|number| number < build_num).unwrap_or(false) {
-                        latest_build = Some(build_num)
-                    }
-                }
-            }
+    for temp_replays_dir in [replays_dir.join(friendly_build), replays_dir.join(friendly_build_with_extra_component)] {
+        debug!("Looking for build-specific replays dir at {:?}", temp_replays_dir);
+        if temp_replays_dir.exists() {
+            return temp_replays_dir;
         }
     }
 
-    if latest_build.is_none() {
+    replays_dir
+}
+
+pub fn load_wows_files(wows_directory: PathBuf, locale: &str, custom_replays_dir: Option<PathBuf>) -> Result<BackgroundTaskCompletion, crate::error::ToolkitError> {
+    let mut idx_files = Vec::new();
+    let bin_dir = wows_directory.join("bin");
+    if !wows_directory.exists() || !bin_dir.exists() {
+        debug!("WoWs or WoWs bin directory does not exist");
         return Err(crate::error::ToolkitError::InvalidWowsDirectory(wows_directory.to_path_buf()));
     }
 
-    let number = latest_build.unwrap();
+    let replays_dir = replays_dir(&wows_directory, custom_replays_dir);
+    let Some(number) = installed_build(&wows_directory) else {
+        return Err(crate::error::ToolkitError::InvalidWowsDirectory(wows_directory.to_path_buf()));
+    };
+
     for file in read_dir(wows_directory.join("bin").join(format!("{}", number)).join("idx"))? {
         let file = file.unwrap();
         if file.file_type().unwrap().is_file() {
@@ -269,11 +436,25 @@ pub fn load_wows_files(wows_directory: PathBuf, locale: &str) -> Result<Backgrou
     let file_tree = idx::build_file_tree(idx_files.as_slice());
     let files = file_tree.paths();
 
-    let language_tag: LanguageTag = locale.parse().unwrap();
-    let attempted_dirs = [locale, language_tag.primary_language(), "en"];
+    let texts_dir = wows_directory.join(format!("bin/{}/res/texts", number));
+    let mut available_locales: Vec<String> = read_dir(&texts_dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| entry.path().join("LC_MESSAGES/global.mo").exists())
+                .filter_map(|entry| entry.file_name().to_str().map(str::to_owned))
+                .collect()
+        })
+        .unwrap_or_default();
+    available_locales.sort();
+
+    // Game locales such as `zh_sg` are not valid language tags, so only fall back
+    // to the primary language if the locale can be parsed as one.
+    let language_tag: Option<LanguageTag> = locale.parse().ok();
+    let attempted_dirs = [Some(locale), language_tag.as_ref().map(|tag| tag.primary_language()), Some("en")];
     let mut found_catalog = None;
-    for dir in attempted_dirs {
-        let localization_path = wows_directory.join(format!("bin/{}/res/texts/{}/LC_MESSAGES/global.mo", number, dir));
+    for dir in attempted_dirs.into_iter().flatten() {
+        let localization_path = texts_dir.join(format!("{}/LC_MESSAGES/global.mo", dir));
         if !localization_path.exists() {
             continue;
         }
@@ -305,6 +486,7 @@ pub fn load_wows_files(wows_directory: PathBuf, locale: &str) -> Result<Backgrou
         game_version: number,
         ship_icons: icons,
         replays_dir: replays_dir.clone(),
+        available_locales,
     };
 
     debug!("Loading replays");
@@ -329,25 +511,21 @@ pub fn load_wows_files(wows_directory: PathBuf, locale: &str) -> Result<Backgrou
     })
 }
 
-async fn download_update(tx: mpsc::Sender<DownloadProgress>, file: Url) -> Result<PathBuf, ToolkitError> {
-    let mut body = reqwest::get(file).await?;
-
-    let total = body.content_length().expect("body has no content-length");
-    let mut downloaded = 0;
+fn download_update(tx: mpsc::Sender<DownloadProgress>, file: Download, file_name: String, checksum_file: Option<Download>) -> Result<PathBuf, ToolkitError> {
     let file_path = Path::new("wows_toolkit.tmp.exe");
 
-    // We're going to be blocking here on I/O but it shouldn't matter since this
-    // application doesn't really use async
-    let mut zip_data = Vec::new();
-
-    while let Some(chunk) = body.chunk().await? {
-        downloaded += chunk.len();
-        let _ = tx.send(DownloadProgress {
-            downloaded: downloaded as u64,
-            total,
-        });
-
-        zip_data.extend_from_slice(chunk.as_bytes());
+    let zip_path = file.progress(tx).to_file(&download::downloads_dir().join(&file_name))?;
+    let zip_data = std::fs::read(&zip_path)?;
+    let _ = std::fs::remove_file(&zip_path);
+
+    // Older releases don't publish checksums, so only verify when one is available
+    if let Some(checksum_file) = checksum_file {
+        let checksums = String::from_utf8_lossy(&checksum_file.to_bytes()?).into_owned();
+        let expected = expected_checksum(&checksums, &file_name).ok_or_else(|| ToolkitError::UpdateChecksumMissing(file_name.clone()))?;
+        let actual = data_encoding::HEXLOWER.encode(Sha256::digest(&zip_data).as_slice());
+        if actual != expected {
+            return Err(ToolkitError::UpdateChecksumMismatch { expected, actual });
+        }
     }
 
     let cursor = Cursor::new(zip_data.as_slice());
@@ -366,25 +544,34 @@ async fn download_update(tx: mpsc::Sender<DownloadProgress>, file: Url) -> Resul
     Ok(file_path.to_path_buf())
 }
 
-pub fn start_download_update_task(runtime: &Runtime, release: &Asset) -> BackgroundTask {
-    let (tx, rx) = mpsc::channel();
+/// Finds the hash for `file_name` in `sha256sum`-style output. A file containing only a hash is
+/// assumed to describe `file_name`.
+fn expected_checksum(checksums: &str, file_name: &str) -> Option<String> {
+    checksums.lines().find_map(|line| {
+        let mut parts = line.split_whitespace();
+        let hash = parts.next()?;
+        match parts.next() {
+            Some(name) if name.trim_start_matches('*') != file_name => None,
+            _ => Some(hash.to_ascii_lowercase()),
+        }
+    })
+}
 
+/// Downloads the release `asset`, verifying it against `checksum_asset` when the release provides one
+pub fn start_download_update_task(asset: &Asset, checksum_asset: Option<&Asset>) -> BackgroundTask {
     let (progress_tx, progress_rx) = mpsc::channel();
-    let url = release.browser_download_url.clone();
-
-    runtime.spawn(async move {
-        let result = download_update(progress_tx, url).await.map(|path| BackgroundTaskCompletion::UpdateDownloaded(path));
-
-        tx.send(result);
-    });
-
-    BackgroundTask {
-        receiver: rx,
-        kind: BackgroundTaskKind::Updating {
-            rx: progress_rx,
-            last_progress: None,
-        },
-    }
+    let file_name = asset.name.clone();
+    // The API endpoint serves the same file when GitHub's download CDN is having trouble
+    let download = Download::new(asset.browser_download_url.clone()).mirror(asset.url.clone());
+    let checksum_download = checksum_asset.map(|asset| Download::new(asset.browser_download_url.clone()).mirror(asset.url.clone()));
+
+    let kind = BackgroundTaskKind::Updating {
+        rx: progress_rx,
+        last_progress: None,
+    };
+    BackgroundTask::spawn(kind, move || {
+        download_update(progress_tx, download, file_name, checksum_download).map(BackgroundTaskCompletion::UpdateDownloaded)
+    })
 }
 
 fn parse_replay_data_in_background(
@@ -392,12 +579,14 @@ fn parse_replay_data_in_background(
     wows_data: &WorldOfWarshipsData,
     client: &reqwest::blocking::Client,
     should_send_replays: Arc<AtomicBool>,
+    connectivity: &Connectivity,
     player_tracker: Arc<RwLock<PlayerTracker>>,
+    event_bus: Option<&EventBus>,
 ) -> Result<(), ()> {
     // Files may be getting written to. If we fail to parse the replay,
     // let's try try to parse this at least 3 times.
     debug!("Sending replay data for: {:?}", path);
-    'main_loop: for _ in 0..3 {
+    for _ in 0..3 {
         match ReplayFile::from_file(path) {
             Ok(replay_file) => {
                 // We only send back random battles
@@ -411,7 +600,12 @@ fn parse_replay_data_in_background(
                     let mut replay = Replay::new(replay_file, Arc::clone(&metadata_provider));
                     match replay.parse(game_version.to_string().as_str()) {
                         Ok(report) => {
-                            if should_send_replays.load(Ordering::Relaxed) {
+                            // Builds which couldn't be sent are tried again next time, but the player tracker is still updated
+                            let mut builds_sent = true;
+                            if should_send_replays.load(Ordering::Relaxed) && connectivity.is_offline() {
+                                debug!("offline, not sending builds");
+                                builds_sent = false;
+                            } else if should_send_replays.load(Ordering::Relaxed) {
                                 // Send the replay builds to the remote server
                                 for player in report.player_entities() {
                                     #[cfg(not(feature = "shipbuilds_debugging"))]
@@ -432,19 +626,47 @@ fn parse_replay_data_in_background(
                                         .send();
                                     if let Err(e) = res {
                                         error!("error sending request: {:?}", e);
+                                        connectivity.record_error(&e);
                                         if e.is_connect() {
-                                            break 'main_loop;
+                                            builds_sent = false;
+                                            break;
                                         }
                                     }
                                 }
-                                debug!("Successfully sent all builds");
+                                if builds_sent {
+                                    debug!("Successfully sent all builds");
+                                }
+                            }
+
+                            if let Some(event_bus) = event_bus {
+                                event_bus.emit(BattleEvent::ReplayParsed {
+                                    replay_path: path.to_path_buf(),
+                                    arena_id: report.arena_id(),
+                                    version: report.version().to_path(),
+                                    game_type: report.game_type().to_string(),
+                                    game_mode: report.game_mode().to_string(),
+                                    map_name: report.map_name().to_string(),
+                                });
                             }
 
                             // Update the player tracker
                             replay.battle_report = Some(report);
-                            player_tracker.write().update_from_replay(&replay);
+                            let mut player_tracker = player_tracker.write();
+                            player_tracker.update_from_replay(&replay);
+
+                            if let Some(event_bus) = event_bus {
+                                for flagged_player in player_tracker.flagged_players_in_replay(&replay) {
+                                    event_bus.emit(BattleEvent::FlaggedPlayerSeen {
+                                        replay_path: path.to_path_buf(),
+                                        player_name: flagged_player.last_name().to_string(),
+                                        clan: flagged_player.clan().to_string(),
+                                        db_id: flagged_player.db_id(),
+                                        notes: flagged_player.notes().to_string(),
+                                    });
+                                }
+                            }
 
-                            return Ok(());
+                            return if builds_sent { Ok(()) } else { Err(()) };
                         }
                         Err(e) => {
                             error!("error parsing background replay: {:?}", e);
@@ -469,7 +691,9 @@ pub fn start_background_parsing_thread(
     sent_replays: Arc<RwLock<HashSet<String>>>,
     wows_data: Arc<RwLock<WorldOfWarshipsData>>,
     should_send_replays: Arc<AtomicBool>,
+    connectivity: Connectivity,
     player_tracker: Arc<RwLock<PlayerTracker>>,
+    event_bus: EventBus,
 ) {
     debug!("starting background parsing thread");
     let _join_handle = std::thread::spawn(move || {
@@ -513,8 +737,15 @@ pub fn start_background_parsing_thread(
                             let sent_replay = { sent_replays.read().contains(path_str.as_ref()) } || cfg!(feature = "shipbuilds_debugging");
 
                             if !sent_replay {
-                                if let Ok(_) = parse_replay_data_in_background(&path, &*wows_data, &client, Arc::clone(&should_send_replays), Arc::clone(&player_tracker))
-                                {
+                                if let Ok(_) = parse_replay_data_in_background(
+                                    &path,
+                                    &*wows_data,
+                                    &client,
+                                    Arc::clone(&should_send_replays),
+                                    &connectivity,
+                                    Arc::clone(&player_tracker),
+                                    None,
+                                ) {
                                     sent_replays.write().insert(path_str.into_owned());
                                 }
                             }
@@ -535,7 +766,15 @@ pub fn start_background_parsing_thread(
             if !sent_replay {
                 debug!("Attempting to send replay at {}", path_str);
                 let wows_data = wows_data.read();
-                if let Ok(_) = parse_replay_data_in_background(&path, &*wows_data, &client, Arc::clone(&should_send_replays), Arc::clone(&player_tracker)) {
+                if let Ok(_) = parse_replay_data_in_background(
+                    &path,
+                    &*wows_data,
+                    &client,
+                    Arc::clone(&should_send_replays),
+                    &connectivity,
+                    Arc::clone(&player_tracker),
+                    Some(&event_bus),
+                ) {
                     sent_replays.write().insert(path_str.into_owned());
                 }
             } else {
@@ -550,8 +789,7 @@ pub fn start_populating_player_inspector(
     wows_data: Arc<RwLock<WorldOfWarshipsData>>,
     player_tracker: Arc<RwLock<PlayerTracker>>,
 ) -> BackgroundTask {
-    let (tx, rx) = mpsc::channel();
-    std::thread::spawn(move || {
+    BackgroundTask::spawn(BackgroundTaskKind::PopulatePlayerInspectorFromReplays, move || {
         for path in replays {
             match ReplayFile::from_file(&path) {
                 Ok(replay_file) => {
@@ -576,11 +814,208 @@ pub fn start_populating_player_inspector(
             }
         }
 
-        let _ = tx.send(Ok(BackgroundTaskCompletion::PopulatePlayerInspectorFromReplays));
-    });
+        Ok(BackgroundTaskCompletion::PopulatePlayerInspectorFromReplays)
+    })
+}
+
+pub fn start_scoring_tournament(replays: Vec<PathBuf>, wows_data: Arc<RwLock<WorldOfWarshipsData>>, tournament: Arc<RwLock<Tournament>>) -> BackgroundTask {
+    BackgroundTask::spawn(BackgroundTaskKind::ScoringTournament, move || {
+        for path in replays {
+            let submission = match ReplayFile::from_file(&path) {
+                Ok(replay_file) => {
+                    let wows_data = wows_data.read();
+                    let (metadata_provider, game_version) = { (wows_data.game_metadata.clone(), wows_data.game_version) };
+                    if let Some(metadata_provider) = metadata_provider {
+                        let mut replay = Replay::new(replay_file, Arc::clone(&metadata_provider));
+                        match replay.parse(game_version.to_string().as_str()) {
+                            Ok(report) => {
+                                replay.battle_report = Some(report);
+                                TournamentSubmission::from_replay(path.clone(), &replay)
+                                    .unwrap_or_else(|| TournamentSubmission::failed(path, "replay has no recording player".to_string()))
+                            }
+                            Err(e) => TournamentSubmission::failed(path, e.to_string()),
+                        }
+                    } else {
+                        TournamentSubmission::failed(path, "game data is not loaded".to_string())
+                    }
+                }
+                Err(e) => TournamentSubmission::failed(path, format!("{:?}", e)),
+            };
+
+            tournament.write().add_submission(submission);
+        }
+
+        tournament.read().save_submissions()?;
+        Ok(BackgroundTaskCompletion::TournamentScored)
+    })
+}
 
-    BackgroundTask {
-        receiver: rx,
-        kind: BackgroundTaskKind::PopulatePlayerInspectorFromReplays,
+/// Downloads a replay from a direct link into `destination_dir`
+pub fn start_downloading_replay(url: String, destination_dir: PathBuf) -> BackgroundTask {
+    let (progress_tx, progress_rx) = mpsc::channel();
+
+    let task_url = url.clone();
+    let kind = BackgroundTaskKind::DownloadingReplay {
+        url,
+        rx: progress_rx,
+        last_progress: None,
+    };
+    BackgroundTask::spawn(kind, move || {
+        download_replay(&task_url, &destination_dir, progress_tx).map(BackgroundTaskCompletion::ReplayDownloaded)
+    })
+}
+
+fn download_replay(url: &str, destination_dir: &Path, tx: mpsc::Sender<DownloadProgress>) -> Result<PathBuf, ToolkitError> {
+    let url = Url::parse(url.trim()).map_err(|_| ToolkitError::InvalidReplayUrl(url.to_string()))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(ToolkitError::InvalidReplayUrl(url.to_string()));
     }
+
+    // Hosting sites generally serve the replay under its original name, but fall back to
+    // something the replay listing will still pick up.
+    let file_name = url
+        .path_segments()
+        .and_then(|segments| segments.last())
+        .map(|name| util::sanitize_file_name(&percent_decode_str(name).decode_utf8_lossy()))
+        .filter(|name| name.ends_with(".wowsreplay"))
+        .unwrap_or_else(|| format!("{}.wowsreplay", chrono::Local::now().format("%Y%m%d_%H%M%S")));
+
+    Download::new(url).progress(tx).to_file(&unused_replay_path(destination_dir, &file_name))
+}
+
+/// `file_name` in `dir`, numbered like `name (1).wowsreplay` if a replay with that name is already there
+fn unused_replay_path(dir: &Path, file_name: &str) -> PathBuf {
+    let stem = file_name.trim_end_matches(".wowsreplay");
+    let mut path = dir.join(file_name);
+    let mut i = 1;
+    while path.exists() {
+        path = dir.join(format!("{} ({}).wowsreplay", stem, i));
+        i += 1;
+    }
+
+    path
+}
+
+pub fn start_syncing_clan_roster(region: Region, application_id: String, tag: String) -> BackgroundTask {
+    BackgroundTask::spawn(BackgroundTaskKind::SyncingClanRoster, move || {
+        clan::fetch_roster(region, &application_id, &tag).map(BackgroundTaskCompletion::ClanRosterSynced)
+    })
+}
+
+/// Opens and parses `replays` against the loaded game data, skipping any which fail after logging why. The
+/// battle report and length are set on each replay returned.
+fn parse_replays(replays: Vec<PathBuf>, wows_data: Arc<RwLock<WorldOfWarshipsData>>, purpose: &'static str) -> impl Iterator<Item = (PathBuf, Replay)> {
+    replays.into_iter().filter_map(move |path| {
+        let replay_file = match ReplayFile::from_file(&path) {
+            Ok(replay_file) => replay_file,
+            Err(e) => {
+                error!("error attempting to open replay for {}: {:?}", purpose, e);
+                return None;
+            }
+        };
+        let (metadata_provider, game_version) = {
+            let wows_data = wows_data.read();
+            (wows_data.game_metadata.clone()?, wows_data.game_version)
+        };
+
+        let mut replay = Replay::new(replay_file, metadata_provider);
+        match replay.parse_timed(game_version.to_string().as_str()) {
+            Ok((battle_report, battle_length)) => {
+                replay.battle_report = Some(battle_report);
+                replay.battle_length = Some(battle_length);
+                Some((path, replay))
+            }
+            Err(e) => {
+                error!("error attempting to parse replay for {}: {:?}", purpose, e);
+                None
+            }
+        }
+    })
+}
+
+/// Parses the clan battle `replays` and aggregates what `clan_tag` played in them
+pub fn start_building_scouting_report(
+    clan_tag: String,
+    replays: Vec<PathBuf>,
+    wows_data: Arc<RwLock<WorldOfWarshipsData>>,
+    player_tracker: Arc<RwLock<PlayerTracker>>,
+) -> BackgroundTask {
+    BackgroundTask::spawn(BackgroundTaskKind::BuildingScoutingReport, move || {
+        let mut report = ScoutingReport::new(clan_tag);
+        for (_path, replay) in parse_replays(replays, wows_data, "scouting report") {
+            report.add_battle(&replay);
+        }
+
+        report.add_tracker_info(&player_tracker.read());
+        Ok(BackgroundTaskCompletion::ScoutingReportBuilt(report))
+    })
+}
+
+pub fn start_building_ship_comparison(
+    ship_name: String,
+    replays: Vec<PathBuf>,
+    wows_data: Arc<RwLock<WorldOfWarshipsData>>,
+    expected_values: Option<Arc<ExpectedValues>>,
+) -> BackgroundTask {
+    BackgroundTask::spawn(BackgroundTaskKind::BuildingShipComparison, move || {
+        let mut comparison = ShipComparison::new(ship_name, expected_values);
+        for (_path, replay) in parse_replays(replays, wows_data, "ship comparison") {
+            comparison.add_battle(&replay);
+        }
+
+        Ok(BackgroundTaskCompletion::ShipComparisonBuilt(comparison))
+    })
+}
+
+pub fn start_verifying_replay(path: PathBuf, metadata_provider: Arc<GameMetadataProvider>, game_version: usize) -> BackgroundTask {
+    BackgroundTask::spawn(BackgroundTaskKind::VerifyingReplay, move || {
+        IntegrityReport::verify(&path, metadata_provider, game_version).map(BackgroundTaskCompletion::ReplayVerified)
+    })
+}
+
+pub fn start_collecting_parse_diagnostics(path: PathBuf, metadata_provider: Arc<GameMetadataProvider>) -> BackgroundTask {
+    BackgroundTask::spawn(BackgroundTaskKind::CollectingParseDiagnostics, move || {
+        let diagnostics = ParseDiagnostics::collect(&path, metadata_provider);
+        Ok(BackgroundTaskCompletion::ParseDiagnosticsCollected(diagnostics))
+    })
+}
+
+pub fn start_extracting_players(folder: PathBuf, wows_data: Option<Arc<RwLock<WorldOfWarshipsData>>>) -> BackgroundTask {
+    BackgroundTask::spawn(BackgroundTaskKind::ExtractingPlayers, move || {
+        let metadata_provider = wows_data.and_then(|wows_data| wows_data.read().game_metadata.clone());
+        let mut extraction = PlayerExtraction::new(folder.clone());
+        for path in replay_filepaths(&folder).unwrap_or_default() {
+            match ReplayFile::from_file(&path) {
+                Ok(replay_file) => extraction.add_replay(&replay_file, metadata_provider.as_deref()),
+                Err(e) => {
+                    error!("error attempting to open replay for player extraction: {:?}", e);
+                    extraction.skipped += 1;
+                }
+            }
+        }
+
+        Ok(BackgroundTaskCompletion::PlayersExtracted(extraction))
+    })
+}
+
+pub fn start_analyzing_matchmaking(replays: Vec<PathBuf>, wows_data: Arc<RwLock<WorldOfWarshipsData>>) -> BackgroundTask {
+    BackgroundTask::spawn(BackgroundTaskKind::AnalyzingMatchmaking, move || {
+        let mut analysis = MatchmakingAnalysis::default();
+        for (_path, replay) in parse_replays(replays, wows_data, "matchmaking analysis") {
+            analysis.add_battle(&replay);
+        }
+
+        Ok(BackgroundTaskCompletion::MatchmakingAnalyzed(analysis))
+    })
+}
+
+pub fn start_collecting_map_stats(replays: Vec<PathBuf>, wows_data: Arc<RwLock<WorldOfWarshipsData>>) -> BackgroundTask {
+    BackgroundTask::spawn(BackgroundTaskKind::CollectingMapStats, move || {
+        let mut stats = MapStats::default();
+        for (_path, replay) in parse_replays(replays, wows_data, "map stats") {
+            stats.add_battle(&replay);
+        }
+
+        Ok(BackgroundTaskCompletion::MapStatsCollected(stats))
+    })
 }
@@ -0,0 +1,495 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use chrono::NaiveDateTime;
+use egui::{Color32, RichText};
+use egui_extras::{Column, TableBuilder};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use tracing::error;
+
+use crate::{
+    app::{TimedMessage, ToolkitTabViewer},
+    error::ToolkitError,
+    icons,
+    replay_parser::{Replay, XP_INDEX},
+    ship_picker::ShipPicker,
+    task,
+    util::{self, separate_number},
+};
+
+const WINDOW_FORMAT: &str = "%Y-%m-%d %H:%M";
+pub(crate) const REPLAY_DATE_FORMAT: &str = "%d.%m.%Y %H:%M:%S";
+const MAX_TIER: u32 = 11;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TournamentRules {
+    pub min_base_xp: i64,
+    /// Start of the window in which battles must be played, in [WINDOW_FORMAT]
+    pub window_start: String,
+    /// End of the window in which battles must be played, in [WINDOW_FORMAT]
+    pub window_end: String,
+    /// Substring of the ship name or GameParams index that must be used. Empty allows any ship.
+    pub required_ship: String,
+    pub min_tier: u32,
+    pub max_tier: u32,
+}
+
+impl Default for TournamentRules {
+    fn default() -> Self {
+        Self {
+            min_base_xp: 0,
+            window_start: Default::default(),
+            window_end: Default::default(),
+            required_ship: Default::default(),
+            min_tier: 1,
+            max_tier: MAX_TIER,
+        }
+    }
+}
+
+fn parse_window_bound(bound: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(bound.trim(), WINDOW_FORMAT).ok()
+}
+
+/// The facts about a submitted replay that are needed to validate it. Validation
+/// happens on the fly so that changing the rules doesn't require re-parsing replays.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct TournamentSubmission {
+    pub path: PathBuf,
+    pub player_name: String,
+    pub clan: String,
+    pub ship_index: String,
+    pub ship_name: String,
+    pub ship_tier: Option<u32>,
+    pub played_at: Option<NaiveDateTime>,
+    pub arena_id: i64,
+    pub base_xp: Option<i64>,
+    pub parse_error: Option<String>,
+}
+
+impl TournamentSubmission {
+    pub fn failed(path: PathBuf, error: String) -> Self {
+        Self {
+            path,
+            parse_error: Some(error),
+            ..Default::default()
+        }
+    }
+
+    pub fn from_replay(path: PathBuf, replay: &Replay) -> Option<Self> {
+        let report = replay.battle_report.as_ref()?;
+        let entity = report.self_entity();
+        let player = entity.player()?;
+        let ship = player.vehicle();
+
+        Some(Self {
+            path,
+            player_name: player.name().to_string(),
+            clan: player.clan().to_string(),
+            ship_index: ship.index().to_string(),
+            ship_name: replay
+                .resource_loader
+                .localized_name_from_param(ship)
+                .map(ToString::to_string)
+                .unwrap_or_else(|| ship.index().to_string()),
+            ship_tier: util::ship_tier(ship),
+            played_at: NaiveDateTime::parse_from_str(&replay.replay_file.meta.dateTime, REPLAY_DATE_FORMAT).ok(),
+            arena_id: report.arena_id(),
+            base_xp: entity
+                .results_info()
+                .and_then(|info| info.as_array().and_then(|info_array| info_array.get(XP_INDEX)).and_then(|xp| xp.as_i64())),
+            parse_error: None,
+        })
+    }
+
+    fn file_name(&self) -> String {
+        self.path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default()
+    }
+}
+
+pub struct LeaderboardEntry {
+    pub player_name: String,
+    pub clan: String,
+    pub battles: usize,
+    pub best_base_xp: i64,
+    pub total_base_xp: i64,
+    pub flagged: usize,
+}
+
+/// Where scored submissions are kept. They're stored apart from the settings so that they aren't copied into
+/// every settings backup.
+pub fn submissions_path() -> PathBuf {
+    let submissions_path = Path::new("tournament_submissions.json");
+    if let Some(storage_dir) = eframe::storage_dir(crate::APP_NAME) {
+        storage_dir.join(submissions_path)
+    } else {
+        submissions_path.to_path_buf()
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Tournament {
+    pub rules: TournamentRules,
+    /// Saved to [submissions_path]. Still read from the settings so that submissions from before they had their
+    /// own file can be moved there.
+    #[serde(skip_serializing)]
+    pub submissions: Vec<TournamentSubmission>,
+}
+
+impl Tournament {
+    /// Loads the submissions saved by [Tournament::save_submissions], moving any found in the settings to their
+    /// own file if it doesn't exist yet
+    pub fn load_submissions(&mut self) {
+        match std::fs::read(submissions_path()) {
+            Ok(data) => match serde_json::from_slice(&data) {
+                Ok(submissions) => self.submissions = submissions,
+                Err(e) => error!("failed to read tournament submissions: {:?}", e),
+            },
+            Err(_) if !self.submissions.is_empty() => {
+                if let Err(e) = self.save_submissions() {
+                    error!("failed to move tournament submissions out of the settings: {:?}", e);
+                }
+            }
+            Err(_) => {}
+        }
+    }
+
+    pub fn save_submissions(&self) -> Result<(), ToolkitError> {
+        let path = submissions_path();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let data = serde_json::to_vec(&self.submissions).map_err(std::io::Error::from)?;
+        std::fs::write(path, data)?;
+
+        Ok(())
+    }
+
+    /// Adds a submission, replacing any previous submission of the same file
+    pub fn add_submission(&mut self, submission: TournamentSubmission) {
+        self.submissions.retain(|existing| existing.path != submission.path);
+        self.submissions.push(submission);
+    }
+
+    /// Returns every reason this submission does not satisfy the tournament rules
+    pub fn anomalies(&self, submission: &TournamentSubmission) -> Vec<String> {
+        if let Some(error) = &submission.parse_error {
+            return vec![format!("Could not parse replay: {}", error)];
+        }
+
+        let rules = &self.rules;
+        let mut anomalies = Vec::new();
+
+        match submission.base_xp {
+            Some(base_xp) if base_xp < rules.min_base_xp => {
+                anomalies.push(format!("Base XP {} is below the minimum of {}", base_xp, rules.min_base_xp));
+            }
+            Some(_) => {}
+            None => anomalies.push("Replay has no battle results (player may have left before the battle ended)".to_string()),
+        }
+
+        if let Some(played_at) = submission.played_at {
+            if parse_window_bound(&rules.window_start).map(|start| played_at < start).unwrap_or(false) {
+                anomalies.push("Played before the tournament window opened".to_string());
+            }
+            if parse_window_bound(&rules.window_end).map(|end| played_at > end).unwrap_or(false) {
+                anomalies.push("Played after the tournament window closed".to_string());
+            }
+        } else {
+            anomalies.push("Replay has an invalid date".to_string());
+        }
+
+        let required_ship = rules.required_ship.trim().to_lowercase();
+        if !required_ship.is_empty() && !submission.ship_name.to_lowercase().contains(&required_ship) && !submission.ship_index.to_lowercase().contains(&required_ship) {
+            anomalies.push(format!("{} is not the required ship", submission.ship_name));
+        }
+
+        match submission.ship_tier {
+            Some(tier) if tier < rules.min_tier || tier > rules.max_tier => {
+                anomalies.push(format!("Tier {} is outside of the allowed tiers ({}-{})", tier, rules.min_tier, rules.max_tier));
+            }
+            Some(_) => {}
+            None => anomalies.push("Could not determine the ship's tier".to_string()),
+        }
+
+        let times_submitted = self
+            .submissions
+            .iter()
+            .filter(|other| other.parse_error.is_none() && other.arena_id == submission.arena_id && other.player_name == submission.player_name)
+            .count();
+        if times_submitted > 1 {
+            anomalies.push(format!("The same battle was submitted {} times", times_submitted));
+        }
+
+        anomalies
+    }
+
+    /// Scores players by the total base XP of their valid submissions
+    pub fn leaderboard(&self) -> Vec<LeaderboardEntry> {
+        let mut entries: HashMap<&str, LeaderboardEntry> = HashMap::new();
+        for submission in self.submissions.iter().filter(|submission| submission.parse_error.is_none()) {
+            let entry = entries.entry(submission.player_name.as_str()).or_insert_with(|| LeaderboardEntry {
+                player_name: submission.player_name.clone(),
+                clan: submission.clan.clone(),
+                battles: 0,
+                best_base_xp: 0,
+                total_base_xp: 0,
+                flagged: 0,
+            });
+
+            if !self.anomalies(submission).is_empty() {
+                entry.flagged += 1;
+                continue;
+            }
+
+            let base_xp = submission.base_xp.unwrap_or_default();
+            entry.battles += 1;
+            entry.best_base_xp = entry.best_base_xp.max(base_xp);
+            entry.total_base_xp += base_xp;
+        }
+
+        entries
+            .into_values()
+            .sorted_by(|a, b| b.total_base_xp.cmp(&a.total_base_xp).then_with(|| b.best_base_xp.cmp(&a.best_base_xp)))
+            .collect()
+    }
+}
+
+impl ToolkitTabViewer<'_> {
+    fn build_tournament_rules(&mut self, ui: &mut egui::Ui) {
+        let mut tournament = self.tab_state.settings.tournament.write();
+        let rules = &mut tournament.rules;
+        let wows_data = self.tab_state.world_of_warships_data.as_ref();
+
+        ui.label("Scoring Rules");
+        ui.group(|ui| {
+            egui::Grid::new("tournament_rules_grid").num_columns(2).show(ui, |ui| {
+                ui.label("Minimum Base XP");
+                ui.add(egui::DragValue::new(&mut rules.min_base_xp).range(0..=10_000).speed(10));
+                ui.end_row();
+
+                for (label, bound) in [("Window Start", &mut rules.window_start), ("Window End", &mut rules.window_end)] {
+                    let is_valid = bound.trim().is_empty() || parse_window_bound(bound).is_some();
+                    ui.label(label);
+                    ui.add(
+                        egui::TextEdit::singleline(bound)
+                            .hint_text("YYYY-MM-DD HH:MM")
+                            .text_color_opt((!is_valid).then_some(Color32::RED)),
+                    );
+                    ui.end_row();
+                }
+
+                let label = ui.label("Required Ship");
+                ShipPicker::new("tournament_required_ship", &mut rules.required_ship, wows_data)
+                    .show(ui)
+                    .labelled_by(label.id)
+                    .on_hover_text("Leave empty to allow any ship");
+                ui.end_row();
+
+                ui.label("Allowed Tiers");
+                ui.horizontal(|ui| {
+                    let max_tier = rules.max_tier;
+                    ui.add(egui::DragValue::new(&mut rules.min_tier).range(1..=max_tier));
+                    ui.label("to");
+                    let min_tier = rules.min_tier;
+                    ui.add(egui::DragValue::new(&mut rules.max_tier).range(min_tier..=MAX_TIER));
+                });
+                ui.end_row();
+            });
+        });
+    }
+
+    fn build_tournament_leaderboard(&self, ui: &mut egui::Ui, leaderboard: &[LeaderboardEntry]) {
+        let locale = self.tab_state.settings.locale.as_ref().map(|s| s.as_ref());
+        TableBuilder::new(ui)
+            .striped(true)
+            .resizable(true)
+            .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+            .column(Column::initial(50.0).clip(true))
+            .column(Column::initial(200.0).clip(true))
+            .column(Column::initial(100.0).clip(true))
+            .column(Column::initial(100.0).clip(true))
+            .column(Column::initial(100.0).clip(true))
+            .column(Column::remainder())
+            .max_scroll_height(200.0)
+            .min_scrolled_height(0.0)
+            .header(20.0, |mut header| {
+                for title in ["Rank", "Player Name", "Valid Battles", "Best Base XP", "Score", "Flagged"] {
+                    header.col(|ui| {
+                        ui.strong(title);
+                    });
+                }
+            })
+            .body(|mut body| {
+                for (rank, entry) in leaderboard.iter().enumerate() {
+                    body.row(20.0, |mut row| {
+                        row.col(|ui| {
+                            ui.label((rank + 1).to_string());
+                        });
+                        row.col(|ui| {
+                            if entry.clan.is_empty() {
+                                ui.label(&entry.player_name);
+                            } else {
+                                ui.label(format!("[{}] {}", entry.clan, entry.player_name));
+                            }
+                        });
+                        row.col(|ui| {
+                            ui.label(entry.battles.to_string());
+                        });
+                        row.col(|ui| {
+                            ui.label(separate_number(entry.best_base_xp, locale));
+                        });
+                        row.col(|ui| {
+                            ui.label(separate_number(entry.total_base_xp, locale));
+                        });
+                        row.col(|ui| {
+                            if entry.flagged > 0 {
+                                ui.label(RichText::new(format!("{} {}", icons::WARNING, entry.flagged)).color(Color32::LIGHT_RED));
+                            } else {
+                                ui.label("-");
+                            }
+                        });
+                    });
+                }
+            });
+    }
+
+    fn build_tournament_submissions(&self, ui: &mut egui::Ui) {
+        let locale = self.tab_state.settings.locale.as_ref().map(|s| s.as_ref());
+        let tournament = self.tab_state.settings.tournament.read();
+        TableBuilder::new(ui)
+            .striped(true)
+            .resizable(true)
+            .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+            .column(Column::initial(60.0).clip(true))
+            .column(Column::initial(200.0).clip(true))
+            .column(Column::initial(150.0).clip(true))
+            .column(Column::initial(130.0).clip(true))
+            .column(Column::initial(90.0).clip(true))
+            .column(Column::remainder().clip(true))
+            .min_scrolled_height(0.0)
+            .header(20.0, |mut header| {
+                for title in ["Status", "Player Name", "Ship", "Played At", "Base XP", "File"] {
+                    header.col(|ui| {
+                        ui.strong(title);
+                    });
+                }
+            })
+            .body(|mut body| {
+                for submission in &tournament.submissions {
+                    let anomalies = tournament.anomalies(submission);
+                    body.row(20.0, |mut row| {
+                        row.col(|ui| {
+                            if anomalies.is_empty() {
+                                ui.label(RichText::new(icons::CHECK_CIRCLE).color(Color32::LIGHT_GREEN))
+                                    .on_hover_text("Valid submission");
+                            } else {
+                                ui.label(RichText::new(icons::WARNING).color(Color32::LIGHT_RED)).on_hover_text(anomalies.join("\n"));
+                            }
+                        });
+                        row.col(|ui| {
+                            ui.label(&submission.player_name);
+                        });
+                        row.col(|ui| {
+                            if let Some(tier) = submission.ship_tier {
+                                ui.label(format!("{} (T{})", submission.ship_name, tier));
+                            } else {
+                                ui.label(&submission.ship_name);
+                            }
+                        });
+                        row.col(|ui| {
+                            if let Some(played_at) = submission.played_at {
+                                ui.label(played_at.format("%Y-%m-%d %H:%M").to_string());
+                            } else {
+                                ui.label("-");
+                            }
+                        });
+                        row.col(|ui| {
+                            if let Some(base_xp) = submission.base_xp {
+                                ui.label(separate_number(base_xp, locale));
+                            } else {
+                                ui.label("-");
+                            }
+                        });
+                        row.col(|ui| {
+                            ui.label(submission.file_name()).on_hover_text(submission.path.to_string_lossy().into_owned());
+                        });
+                    });
+                }
+            });
+    }
+
+    /// Builds the tournament tab
+    pub fn build_tournament_tab(&mut self, ui: &mut egui::Ui) {
+        ui.vertical(|ui| {
+            if self.tab_state.is_locked() {
+                self.build_unlock_prompt(ui);
+                ui.disable();
+            }
+            self.build_tournament_rules(ui);
+
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(
+                        self.tab_state.world_of_warships_data.is_some(),
+                        egui::Button::new(format!("{} Add Replays...", icons::FOLDER_OPEN)),
+                    )
+                    .clicked()
+                {
+                    if let Some(files) = rfd::FileDialog::new().add_filter("WoWs Replays", &["wowsreplay"]).pick_files() {
+                        if let Some(wows_data) = self.tab_state.world_of_warships_data.as_ref() {
+                            crate::update_background_task!(
+                                self.tab_state.background_task,
+                                Some(task::start_scoring_tournament(
+                                    files,
+                                    Arc::clone(wows_data),
+                                    Arc::clone(&self.tab_state.settings.tournament)
+                                ))
+                            );
+                        }
+                    }
+                }
+
+                if ui.button("Clear Submissions").clicked() {
+                    let mut tournament = self.tab_state.settings.tournament.write();
+                    tournament.submissions.clear();
+                    if let Err(e) = tournament.save_submissions() {
+                        *self.tab_state.timed_message.write() = Some(TimedMessage::new(format!("{} Failed to save submissions: {}", icons::WARNING, e)));
+                    }
+                }
+
+                if ui.button(format!("{} Copy Leaderboard", icons::COPY)).clicked() {
+                    let leaderboard = self.tab_state.settings.tournament.read().leaderboard();
+                    let text = leaderboard
+                        .iter()
+                        .enumerate()
+                        .map(|(rank, entry)| format!("{}. {} - {} ({} battles)", rank + 1, entry.player_name, entry.total_base_xp, entry.battles))
+                        .join("\n");
+                    ui.output_mut(|output| output.copied_text = text);
+                    *self.tab_state.timed_message.write() = Some(TimedMessage::new(format!("{} Leaderboard copied", icons::CHECK_CIRCLE)));
+                }
+            });
+
+            ui.add_space(10.0);
+
+            let leaderboard = self.tab_state.settings.tournament.read().leaderboard();
+            ui.heading("Leaderboard");
+            ui.push_id("tournament_leaderboard", |ui| {
+                self.build_tournament_leaderboard(ui, &leaderboard);
+            });
+
+            ui.add_space(10.0);
+
+            ui.heading("Submissions");
+            ui.push_id("tournament_submissions", |ui| {
+                self.build_tournament_submissions(ui);
+            });
+        });
+    }
+}
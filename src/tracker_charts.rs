@@ -0,0 +1,157 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use chrono::{Datelike, NaiveDate};
+use egui_plot::{Bar, BarChart, Legend, Line, Plot};
+use tracing::error;
+
+use crate::icons;
+
+/// How long to wait for a requested screenshot before giving up on the export
+const EXPORT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Activity in the week starting on the given Monday
+#[derive(Debug, Default, Clone, Copy)]
+pub struct WeeklyActivity {
+    pub battles: usize,
+    /// Tracked players seen across those battles
+    pub encounters: usize,
+}
+
+/// State of the player tracker's charts window
+#[derive(Debug, Default)]
+pub struct TrackerCharts {
+    pub open: bool,
+    /// Area of the screen to crop, where to save it, and when the export was started, until the requested
+    /// screenshot arrives
+    pending_export: Option<(egui::Rect, PathBuf, Instant)>,
+    export_message: Option<String>,
+}
+
+/// Monday of the week `date` falls in
+pub fn week_start(date: NaiveDate) -> NaiveDate {
+    date - chrono::Duration::days(date.weekday().num_days_from_monday() as i64)
+}
+
+pub(crate) fn date_to_x(date: NaiveDate) -> f64 {
+    date.num_days_from_ce() as f64
+}
+
+pub(crate) fn x_to_date(x: f64) -> Option<NaiveDate> {
+    NaiveDate::from_num_days_from_ce_opt(x.round() as i32)
+}
+
+fn save_png(image: &egui::ColorImage, path: &Path) -> Result<(), image::ImageError> {
+    let [width, height] = image.size;
+    let data: Vec<u8> = image.pixels.iter().flat_map(|pixel| pixel.to_array()).collect();
+    image::save_buffer(path, &data, width as u32, height as u32, image::ColorType::Rgba8)
+}
+
+impl TrackerCharts {
+    /// Saves the chart once the screenshot requested by the export button comes back
+    fn finish_export(&mut self, ctx: &egui::Context) {
+        let Some((_, _, started)) = self.pending_export.as_ref() else {
+            return;
+        };
+        if started.elapsed() > EXPORT_TIMEOUT {
+            self.pending_export = None;
+            self.export_message = Some(format!("{} Failed to save chart: no screenshot was taken", icons::WARNING));
+            return;
+        }
+        // Make sure there's a frame to notice the timeout on, even if nothing else is going on
+        ctx.request_repaint_after(EXPORT_TIMEOUT);
+
+        let screenshot = ctx.input(|input| {
+            input.raw.events.iter().find_map(|event| match event {
+                egui::Event::Screenshot { image, .. } => Some(image.clone()),
+                _ => None,
+            })
+        });
+        let (Some(screenshot), Some((rect, path, _))) = (screenshot, self.pending_export.as_ref()) else {
+            return;
+        };
+
+        let chart = screenshot.region(rect, Some(ctx.pixels_per_point()));
+        self.export_message = Some(match save_png(&chart, path) {
+            Ok(()) => format!("{} Saved to {}", icons::CHECK_CIRCLE, path.display()),
+            Err(e) => {
+                error!("failed to save chart: {:?}", e);
+                format!("{} Failed to save chart: {}", icons::WARNING, e)
+            }
+        });
+        self.pending_export = None;
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, activity: &BTreeMap<NaiveDate, WeeklyActivity>) {
+        self.finish_export(ctx);
+
+        let mut open = self.open;
+        egui::Window::new(format!("{} Player Tracker Charts", icons::CHART_BAR))
+            .open(&mut open)
+            .default_size([600.0, 350.0])
+            .show(ctx, |ui| {
+                if activity.is_empty() {
+                    ui.label("No encounters have been tracked yet.");
+                    return;
+                }
+
+                ui.horizontal(|ui| {
+                    ui.strong("Encounters per Week");
+                    let export_button = ui.add_enabled(self.pending_export.is_none(), egui::Button::new(format!("{} Export PNG", icons::IMAGE)));
+                    if export_button.clicked() {
+                        if let Some(path) = rfd::FileDialog::new()
+                            .add_filter("PNG", &["png"])
+                            .set_file_name("encounters per week.png")
+                            .save_file()
+                        {
+                            // The plot's area is filled in below, since it hasn't been laid out yet
+                            self.pending_export = Some((egui::Rect::NOTHING, path, Instant::now()));
+                            self.export_message = None;
+                        }
+                    }
+                    if let Some(message) = &self.export_message {
+                        ui.label(message.as_str());
+                    }
+                });
+
+                let battles: Vec<Bar> = activity
+                    .iter()
+                    .map(|(week, activity)| Bar::new(date_to_x(*week) + 3.5, activity.battles as f64).width(6.0))
+                    .collect();
+                let encounters: Vec<[f64; 2]> = activity.iter().map(|(week, activity)| [date_to_x(*week) + 3.5, activity.encounters as f64]).collect();
+
+                let plot = Plot::new("player_tracker_encounters_per_week")
+                    .legend(Legend::default())
+                    .allow_scroll(false)
+                    .include_y(0.0)
+                    .x_axis_formatter(|mark, _range| x_to_date(mark.value).map(|date| date.format("%Y-%m-%d").to_string()).unwrap_or_default())
+                    .label_formatter(|name, value| {
+                        let week = x_to_date(value.x).map(week_start).map(|date| date.format("%Y-%m-%d").to_string()).unwrap_or_default();
+                        if name.is_empty() {
+                            format!("Week of {}", week)
+                        } else {
+                            format!("{}\nWeek of {}: {:.0}", name, week, value.y)
+                        }
+                    })
+                    .show(ui, |plot_ui| {
+                        plot_ui.bar_chart(BarChart::new(battles).name("Battles"));
+                        plot_ui.line(Line::new(encounters).name("Tracked Players Encountered"));
+                    });
+
+                if let Some((rect, _path, _started)) = self.pending_export.as_mut() {
+                    if *rect == egui::Rect::NOTHING {
+                        *rect = plot.response.rect;
+                        ui.ctx().send_viewport_cmd(egui::ViewportCommand::Screenshot);
+                    }
+                }
+            });
+        self.open = open;
+        if !open {
+            // The screenshot would be of whatever is there instead of the chart
+            self.pending_export = None;
+        }
+    }
+}
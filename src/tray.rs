@@ -0,0 +1,125 @@
+use std::sync::mpsc;
+
+/// Something picked from the tray icon's menu
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrayAction {
+    ShowWindow,
+    OpenReplayFile,
+    Search,
+    Quit,
+}
+
+/// Icon in the system tray which the window can be hidden to. New replays are still parsed in the
+/// background while the window is hidden.
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+pub struct Tray {
+    #[cfg(target_os = "windows")]
+    _icon: tray_icon::TrayIcon,
+    actions: mpsc::Receiver<TrayAction>,
+}
+
+impl Tray {
+    /// Adds the icon to the tray. Must be called from the thread running the event loop.
+    #[cfg(target_os = "windows")]
+    pub fn new(ctx: &egui::Context, frame: &eframe::Frame) -> Option<Self> {
+        use raw_window_handle::{HasWindowHandle, RawWindowHandle};
+        use tracing::error;
+        use tray_icon::{
+            menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem},
+            Icon, MouseButton, TrayIconBuilder, TrayIconEvent,
+        };
+
+        let hwnd = match frame.window_handle().map(|handle| handle.as_raw()) {
+            Ok(RawWindowHandle::Win32(handle)) => handle.hwnd.get(),
+            _ => {
+                error!("no window handle to restore the window from the tray with");
+                return None;
+            }
+        };
+
+        let icon_data = eframe::icon_data::from_png_bytes(include_bytes!("../assets/wows_toolkit.png")).ok()?;
+        let icon = Icon::from_rgba(icon_data.rgba, icon_data.width, icon_data.height).ok()?;
+
+        let show = MenuItem::new(format!("Show {}", crate::APP_NAME), true, None);
+        let open_replay_file = MenuItem::new("Open Replay File...", true, None);
+        let search = MenuItem::new("Search...", true, None);
+        let quit = MenuItem::new("Quit", true, None);
+        let menu = Menu::new();
+        if let Err(e) = menu.append_items(&[&show, &open_replay_file, &search, &PredefinedMenuItem::separator(), &quit]) {
+            error!("failed to build tray menu: {:?}", e);
+            return None;
+        }
+        let item_actions = [
+            (show.id().clone(), TrayAction::ShowWindow),
+            (open_replay_file.id().clone(), TrayAction::OpenReplayFile),
+            (search.id().clone(), TrayAction::Search),
+            (quit.id().clone(), TrayAction::Quit),
+        ];
+
+        // Events can arrive while the window is hidden, so the handlers bring it back rather than waiting for
+        // `update` to poll for them. Hidden windows aren't redrawn, so `update` wouldn't run until they did.
+        let (tx, rx) = mpsc::channel();
+        let menu_tx = tx.clone();
+        let menu_ctx = ctx.clone();
+        MenuEvent::set_event_handler(Some(move |event: MenuEvent| {
+            if let Some((_, action)) = item_actions.iter().find(|(id, _)| *id == event.id) {
+                let _ = menu_tx.send(*action);
+                if *action != TrayAction::Quit {
+                    show_window(&menu_ctx, hwnd);
+                }
+                menu_ctx.request_repaint();
+            }
+        }));
+        let icon_ctx = ctx.clone();
+        TrayIconEvent::set_event_handler(Some(move |event: TrayIconEvent| {
+            if let TrayIconEvent::DoubleClick { button: MouseButton::Left, .. } = event {
+                let _ = tx.send(TrayAction::ShowWindow);
+                show_window(&icon_ctx, hwnd);
+                icon_ctx.request_repaint();
+            }
+        }));
+
+        match TrayIconBuilder::new().with_menu(Box::new(menu)).with_tooltip(crate::APP_NAME).with_icon(icon).build() {
+            Ok(icon) => Some(Self { _icon: icon, actions: rx }),
+            Err(e) => {
+                error!("failed to add tray icon: {:?}", e);
+                None
+            }
+        }
+    }
+
+    /// Tray icons need a GTK event loop on Linux and an app bundle on macOS, neither of which the app has
+    #[cfg(not(target_os = "windows"))]
+    pub fn new(_ctx: &egui::Context, _frame: &eframe::Frame) -> Option<Self> {
+        None
+    }
+
+    pub fn try_recv(&self) -> Option<TrayAction> {
+        self.actions.try_recv().ok()
+    }
+}
+
+/// Shows the window with the Win32 API, then sends the same commands to the viewport so its state matches
+#[cfg(target_os = "windows")]
+fn show_window(ctx: &egui::Context, hwnd: isize) {
+    use windows_sys::Win32::UI::WindowsAndMessaging::{IsIconic, SetForegroundWindow, ShowWindow, SW_RESTORE, SW_SHOW};
+
+    let hwnd = hwnd as windows_sys::Win32::Foundation::HWND;
+    // SAFETY: the handle is for the app's main window, which outlives the tray icon
+    unsafe {
+        ShowWindow(hwnd, SW_SHOW);
+        if IsIconic(hwnd) != 0 {
+            ShowWindow(hwnd, SW_RESTORE);
+        }
+        SetForegroundWindow(hwnd);
+    }
+
+    ctx.send_viewport_cmd(egui::ViewportCommand::Visible(true));
+    ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(false));
+    ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+}
+
+pub fn hide_window(ctx: &egui::Context) {
+    ctx.send_viewport_cmd(egui::ViewportCommand::Visible(false));
+}
@@ -0,0 +1,248 @@
+use std::{
+    collections::HashMap,
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use parking_lot::RwLock;
+use tracing::debug;
+use wows_replays::ReplayFile;
+
+use crate::{
+    app::{TabState, TimedMessage},
+    icons,
+    player_tracker::TrackerSnapshot,
+    replay_parser::Replay,
+};
+
+/// How many actions can be undone
+const MAX_UNDO_HISTORY: usize = 50;
+
+/// Trashed replays are prefixed with the time they were deleted in this format
+pub const TRASH_DATE_FORMAT: &str = "%Y%m%d%H%M%S";
+
+/// Holding area for deleted replays so deleting them can be undone
+pub fn trash_dir() -> PathBuf {
+    let trash_dir = Path::new("trash");
+    if let Some(storage_dir) = eframe::storage_dir(crate::APP_NAME) {
+        storage_dir.join(trash_dir)
+    } else {
+        trash_dir.to_path_buf()
+    }
+}
+
+/// A destructive operation which can be undone
+pub enum Operation {
+    ClearTracker,
+    TrashReplay(PathBuf),
+    TrashReplays(Vec<PathBuf>),
+}
+
+impl Operation {
+    fn description(&self) -> String {
+        match self {
+            Operation::ClearTracker => "Clear Player Tracker".to_string(),
+            Operation::TrashReplay(path) => format!("Delete {}", file_name(path)),
+            Operation::TrashReplays(paths) => format!("Delete {} Replays", paths.len()),
+        }
+    }
+}
+
+/// Everything needed to undo an operation which has been performed
+pub enum Undo {
+    /// Holds the tracker contents from before it was cleared
+    ClearTracker(TrackerSnapshot),
+    TrashReplay {
+        original: PathBuf,
+        trashed: PathBuf,
+    },
+    /// Original and trashed paths of each replay
+    TrashReplays(Vec<(PathBuf, PathBuf)>),
+}
+
+impl Undo {
+    fn description(&self) -> String {
+        match self {
+            Undo::ClearTracker(_) => "Clear Player Tracker".to_string(),
+            Undo::TrashReplay { original, .. } => format!("Delete {}", file_name(original)),
+            Undo::TrashReplays(replays) => format!("Delete {} Replays", replays.len()),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct UndoStack {
+    done: Vec<Undo>,
+    undone: Vec<Operation>,
+}
+
+impl UndoStack {
+    /// Records an operation which was performed outside of [`TabState::perform`]
+    pub fn push(&mut self, undo: Undo) {
+        self.push_done(undo);
+        self.undone.clear();
+    }
+
+    fn push_done(&mut self, undo: Undo) {
+        if self.done.len() == MAX_UNDO_HISTORY {
+            self.done.remove(0);
+        }
+        self.done.push(undo);
+    }
+
+    /// Drops undo entries for trashed replays which have since been deleted for good, since they can't be
+    /// restored anymore
+    pub fn forget_trashed(&mut self, deleted: &[PathBuf]) {
+        self.done.retain_mut(|undo| match undo {
+            Undo::ClearTracker(_) => true,
+            Undo::TrashReplay { trashed, .. } => !deleted.contains(trashed),
+            Undo::TrashReplays(replays) => {
+                replays.retain(|(_, trashed)| !deleted.contains(trashed));
+                !replays.is_empty()
+            }
+        });
+    }
+
+    pub fn undo_description(&self) -> Option<String> {
+        self.done.last().map(Undo::description)
+    }
+
+    pub fn redo_description(&self) -> Option<String> {
+        self.undone.last().map(Operation::description)
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default()
+}
+
+/// Renames `from` to `to`, falling back to a copy when they're on different drives
+fn move_file(from: &Path, to: &Path) -> io::Result<()> {
+    if let Some(parent) = to.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    if std::fs::rename(from, to).is_err() {
+        std::fs::copy(from, to)?;
+        std::fs::remove_file(from)?;
+    }
+
+    Ok(())
+}
+
+impl TabState {
+    pub fn perform(&mut self, operation: Operation) {
+        match self.apply(operation) {
+            Ok(undo) => self.undo_stack.push(undo),
+            Err(e) => self.show_undo_error(e),
+        }
+    }
+
+    pub fn undo(&mut self) {
+        let Some(undo) = self.undo_stack.done.pop() else {
+            return;
+        };
+
+        match self.revert(undo) {
+            Ok(operation) => self.undo_stack.undone.push(operation),
+            Err(e) => self.show_undo_error(e),
+        }
+    }
+
+    pub fn redo(&mut self) {
+        let Some(operation) = self.undo_stack.undone.pop() else {
+            return;
+        };
+
+        match self.apply(operation) {
+            Ok(undo) => self.undo_stack.push_done(undo),
+            Err(e) => self.show_undo_error(e),
+        }
+    }
+
+    fn apply(&mut self, operation: Operation) -> io::Result<Undo> {
+        match operation {
+            Operation::ClearTracker => Ok(Undo::ClearTracker(self.settings.player_tracker.write().take_tracked_players())),
+            Operation::TrashReplay(original) => {
+                let trashed = self.trash_replay(&original)?;
+                Ok(Undo::TrashReplay { original, trashed })
+            }
+            Operation::TrashReplays(originals) => {
+                // Replays which can't be moved are left in place rather than losing track of the ones which were
+                let trashed = originals
+                    .into_iter()
+                    .filter_map(|original| match self.trash_replay(&original) {
+                        Ok(trashed) => Some((original, trashed)),
+                        Err(e) => {
+                            debug!("failed to trash {:?}: {:?}", original, e);
+                            None
+                        }
+                    })
+                    .collect();
+                Ok(Undo::TrashReplays(trashed))
+            }
+        }
+    }
+
+    /// Moves a replay to the trash, returning where it was moved to
+    fn trash_replay(&mut self, original: &Path) -> io::Result<PathBuf> {
+        let trashed = trash_dir().join(format!("{}_{}", chrono::Local::now().format(TRASH_DATE_FORMAT), file_name(original)));
+        debug!("moving {:?} to {:?}", original, trashed);
+        move_file(original, &trashed)?;
+
+        let removed = self.replay_files.as_mut().and_then(|replay_files| replay_files.remove(original));
+        self.replay_listing = None;
+        // The replay viewer shouldn't keep showing a replay which is no longer in the listing
+        if let (Some(removed), Some(current_replay)) = (removed, self.current_replay.as_ref()) {
+            if Arc::ptr_eq(&removed, current_replay) {
+                self.current_replay = None;
+            }
+        }
+
+        Ok(trashed)
+    }
+
+    fn restore_replay(&mut self, original: &Path, trashed: &Path) -> io::Result<()> {
+        move_file(trashed, original)?;
+        // The replay watcher picks the file back up, but not every replays directory is watched
+        let game_metadata = self.world_of_warships_data.as_ref().and_then(|wows_data| wows_data.read().game_metadata.clone());
+        if let (Some(game_metadata), Ok(replay_file)) = (game_metadata, ReplayFile::from_file(original)) {
+            let replay = Arc::new(RwLock::new(Replay::new(replay_file, game_metadata)));
+            self.replay_files.get_or_insert_with(HashMap::new).insert(original.to_path_buf(), replay);
+            self.replay_listing = None;
+        }
+
+        Ok(())
+    }
+
+    fn revert(&mut self, undo: Undo) -> io::Result<Operation> {
+        match undo {
+            Undo::ClearTracker(snapshot) => {
+                self.settings.player_tracker.write().restore_tracked_players(snapshot);
+                Ok(Operation::ClearTracker)
+            }
+            Undo::TrashReplay { original, trashed } => {
+                self.restore_replay(&original, &trashed)?;
+                Ok(Operation::TrashReplay(original))
+            }
+            Undo::TrashReplays(replays) => {
+                let originals = replays
+                    .into_iter()
+                    .filter_map(|(original, trashed)| match self.restore_replay(&original, &trashed) {
+                        Ok(()) => Some(original),
+                        Err(e) => {
+                            debug!("failed to restore {:?}: {:?}", trashed, e);
+                            None
+                        }
+                    })
+                    .collect();
+                Ok(Operation::TrashReplays(originals))
+            }
+        }
+    }
+
+    fn show_undo_error(&self, e: io::Error) {
+        *self.timed_message.write() = Some(TimedMessage::new(format!("{} {}", icons::WARNING, e)));
+    }
+}
@@ -0,0 +1,206 @@
+use std::{
+    collections::BTreeMap,
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant},
+};
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, error};
+
+use crate::{app::ToolkitTabViewer, connectivity::Connectivity};
+
+/// How often collected usage statistics are sent
+const SUBMIT_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// Counters since the last submission. Kept globally so background threads can record into it.
+static STATS: Mutex<UsageStats> = Mutex::new(UsageStats::new());
+
+static LAST_SUBMITTED: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// Where to send whatever is left when the app exits or panics. `None` while statistics can't be sent.
+static ENDPOINT: Mutex<Option<String>> = Mutex::new(None);
+
+/// Mirrors [UsageStatsSettings::enabled] so nothing is recorded while statistics are off
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Opt-in usage statistics. Nothing is collected or sent unless enabled.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UsageStatsSettings {
+    pub enabled: bool,
+    /// URL the statistics are POSTed to as JSON
+    pub endpoint: String,
+}
+
+#[derive(Debug, Default, Clone, Copy, Serialize)]
+struct ParseCounts {
+    parsed: u32,
+    failed: u32,
+}
+
+/// Anonymous counters. Contains no player names, replay contents or file paths.
+#[derive(Debug, Serialize)]
+struct UsageStats {
+    /// How often each feature was used
+    features: BTreeMap<&'static str, u32>,
+    /// Replay parse results by the build the replay was recorded on
+    replay_parses: BTreeMap<String, ParseCounts>,
+    /// Panics, counted as they happen
+    panics: u32,
+}
+
+impl UsageStats {
+    const fn new() -> Self {
+        Self {
+            features: BTreeMap::new(),
+            replay_parses: BTreeMap::new(),
+            panics: 0,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.features.is_empty() && self.replay_parses.is_empty() && self.panics == 0
+    }
+}
+
+#[derive(Serialize)]
+struct UsageReport<'a> {
+    app_version: &'static str,
+    os: &'static str,
+    arch: &'static str,
+    #[serde(flatten)]
+    stats: &'a UsageStats,
+}
+
+impl<'a> UsageReport<'a> {
+    fn new(stats: &'a UsageStats) -> Self {
+        Self {
+            app_version: env!("CARGO_PKG_VERSION"),
+            os: std::env::consts::OS,
+            arch: std::env::consts::ARCH,
+            stats,
+        }
+    }
+}
+
+pub fn record_feature(name: &'static str) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    *STATS.lock().features.entry(name).or_default() += 1;
+}
+
+pub fn record_replay_parse(build: String, succeeded: bool) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    let mut stats = STATS.lock();
+    let counts = stats.replay_parses.entry(build).or_default();
+    if succeeded {
+        counts.parsed += 1;
+    } else {
+        counts.failed += 1;
+    }
+}
+
+/// Counts the panic and sends the statistics right away, as the app may be about to go down
+pub fn record_panic() {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    // The panic may have happened while the statistics were locked
+    if let Some(mut stats) = STATS.try_lock() {
+        stats.panics += 1;
+    }
+    flush();
+}
+
+/// Sends the statistics which haven't been sent yet, waiting until the request is done. Used when the app exits so
+/// that sessions shorter than [SUBMIT_INTERVAL] still get reported.
+pub fn flush() {
+    let Some(endpoint) = ENDPOINT.try_lock().and_then(|endpoint| endpoint.clone()) else {
+        return;
+    };
+    let Some(stats) = STATS.try_lock().map(|mut stats| std::mem::replace(&mut *stats, UsageStats::new())) else {
+        return;
+    };
+    if stats.is_empty() {
+        return;
+    }
+
+    // On a fresh thread, as the blocking client can't be used from within an async runtime
+    let _ = std::thread::spawn(move || submit(&endpoint, &stats)).join();
+}
+
+fn submit(endpoint: &str, stats: &UsageStats) {
+    let result = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .user_agent(crate::APP_NAME)
+        .build()
+        .and_then(|client| client.post(endpoint).json(&UsageReport::new(stats)).send())
+        .and_then(|res| res.error_for_status());
+    match result {
+        Ok(_) => debug!("submitted usage statistics"),
+        Err(e) => error!("failed to submit usage statistics to {}: {:?}", endpoint, e),
+    }
+}
+
+/// Called every frame. Applies the settings and sends the statistics collected so far on a background
+/// thread once it's been long enough since the last time.
+pub fn tick(settings: &UsageStatsSettings, connectivity: &Connectivity) {
+    if ENABLED.swap(settings.enabled, Ordering::Relaxed) && !settings.enabled {
+        // Don't hold on to anything collected before statistics were turned off
+        *STATS.lock() = UsageStats::new();
+    }
+
+    let endpoint = settings.endpoint.trim();
+    if !settings.enabled || endpoint.is_empty() || connectivity.is_offline() {
+        *ENDPOINT.lock() = None;
+        return;
+    }
+    {
+        let mut saved_endpoint = ENDPOINT.lock();
+        if saved_endpoint.as_deref() != Some(endpoint) {
+            *saved_endpoint = Some(endpoint.to_string());
+        }
+    }
+
+    {
+        let mut last_submitted = LAST_SUBMITTED.lock();
+        let started = *last_submitted.get_or_insert_with(Instant::now);
+        if started.elapsed() < SUBMIT_INTERVAL {
+            return;
+        }
+        *last_submitted = Some(Instant::now());
+    }
+
+    let stats = std::mem::replace(&mut *STATS.lock(), UsageStats::new());
+    if stats.is_empty() {
+        return;
+    }
+
+    let endpoint = endpoint.to_string();
+    let _join_handle = std::thread::spawn(move || submit(&endpoint, &stats));
+}
+
+impl ToolkitTabViewer<'_> {
+    pub fn build_usage_stats_settings(&mut self, ui: &mut egui::Ui) {
+        let settings = &mut self.tab_state.settings.usage_stats;
+        ui.label(
+            "Helps decide what to work on next by sending which features are used, how many replays fail to parse for each game version, \
+             and how often the app crashes. No player names, replay contents or file paths are sent. Off unless turned on here.",
+        );
+        ui.checkbox(&mut settings.enabled, "Send Usage Statistics");
+        ui.horizontal(|ui| {
+            let label = ui.label("Endpoint");
+            ui.add_enabled(settings.enabled, egui::TextEdit::singleline(&mut settings.endpoint).hint_text("https://"))
+                .labelled_by(label.id);
+        });
+        ui.collapsing("What Will Be Sent", |ui| {
+            let stats = STATS.lock();
+            let report = serde_json::to_string_pretty(&UsageReport::new(&stats)).unwrap_or_default();
+            ui.label(egui::RichText::new(report).monospace());
+        });
+    }
+}
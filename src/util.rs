@@ -6,7 +6,12 @@ use std::{io::Write, path::Path, process::Command};
 use thousands::Separable;
 use tracing::debug;
 use wows_replays::analyzer::battle_controller::VehicleEntity;
-use wowsunpack::game_params::{provider::GameMetadataProvider, types::GameParamProvider};
+use wowsunpack::game_params::{
+    provider::GameMetadataProvider,
+    types::{GameParamProvider, Param, ParamData},
+};
+
+use crate::region::Region;
 
 pub fn separate_number<T: Separable>(num: T, locale: Option<&str>) -> String {
     let language: LanguageTag = locale.and_then(|locale| locale.parse().ok()).unwrap_or_else(|| LanguageTag::parse("en-US").unwrap());
@@ -17,34 +22,20 @@ pub fn separate_number<T: Separable>(num: T, locale: Option<&str>) -> String {
     }
 }
 
-pub fn player_color_for_team_relation(relation: u32, is_dark_mode: bool) -> Color32 {
-    match relation {
-        0 => Color32::GOLD,
-        1 => {
-            if is_dark_mode {
-                Color32::LIGHT_GREEN
-            } else {
-                Color32::DARK_GREEN
-            }
-        }
-        _ => {
-            if is_dark_mode {
-                Color32::LIGHT_RED
-            } else {
-                Color32::DARK_RED
-            }
-        }
+/// Returns the tier of a ship, or `None` if the param is not a vehicle
+pub fn ship_tier(param: &Param) -> Option<u32> {
+    match param.data() {
+        ParamData::Vehicle(vehicle) => Some(vehicle.level()),
+        _ => None,
     }
 }
 
-pub fn build_tomato_gg_url(entity: &VehicleEntity) -> Option<String> {
-    let player = entity.player()?;
-    Some(format!("https://tomato.gg/wows/stats/{}/{}-{}", player.realm(), player.name(), player.db_id()))
+pub fn build_tomato_gg_url(region: Region, db_id: i64, name: &str) -> String {
+    format!("https://tomato.gg/wows/stats/{}/{}-{}", region.realm(), name, db_id)
 }
 
-pub fn build_wows_numbers_url(entity: &VehicleEntity) -> Option<String> {
-    let player = entity.player()?;
-    Some(format!("https://{}.wows-numbers.com/player/{},{}", player.realm(), player.db_id(), player.name()))
+pub fn build_wows_numbers_url(region: Region, db_id: i64, name: &str) -> String {
+    format!("https://{}.wows-numbers.com/player/{},{}", region.realm(), db_id, name)
 }
 
 pub fn build_ship_config_url(entity: &VehicleEntity, metadata_provider: &GameMetadataProvider) -> String {
@@ -202,3 +193,21 @@ pub fn open_file_explorer(path: &Path) {
         Command::new("explorer.exe").arg("/select,").arg(path).spawn().unwrap();
     }
 }
+
+/// Replaces path separators and characters Windows doesn't allow in file names, so `name` can be used
+/// as a single file name on any platform
+pub fn sanitize_file_name(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| {
+            if c.is_control() || matches!(c, '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*') {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect();
+
+    // Windows also drops trailing dots and spaces
+    name.trim_end_matches(['.', ' ']).to_string()
+}
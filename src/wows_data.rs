@@ -1,7 +1,7 @@
 use std::{
     collections::HashMap,
     path::{Path, PathBuf},
-    sync::{mpsc, Arc},
+    sync::Arc,
 };
 
 use parking_lot::RwLock;
@@ -15,6 +15,7 @@ use crate::{
     build_tracker,
     replay_parser::Replay,
     task::{BackgroundTask, BackgroundTaskCompletion, BackgroundTaskKind},
+    usage_stats,
 };
 
 pub struct ShipIcon {
@@ -37,6 +38,9 @@ pub struct WorldOfWarshipsData {
     pub game_version: usize,
 
     pub replays_dir: PathBuf,
+
+    /// Locales which have game text translations in the current build
+    pub available_locales: Vec<String>,
 }
 
 impl WorldOfWarshipsData {
@@ -52,7 +56,8 @@ impl WorldOfWarshipsData {
             return None;
         }
 
-        let replay_file: ReplayFile = ReplayFile::from_decrypted_parts(meta_data.unwrap(), replay_data.unwrap()).unwrap();
+        // The replay may have only just been created when a battle is loading
+        let replay_file: ReplayFile = ReplayFile::from_decrypted_parts(meta_data.unwrap(), replay_data.unwrap()).ok()?;
         let game_metadata = self.game_metadata.clone()?;
         let replay = Replay::new(replay_file, game_metadata);
 
@@ -73,12 +78,12 @@ impl WorldOfWarshipsData {
     pub fn load_replay(&self, replay: Arc<RwLock<Replay>>) -> Option<BackgroundTask> {
         let game_version = self.game_version;
 
-        let (tx, rx) = mpsc::channel();
-
         let metadata_provider = self.game_metadata.as_ref().unwrap().clone();
-        let _join_handle = std::thread::spawn(move || {
-            let res = { replay.read().parse(game_version.to_string().as_str()) };
-            let res = res.map(move |report| {
+        Some(BackgroundTask::spawn(BackgroundTaskKind::LoadingReplay, move || {
+            let build = replay.read().build().unwrap_or("unknown").to_string();
+            let res = { replay.read().parse_approximate(game_version.to_string().as_str()) };
+            usage_stats::record_replay_parse(build, res.is_ok());
+            res.map(move |report| {
                 // // Send the replay builds to the remote server
                 // for player in report.player_entities() {
                 //     let client = reqwest::blocking::Client::new();
@@ -99,14 +104,7 @@ impl WorldOfWarshipsData {
                     replay_guard.assign_divs();
                 }
                 BackgroundTaskCompletion::ReplayLoaded { replay }
-            });
-
-            let _ = tx.send(res);
-        });
-
-        Some(BackgroundTask {
-            receiver: rx,
-            kind: BackgroundTaskKind::LoadingReplay,
-        })
+            })
+        }))
     }
 }